@@ -1,19 +1,24 @@
 use crate::{
-    domain::model::aggregated_by::{
-        ChargeAndDailyRecords, ChargeAndTotalPeriodRecords, TaskAndDailyRecords,
-        TaskAndTotalPeriodRecords,
+    domain::model::{
+        aggregated_by::{
+            AllAggregatedRecords, ChargeAndDailyRecords, ChargeAndTotalPeriodRecords,
+            LocationLevel, LocationRecords, PivotTable, TaskAndDailyRecords,
+            TaskAndTotalPeriodRecords, WorkingDayCheckRecords,
+        },
+        clickup::ChargedTimeEntry,
     },
-    usecase::Usecases,
+    usecase::{AggregateQuery, Usecases},
 };
 
 use super::dto::RequestDto;
+use anyhow::Result;
 
 #[derive(Debug, Clone)]
 pub(crate) struct Controller<U> {
     usecases: U,
 }
 
-impl<'a, U: Usecases> Controller<U> {
+impl<U: Usecases> Controller<U> {
     pub(crate) async fn new(usecases: U) -> Self {
         Self { usecases }
     }
@@ -21,41 +26,95 @@ impl<'a, U: Usecases> Controller<U> {
     pub(crate) async fn aggregate_by_task_and_total_period(
         &self,
         dto: RequestDto,
-    ) -> TaskAndTotalPeriodRecords {
+    ) -> Result<TaskAndTotalPeriodRecords> {
         self.usecases
             .aggregate_duration_use_case()
-            .by_task_and_total_period(dto.start_date().clone(), dto.end_date().clone(), dto.all())
+            .by_task_and_total_period(&AggregateQuery::from(dto))
             .await
-            .expect("Failed to process AggregateDurationUsecase: by_task_and_total_period")
     }
 
-    pub(crate) async fn aggregate_by_task_and_daily(&self, dto: RequestDto) -> TaskAndDailyRecords {
+    pub(crate) async fn aggregate_by_task_and_daily(
+        &self,
+        dto: RequestDto,
+    ) -> Result<TaskAndDailyRecords> {
         self.usecases
             .aggregate_duration_use_case()
-            .by_task_and_daily(dto.start_date().clone(), dto.end_date().clone(), dto.all())
+            .by_task_and_daily(&AggregateQuery::from(dto))
             .await
-            .expect("Failed to process AggregateDurationUsecase: by_task_and_daily")
     }
 
     pub(crate) async fn aggregate_by_charge_and_total_period(
         &self,
         dto: RequestDto,
-    ) -> ChargeAndTotalPeriodRecords {
+    ) -> Result<ChargeAndTotalPeriodRecords> {
         self.usecases
             .aggregate_duration_use_case()
-            .by_charge_and_total_period(dto.start_date().clone(), dto.end_date().clone(), dto.all())
+            .by_charge_and_total_period(&AggregateQuery::from(dto))
             .await
-            .expect("Failed to process AggregateDurationUsecase: by_charge_and_total_period")
     }
 
     pub(crate) async fn aggregate_by_charge_and_daily(
         &self,
         dto: RequestDto,
-    ) -> ChargeAndDailyRecords {
+    ) -> Result<ChargeAndDailyRecords> {
+        self.usecases
+            .aggregate_duration_use_case()
+            .by_charge_and_daily(&AggregateQuery::from(dto))
+            .await
+    }
+
+    pub(crate) async fn aggregate_by_location(
+        &self,
+        dto: RequestDto,
+        level: LocationLevel,
+    ) -> Result<LocationRecords> {
+        self.usecases
+            .aggregate_duration_use_case()
+            .by_location(&AggregateQuery::from(dto), level)
+            .await
+    }
+
+    pub(crate) async fn aggregate_by_charge_and_date_pivot(
+        &self,
+        dto: RequestDto,
+    ) -> Result<PivotTable> {
         self.usecases
             .aggregate_duration_use_case()
-            .by_charge_and_daily(dto.start_date().clone(), dto.end_date().clone(), dto.all())
+            .by_charge_and_date_pivot(&AggregateQuery::from(dto))
+            .await
+    }
+
+    pub(crate) async fn aggregate_by_task_and_date_pivot(
+        &self,
+        dto: RequestDto,
+    ) -> Result<PivotTable> {
+        self.usecases
+            .aggregate_duration_use_case()
+            .by_task_and_date_pivot(&AggregateQuery::from(dto))
+            .await
+    }
+
+    pub(crate) async fn aggregate_by_all(&self, dto: RequestDto) -> Result<AllAggregatedRecords> {
+        self.usecases
+            .aggregate_duration_use_case()
+            .by_all(&AggregateQuery::from(dto))
+            .await
+    }
+
+    pub(crate) async fn check_working_days(
+        &self,
+        dto: RequestDto,
+    ) -> Result<WorkingDayCheckRecords> {
+        self.usecases
+            .aggregate_duration_use_case()
+            .check_working_days(&AggregateQuery::from(dto))
+            .await
+    }
+
+    pub(crate) async fn list_time_entries(&self, dto: RequestDto) -> Result<Vec<ChargedTimeEntry>> {
+        self.usecases
+            .list_time_entries_use_case()
+            .by_date_range(&AggregateQuery::from(dto))
             .await
-            .expect("Failed to process AggregateDurationUsecase: by_charge_and_daily")
     }
 }
@@ -1,30 +1,119 @@
-use crate::cui::Opts;
+use crate::{
+    cui::Opts,
+    domain::model::{
+        BillingPeriod, Bucketing, ChargeRules, CompanyHolidays, DayBoundary, RoundingPolicy,
+        TaskDuration, WorkingCalendar,
+    },
+    error::AppError,
+    usecase::AggregateQuery,
+};
+use chrono::Duration;
+use std::path::Path;
 
 #[derive(Debug, Clone)]
 pub(crate) struct RequestDto {
     start_date: Option<String>,
     end_date: Option<String>,
+    day_boundary: DayBoundary,
+    period: Option<BillingPeriod>,
     all: bool,
+    split_entries: bool,
+    charge_rules: ChargeRules,
+    rounding: Option<RoundingPolicy>,
+    bucketing: Bucketing,
+    calendar: WorkingCalendar,
+    min_duration: TaskDuration,
 }
 
-impl RequestDto {
-    pub(crate) fn start_date(&self) -> &Option<String> {
-        &self.start_date
-    }
-    pub(crate) fn end_date(&self) -> &Option<String> {
-        &self.end_date
-    }
-    pub(crate) fn all(&self) -> bool {
-        self.all
+impl From<RequestDto> for AggregateQuery {
+    fn from(dto: RequestDto) -> Self {
+        Self {
+            start_date: dto.start_date,
+            end_date: dto.end_date,
+            day_boundary: dto.day_boundary,
+            period: dto.period,
+            all: dto.all,
+            split_entries: dto.split_entries,
+            charge_rules: dto.charge_rules,
+            rounding: dto.rounding,
+            bucketing: dto.bucketing,
+            calendar: dto.calendar,
+            min_duration: dto.min_duration,
+        }
     }
 }
 
-impl From<Opts> for RequestDto {
-    fn from(opts: Opts) -> Self {
-        Self {
+impl TryFrom<Opts> for RequestDto {
+    type Error = AppError;
+
+    fn try_from(opts: Opts) -> Result<Self, Self::Error> {
+        Ok(Self {
             start_date: opts.start_date().clone(),
             end_date: opts.end_date().clone(),
+            day_boundary: opts.day_boundary().in_zone(opts.time_zone()),
+            period: opts
+                .period()
+                .as_ref()
+                .map(|selection| BillingPeriod::find(selection, opts.period_definitions()))
+                .transpose()
+                .map_err(|e| AppError::Usage(e.to_string()))?,
             all: opts.all(),
-        }
+            split_entries: opts.split_entries(),
+            charge_rules: opts
+                .charge_rules()
+                .as_ref()
+                .map(|path| read_charge_rules(path))
+                .transpose()?
+                .unwrap_or_default()
+                .with_custom_field(opts.charge_field().clone()),
+            rounding: RoundingPolicy::new(
+                opts.rounding(),
+                opts.charge_rounding().to_vec(),
+                opts.rounding_scope(),
+            ),
+            bucketing: opts.bucketing(),
+            calendar: WorkingCalendar::new(
+                opts.holidays()
+                    .as_ref()
+                    .map(|path| read_company_holidays(path))
+                    .transpose()?
+                    .unwrap_or_default(),
+            ),
+            min_duration: Duration::seconds((opts.min_hours() * 3600.0).round() as i64).into(),
+        })
+    }
+}
+
+/// 会社の休日を記述したファイルを読み込む。拡張子が .ics の場合はiCalendar形式とする
+fn read_company_holidays(path: &Path) -> Result<CompanyHolidays, AppError> {
+    let config_error = |e: String| {
+        AppError::Config(format!(
+            "Fail to read holidays file '{}': {}",
+            path.display(),
+            e
+        ))
+    };
+    let text = std::fs::read_to_string(path).map_err(|e| config_error(e.to_string()))?;
+    let is_ics = path
+        .extension()
+        .is_some_and(|extension| extension.eq_ignore_ascii_case("ics"));
+    match is_ics {
+        true => CompanyHolidays::parse_ics(&text),
+        false => CompanyHolidays::parse_text(&text),
     }
+    .map_err(|e| config_error(e.to_string()))
+}
+
+/// チャージコードを振り分ける規則を記述したJSONファイルを読み込む
+fn read_charge_rules(path: &Path) -> Result<ChargeRules, AppError> {
+    std::fs::read_to_string(path)
+        .map_err(anyhow::Error::from)
+        .and_then(|text| ChargeRules::parse_json(&text))
+        .map_err(|e| {
+            AppError::Config(format!(
+                "Fail to read charge rules file '{}': {}",
+                path.display(),
+                e
+            ))
+        })
 }
@@ -1,4 +1,6 @@
 mod controller;
 pub(super) mod dto;
+pub(super) mod presenter;
 
 pub(super) use controller::Controller;
+pub(super) use presenter::Presenter;
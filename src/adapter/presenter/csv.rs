@@ -0,0 +1,298 @@
+use super::grouped_records;
+use crate::domain::model::{
+    aggregated_by::{AggregatedRecords, GroupedRow, PivotTable},
+    Notation,
+};
+
+/// Excelで日本語を文字化けさせずに開くためのバイトオーダーマーク
+const UTF8_BOM: &str = "\u{feff}";
+/// RFC 4180 では改行コードはCRLFと定められている
+const CRLF: &str = "\r\n";
+
+/// CSV出力時の設定
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct CsvOptions {
+    pub(crate) bom: bool,
+    pub(crate) header: bool,
+    pub(crate) grand_total: bool,
+}
+
+impl Default for CsvOptions {
+    fn default() -> Self {
+        Self {
+            bom: false,
+            header: true,
+            grand_total: true,
+        }
+    }
+}
+
+/// 集計結果をRFC 4180準拠のCSV文字列に変換する
+pub(super) fn render<R: AggregatedRecords>(
+    records: &R,
+    options: &CsvOptions,
+    subtotal: bool,
+    notation: &Notation,
+) -> String {
+    let columns = records.columns();
+    let mut lines = Vec::new();
+
+    if options.header {
+        // 列名は機械的に扱えるよう、言語に依らない名前とする
+        lines.push(to_line(
+            columns.iter().map(|column| column.name().to_string()),
+        ));
+    }
+    for row in grouped_records(records, subtotal) {
+        match row {
+            GroupedRow::Record(record) => lines.push(to_line(
+                columns
+                    .iter()
+                    .map(|column| column.format(&record, notation)),
+            )),
+            GroupedRow::Subtotal(subtotal) => {
+                lines.push(to_line(subtotal.line(&columns, notation).into_iter()))
+            }
+        }
+    }
+    if options.grand_total {
+        // 総合計は所要時間の列に揃えて出力する
+        lines.push(to_line(columns.iter().enumerate().map(|(i, column)| {
+            match records.column_total(column) {
+                Some(total) => notation.duration(&total),
+                None if i == 0 => notation.messages().grand_total.to_string(),
+                None => String::new(),
+            }
+        })));
+    }
+
+    to_csv(lines, options)
+}
+
+/// ピボット形式の集計結果をRFC 4180準拠のCSV文字列に変換する
+pub(super) fn render_pivot(
+    table: &PivotTable,
+    options: &CsvOptions,
+    notation: &Notation,
+) -> String {
+    let mut lines = Vec::new();
+
+    if options.header {
+        lines.push(to_line(table.column_names(notation).into_iter()));
+    }
+    for row in table.rows() {
+        lines.push(to_line(row.line(notation).into_iter()));
+    }
+    if options.grand_total {
+        lines.push(to_line(table.grand_total_line(notation).into_iter()));
+    }
+
+    to_csv(lines, options)
+}
+
+fn to_csv(lines: Vec<String>, options: &CsvOptions) -> String {
+    let bom = if options.bom { UTF8_BOM } else { "" };
+    lines.into_iter().fold(bom.to_string(), |mut csv, line| {
+        csv.push_str(&line);
+        csv.push_str(CRLF);
+        csv
+    })
+}
+
+fn to_line(fields: impl Iterator<Item = String>) -> String {
+    fields
+        .map(|field| escape(&field))
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+/// カンマ・ダブルクォート・改行を含む項目はダブルクォートで囲み、
+/// 項目内のダブルクォートは2つ重ねてエスケープする
+fn escape(field: &str) -> String {
+    if field.contains([',', '"', '\r', '\n']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::adapter::presenter::fixtures::{gen_task_record, gen_task_records};
+    use crate::domain::model::{
+        aggregated_by::{
+            ChargeAndDailyRecords, ChargeAndTotalPeriodRecords, PivotTable,
+            TaskAndTotalPeriodRecords,
+        },
+        DateRange, DayBoundary, DurationFormat, Locale, TaskRecords, WorkingCalendar,
+    };
+
+    #[test]
+    fn 特殊文字を含まない項目はそのまま出力される() {
+        assert_eq!(escape("task\ttab"), "task\ttab");
+        assert_eq!(escape("タスク"), "タスク");
+    }
+
+    #[test]
+    fn 特殊文字を含む項目はダブルクォートで囲まれる() {
+        assert_eq!(escape("a,b"), "\"a,b\"");
+        assert_eq!(escape("line\nbreak"), "\"line\nbreak\"");
+        assert_eq!(escape("say \"hi\""), "\"say \"\"hi\"\"\"");
+    }
+
+    #[test]
+    fn ヘッダと総合計を含むcsvが出力される() {
+        let date_range = DateRange::new(
+            Some("2023/04/03"),
+            Some("2023/04/04"),
+            DayBoundary::default(),
+        )
+        .unwrap();
+        let records = ChargeAndTotalPeriodRecords::new(date_range, gen_task_records());
+        let csv = render(
+            &records,
+            &CsvOptions::default(),
+            false,
+            &Notation::default(),
+        );
+
+        assert_eq!(
+            csv,
+            [
+                "updated_at,total_duration,charge_name",
+                "2023/04/04 12:00:00,01:15:00,charge-1",
+                "2023/04/04 12:00:00,01:30:00,charge-2",
+                "総合計,02:45:00,",
+                "",
+            ]
+            .join(CRLF)
+        );
+    }
+
+    #[test]
+    fn bomを付与しヘッダと総合計を省略できる() {
+        let date_range = DateRange::new(
+            Some("2023/04/03"),
+            Some("2023/04/04"),
+            DayBoundary::default(),
+        )
+        .unwrap();
+        let mut record = gen_task_record("a", "charge-1", 3, 30);
+        record.task_name = "a,\"b\"\nc".to_string();
+        let records = TaskAndTotalPeriodRecords::new(date_range, TaskRecords::new(vec![record]));
+        let options = CsvOptions {
+            bom: true,
+            header: false,
+            grand_total: false,
+        };
+        let csv = render(&records, &options, false, &Notation::default());
+
+        assert_eq!(
+            csv,
+            "\u{feff}2023/04/03 12:00:00,00:30:00,charge-1,in progress,\"a,\"\"b\"\"\nc\"\r\n"
+        );
+    }
+
+    #[test]
+    fn ピボット形式では所要時間の無い日付が空欄になる() {
+        let date_range = DateRange::new(
+            Some("2023/04/03"),
+            Some("2023/04/04"),
+            DayBoundary::default(),
+        )
+        .unwrap();
+        let table = PivotTable::by_charge(date_range, gen_task_records());
+        let csv = render_pivot(&table, &CsvOptions::default(), &Notation::default());
+
+        assert_eq!(
+            csv,
+            [
+                "charge_name,2023/04/03,2023/04/04,total_duration",
+                "charge-1,00:30:00,00:45:00,01:15:00",
+                "charge-2,,01:30:00,01:30:00",
+                "総合計,00:30:00,02:15:00,02:45:00",
+                "",
+            ]
+            .join(CRLF)
+        );
+    }
+
+    #[test]
+    fn 英語でもピボット形式の列名は変えずに日付を英語の表記にする() {
+        let date_range = DateRange::new(
+            Some("2023/04/03"),
+            Some("2023/04/04"),
+            DayBoundary::default(),
+        )
+        .unwrap();
+        let table = PivotTable::by_charge(date_range, gen_task_records());
+        let en = Notation::new(Locale::En, DurationFormat::default());
+        let csv = render_pivot(&table, &CsvOptions::default(), &en);
+
+        assert_eq!(
+            csv.split(CRLF).collect::<Vec<_>>(),
+            vec![
+                "charge_name,2023-04-03,2023-04-04,total_duration",
+                "charge-1,00:30:00,00:45:00,01:15:00",
+                "charge-2,,01:30:00,01:30:00",
+                "Grand total,00:30:00,02:15:00,02:45:00",
+                "",
+            ]
+        );
+    }
+
+    #[test]
+    fn 小計の行を挟める() {
+        let date_range = DateRange::new(
+            Some("2023/04/03"),
+            Some("2023/04/04"),
+            DayBoundary::default(),
+        )
+        .unwrap();
+        let records = ChargeAndDailyRecords::new(date_range, gen_task_records());
+        let csv = render(&records, &CsvOptions::default(), true, &Notation::default());
+
+        assert_eq!(
+            csv.split(CRLF).collect::<Vec<_>>(),
+            vec![
+                "target_date,updated_at,total_duration,charge_name",
+                "2023/04/03,2023/04/03 12:00:00,00:30:00,charge-1",
+                "2023/04/03,小計,00:30:00,",
+                "2023/04/04,2023/04/04 12:00:00,00:45:00,charge-1",
+                "2023/04/04,2023/04/04 12:00:00,01:30:00,charge-2",
+                "2023/04/04,小計,02:15:00,",
+                "総合計,,02:45:00,",
+                "",
+            ]
+        );
+    }
+
+    #[test]
+    fn 作業の無かった稼働日は対象日と所要時間0のみの行にして小計を挟まない() {
+        let date_range = DateRange::new(
+            Some("2023/04/03"),
+            Some("2023/04/05"),
+            DayBoundary::default(),
+        )
+        .unwrap();
+        let records = ChargeAndDailyRecords::new(date_range, gen_task_records())
+            .fill_working_days(&WorkingCalendar::default());
+        let csv = render(&records, &CsvOptions::default(), true, &Notation::default());
+
+        assert_eq!(
+            csv.split(CRLF).collect::<Vec<_>>(),
+            vec![
+                "target_date,updated_at,total_duration,charge_name",
+                "2023/04/03,2023/04/03 12:00:00,00:30:00,charge-1",
+                "2023/04/03,小計,00:30:00,",
+                "2023/04/04,2023/04/04 12:00:00,00:45:00,charge-1",
+                "2023/04/04,2023/04/04 12:00:00,01:30:00,charge-2",
+                "2023/04/04,小計,02:15:00,",
+                "2023/04/05,,00:00:00,",
+                "総合計,,02:45:00,",
+                "",
+            ]
+        );
+    }
+}
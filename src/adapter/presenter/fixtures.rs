@@ -0,0 +1,12 @@
+//! 出力形式毎のテストで共通して利用する集計元データ
+
+pub(super) use crate::domain::model::fixtures::gen_task_record;
+use crate::domain::model::TaskRecords;
+
+pub(super) fn gen_task_records() -> TaskRecords {
+    TaskRecords::new(vec![
+        gen_task_record("a", "charge-1", 3, 30),
+        gen_task_record("a", "charge-1", 4, 45),
+        gen_task_record("b", "charge-2", 4, 90),
+    ])
+}
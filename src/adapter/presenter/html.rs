@@ -0,0 +1,475 @@
+use std::collections::BTreeMap;
+use std::f64::consts::PI;
+
+use crate::domain::model::{
+    aggregated_by::{AggregatedRecord, AggregatedRecords, AllAggregatedRecords, Column},
+    Notation,
+};
+use chrono::NaiveDate;
+
+/// グラフの系列に割り当てる色（系列数が多い場合は先頭から繰り返す）
+const PALETTE: [&str; 10] = [
+    "#4e79a7", "#f28e2b", "#e15759", "#76b7b2", "#59a14f", "#edc948", "#b07aa1", "#ff9da7",
+    "#9c755f", "#bab0ac",
+];
+
+/// 表の見出しをクリックすると並び替えるスクリプト
+/// オフラインでも開けるよう、外部のライブラリは使わずに埋め込む
+const SORT_SCRIPT: &str = r#"
+document.querySelectorAll("table.sortable").forEach(function (table) {
+  table.querySelectorAll("thead th").forEach(function (th, index) {
+    th.addEventListener("click", function () {
+      var tbody = table.tBodies[0];
+      var asc = th.dataset.order !== "asc";
+      table.querySelectorAll("thead th").forEach(function (other) { delete other.dataset.order; });
+      th.dataset.order = asc ? "asc" : "desc";
+      var key = function (row) {
+        var cell = row.cells[index];
+        return cell.dataset.sort !== undefined ? Number(cell.dataset.sort) : cell.textContent;
+      };
+      Array.from(tbody.rows)
+        .sort(function (a, b) {
+          var x = key(a), y = key(b);
+          var result = typeof x === "number" ? x - y : String(x).localeCompare(String(y));
+          return asc ? result : -result;
+        })
+        .forEach(function (row) { tbody.appendChild(row); });
+    });
+  });
+});
+"#;
+
+const STYLE: &str = r#"
+body { font-family: sans-serif; margin: 2em; color: #333; }
+h1 { font-size: 1.5em; }
+h2 { font-size: 1.2em; margin-top: 2em; border-bottom: 1px solid #ccc; }
+table { border-collapse: collapse; margin: 1em 0; }
+th, td { border: 1px solid #ccc; padding: 4px 8px; }
+th { background: #f0f0f0; cursor: pointer; user-select: none; }
+th[data-order="asc"]::after { content: " \25B2"; }
+th[data-order="desc"]::after { content: " \25BC"; }
+td.duration { text-align: right; font-variant-numeric: tabular-nums; }
+tfoot td { font-weight: bold; }
+.charts { display: flex; flex-wrap: wrap; gap: 2em; }
+.legend span { display: inline-block; margin-right: 1em; }
+.legend i { display: inline-block; width: 10px; height: 10px; margin-right: 4px; }
+"#;
+
+/// 全ての集計単位の表と、チャージ毎のグラフを1つのHTMLにまとめる
+pub(super) fn render(records: &AllAggregatedRecords, notation: &Notation) -> String {
+    let messages = notation.messages();
+    let title = escape(&notation.title(records.date_range()));
+    let colors = charge_colors(records);
+
+    let body = [
+        format!("<h1>{}</h1>", title),
+        format!("<h2>{}</h2>", messages.charts),
+        format!(
+            "<div class=\"charts\">{}{}</div>",
+            stacked_bar_chart(records.charge_and_daily(), &colors, notation),
+            pie_chart(records.charge_and_total_period(), &colors, notation)
+        ),
+        legend(&colors),
+        section(
+            messages.task_and_total_period,
+            records.task_and_total_period(),
+            notation,
+        ),
+        section(messages.task_and_daily, records.task_and_daily(), notation),
+        section(
+            messages.charge_and_total_period,
+            records.charge_and_total_period(),
+            notation,
+        ),
+        section(
+            messages.charge_and_daily,
+            records.charge_and_daily(),
+            notation,
+        ),
+    ]
+    .join("\n");
+
+    format!(
+        "<!DOCTYPE html>\n<html lang=\"{}\">\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n<style>{}</style>\n</head>\n<body>\n{}\n<script>{}</script>\n</body>\n</html>\n",
+        messages.html_lang, title, STYLE, body, SORT_SCRIPT
+    )
+}
+
+/// チャージ名毎に色を割り当てる
+fn charge_colors(records: &AllAggregatedRecords) -> BTreeMap<String, &'static str> {
+    let mut charge_names = records
+        .charge_and_total_period()
+        .records()
+        .iter()
+        .filter_map(|record| record.charge_name().map(str::to_string))
+        .collect::<Vec<_>>();
+    charge_names.sort();
+    charge_names
+        .into_iter()
+        .enumerate()
+        .map(|(i, charge_name)| (charge_name, PALETTE[i % PALETTE.len()]))
+        .collect()
+}
+
+fn legend(colors: &BTreeMap<String, &'static str>) -> String {
+    let items = colors
+        .iter()
+        .map(|(charge_name, color)| {
+            format!(
+                "<span><i style=\"background:{}\"></i>{}</span>",
+                color,
+                escape(charge_name)
+            )
+        })
+        .collect::<String>();
+    format!("<div class=\"legend\">{}</div>", items)
+}
+
+/// 見出しと並び替え可能な表
+fn section<R: AggregatedRecords>(heading: &str, records: &R, notation: &Notation) -> String {
+    let columns = records.columns();
+    let thead = columns
+        .iter()
+        .map(|column| format!("<th>{}</th>", column.label(notation.messages())))
+        .collect::<String>();
+    let tbody = records
+        .records()
+        .iter()
+        .map(|record| {
+            let cells = columns
+                .iter()
+                .map(|column| cell(column, record, notation))
+                .collect::<String>();
+            format!("<tr>{}</tr>", cells)
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+    let tfoot = columns
+        .iter()
+        .enumerate()
+        .map(|(i, column)| match records.column_total(column) {
+            Some(total) => format!("<td class=\"duration\">{}</td>", notation.duration(&total)),
+            None if i == 0 => format!("<td>{}</td>", notation.messages().grand_total),
+            None => "<td></td>".to_string(),
+        })
+        .collect::<String>();
+
+    format!(
+        "<h2>{}</h2>\n<table class=\"sortable\">\n<thead><tr>{}</tr></thead>\n<tbody>\n{}\n</tbody>\n<tfoot><tr>{}</tr></tfoot>\n</table>",
+        heading, thead, tbody, tfoot
+    )
+}
+
+fn cell<R: AggregatedRecord>(column: &Column, record: &R, notation: &Notation) -> String {
+    let text = escape(&column.format(record, notation));
+    // 所要時間は秒数で並び替える
+    if let Some(duration) = column.duration(record) {
+        return format!(
+            "<td class=\"duration\" data-sort=\"{}\">{}</td>",
+            duration.num_seconds(),
+            text
+        );
+    }
+    match column {
+        Column::TaskName => match record.task_url() {
+            Some(url) if !url.is_empty() => {
+                format!("<td><a href=\"{}\">{}</a></td>", escape(url), text)
+            }
+            _ => format!("<td>{}</td>", text),
+        },
+        _ => format!("<td>{}</td>", text),
+    }
+}
+
+/// 日毎・チャージ毎の作業時間の積み上げ棒グラフ
+fn stacked_bar_chart<R: AggregatedRecords>(
+    records: &R,
+    colors: &BTreeMap<String, &'static str>,
+    notation: &Notation,
+) -> String {
+    let (width, height, margin) = (720.0, 320.0, 40.0);
+    let dates = records.date_range().target_dates();
+
+    // 日付毎に、チャージ名順で所要時間(時間)を積み上げる
+    // 作業の無かった稼働日の行は積み上げない
+    let mut hours_by_date = BTreeMap::<NaiveDate, BTreeMap<String, f64>>::new();
+    for record in records.records() {
+        if let (Some(date), Some(charge_name)) = (record.target_date(), record.charge_name()) {
+            *hours_by_date
+                .entry(date)
+                .or_default()
+                .entry(charge_name.to_string())
+                .or_default() += hours(record.total_duration().num_seconds());
+        }
+    }
+    let max_hours = hours_by_date
+        .values()
+        .map(|charges| charges.values().sum::<f64>())
+        .fold(1.0, f64::max)
+        .ceil();
+
+    let plot_width = width - margin * 2.0;
+    let plot_height = height - margin * 2.0;
+    let slot = plot_width / dates.len().max(1) as f64;
+    let bar_width = slot * 0.7;
+    let y = |hours: f64| margin + plot_height - plot_height * hours / max_hours;
+
+    let mut elements = Vec::new();
+    // 目盛り線
+    let step = (max_hours / 8.0).ceil().max(1.0);
+    let mut tick = 0.0;
+    while tick <= max_hours {
+        elements.push(format!(
+            "<line x1=\"{m}\" y1=\"{y:.1}\" x2=\"{x2}\" y2=\"{y:.1}\" stroke=\"#ddd\"/><text x=\"{tx}\" y=\"{ty:.1}\" font-size=\"10\" text-anchor=\"end\">{tick}h</text>",
+            m = margin,
+            x2 = width - margin,
+            y = y(tick),
+            tx = margin - 4.0,
+            ty = y(tick) + 3.0,
+            tick = tick
+        ));
+        tick += step;
+    }
+    // 棒
+    for (i, date) in dates.iter().enumerate() {
+        let x = margin + slot * i as f64 + (slot - bar_width) / 2.0;
+        let mut stacked = 0.0;
+        if let Some(charges) = hours_by_date.get(date) {
+            for (charge_name, hours) in charges {
+                elements.push(format!(
+                    "<rect x=\"{:.1}\" y=\"{:.1}\" width=\"{:.1}\" height=\"{:.1}\" fill=\"{}\"><title>{} {}: {:.2}h</title></rect>",
+                    x,
+                    y(stacked + hours),
+                    bar_width,
+                    y(stacked) - y(stacked + hours),
+                    colors.get(charge_name).unwrap_or(&PALETTE[0]),
+                    notation.date(date),
+                    escape(charge_name),
+                    hours
+                ));
+                stacked += hours;
+            }
+        }
+        elements.push(format!(
+            "<text x=\"{:.1}\" y=\"{:.1}\" font-size=\"10\" text-anchor=\"middle\">{}</text>",
+            x + bar_width / 2.0,
+            height - margin + 14.0,
+            date.format("%m/%d")
+        ));
+    }
+
+    format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w}\" height=\"{h}\" viewBox=\"0 0 {w} {h}\" role=\"img\"><title>{}</title>{}</svg>",
+        notation.messages().daily_chart,
+        elements.join(""),
+        w = width,
+        h = height
+    )
+}
+
+/// 対象期間におけるチャージ毎の作業時間の割合を表す円グラフ
+fn pie_chart<R: AggregatedRecords>(
+    records: &R,
+    colors: &BTreeMap<String, &'static str>,
+    notation: &Notation,
+) -> String {
+    let (size, radius) = (320.0, 140.0);
+    let center = size / 2.0;
+    let total = records.grand_total().num_seconds() as f64;
+
+    let mut elements = Vec::new();
+    let mut start_angle = -PI / 2.0;
+    for record in records.records() {
+        let seconds = record.total_duration().num_seconds() as f64;
+        let Some(charge_name) = record.charge_name() else {
+            continue;
+        };
+        if seconds <= 0.0 {
+            continue;
+        }
+        let color = colors.get(charge_name).unwrap_or(&PALETTE[0]);
+        let tooltip = format!(
+            "<title>{}: {:.1}%</title>",
+            escape(charge_name),
+            seconds / total * 100.0
+        );
+        if seconds >= total {
+            // 1件のみの場合は弧を描けないので円にする
+            elements.push(format!(
+                "<circle cx=\"{c}\" cy=\"{c}\" r=\"{r}\" fill=\"{}\">{}</circle>",
+                color,
+                tooltip,
+                c = center,
+                r = radius
+            ));
+            break;
+        }
+        let end_angle = start_angle + 2.0 * PI * seconds / total;
+        let large_arc = if end_angle - start_angle > PI { 1 } else { 0 };
+        elements.push(format!(
+            "<path d=\"M {c} {c} L {:.2} {:.2} A {r} {r} 0 {} 1 {:.2} {:.2} Z\" fill=\"{}\" stroke=\"#fff\">{}</path>",
+            center + radius * start_angle.cos(),
+            center + radius * start_angle.sin(),
+            large_arc,
+            center + radius * end_angle.cos(),
+            center + radius * end_angle.sin(),
+            color,
+            tooltip,
+            c = center,
+            r = radius
+        ));
+        start_angle = end_angle;
+    }
+
+    format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{s}\" height=\"{s}\" viewBox=\"0 0 {s} {s}\" role=\"img\"><title>{}</title>{}</svg>",
+        notation.messages().share_chart,
+        elements.join(""),
+        s = size
+    )
+}
+
+fn hours(seconds: i64) -> f64 {
+    seconds as f64 / 3600.0
+}
+
+/// HTMLとして解釈される文字をエスケープする
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&#39;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::adapter::presenter::fixtures::{gen_task_record, gen_task_records};
+    use crate::domain::model::{
+        DateRange, DayBoundary, DurationFormat, Locale, TaskRecords, WorkingCalendar,
+    };
+
+    #[test]
+    fn htmlとして解釈される文字はエスケープされる() {
+        assert_eq!(
+            escape("<b>\"A&B\"</b>"),
+            "&lt;b&gt;&quot;A&amp;B&quot;&lt;/b&gt;"
+        );
+    }
+
+    #[test]
+    fn 外部リソースに依存しない() {
+        let date_range = DateRange::new(
+            Some("2023/04/03"),
+            Some("2023/04/04"),
+            DayBoundary::default(),
+        )
+        .unwrap();
+        let html = render(
+            &AllAggregatedRecords::new(date_range, gen_task_records()),
+            &Notation::default(),
+        );
+
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert!(!html.contains("src=\"http"));
+        assert!(!html.contains("<link"));
+        assert_eq!(html.matches("<table class=\"sortable\">").count(), 4);
+        assert_eq!(html.matches("<svg").count(), 2);
+    }
+
+    #[test]
+    fn 英語では言語属性と見出しが英語になる() {
+        let date_range = DateRange::new(
+            Some("2023/04/03"),
+            Some("2023/04/04"),
+            DayBoundary::default(),
+        )
+        .unwrap();
+        let html = render(
+            &AllAggregatedRecords::new(date_range, gen_task_records()),
+            &Notation::new(Locale::En, DurationFormat::default()),
+        );
+
+        assert!(html.contains("<html lang=\"en\">"));
+        assert!(html.contains("<h1>Target period: 2023-04-03 to 2023-04-04 (Asia/Tokyo)</h1>"));
+        assert!(html.contains("<h2>By charge (daily)</h2>"));
+        assert!(html.contains("<th>Charge code</th>"));
+        assert!(html.contains("<td>Grand total</td>"));
+    }
+
+    #[test]
+    fn 積み上げ棒グラフは日毎チャージ毎に棒を描く() {
+        let date_range = DateRange::new(
+            Some("2023/04/03"),
+            Some("2023/04/05"),
+            DayBoundary::default(),
+        )
+        .unwrap();
+        let records = AllAggregatedRecords::new(date_range, gen_task_records());
+        let svg = stacked_bar_chart(
+            records.charge_and_daily(),
+            &charge_colors(&records),
+            &Notation::default(),
+        );
+
+        // 4/3: charge-1, 4/4: charge-1 + charge-2, 4/5: 記録なし
+        assert_eq!(svg.matches("<rect").count(), 3);
+        assert!(svg.contains(">04/05</text>"));
+    }
+
+    #[test]
+    fn 円グラフはチャージが1件なら円を描く() {
+        let date_range = DateRange::new(Some("2023/04/03"), None, DayBoundary::default()).unwrap();
+        let task_records = TaskRecords::new(vec![gen_task_record("a", "charge-1", 3, 30)]);
+        let records = AllAggregatedRecords::new(date_range, task_records);
+        let svg = pie_chart(
+            records.charge_and_total_period(),
+            &charge_colors(&records),
+            &Notation::default(),
+        );
+
+        assert_eq!(svg.matches("<circle").count(), 1);
+        assert_eq!(svg.matches("<path").count(), 0);
+    }
+
+    #[test]
+    fn 円グラフはチャージ毎に扇形を描く() {
+        let date_range = DateRange::new(
+            Some("2023/04/03"),
+            Some("2023/04/04"),
+            DayBoundary::default(),
+        )
+        .unwrap();
+        let records = AllAggregatedRecords::new(date_range, gen_task_records());
+        let svg = pie_chart(
+            records.charge_and_total_period(),
+            &charge_colors(&records),
+            &Notation::default(),
+        );
+
+        assert_eq!(svg.matches("<path").count(), 2);
+        assert!(svg.contains("<title>charge-2: 54.5%</title>"));
+    }
+
+    #[test]
+    fn 作業の無かった稼働日は表に対象日のみの行を出力し棒を描かない() {
+        let date_range = DateRange::new(
+            Some("2023/04/03"),
+            Some("2023/04/05"),
+            DayBoundary::default(),
+        )
+        .unwrap();
+        let records = AllAggregatedRecords::new(date_range, gen_task_records())
+            .fill_working_days(&WorkingCalendar::default());
+        let colors = charge_colors(&records);
+        let svg = stacked_bar_chart(records.charge_and_daily(), &colors, &Notation::default());
+        let table = section("", records.charge_and_daily(), &Notation::default());
+
+        assert_eq!(svg.matches("<rect").count(), 3);
+        assert!(table.contains(
+            "<tr><td>2023/04/05</td><td></td><td class=\"duration\" data-sort=\"0\">00:00:00</td><td></td></tr>"
+        ));
+        assert_eq!(colors.len(), 2);
+    }
+}
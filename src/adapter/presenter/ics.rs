@@ -0,0 +1,154 @@
+use crate::domain::model::clickup::ChargedTimeEntry;
+use chrono::{DateTime, TimeZone, Utc};
+
+/// RFC 5545 では改行コードはCRLFと定められている
+const CRLF: &str = "\r\n";
+/// 1行あたりの最大オクテット数（これを超える行は折り返す）
+const MAX_LINE_OCTETS: usize = 75;
+
+/// タイムエントリーを1件ずつVEVENTとしたiCalendar文字列に変換する
+/// 分類には、振り分けたチャージ名を用いる
+pub(super) fn render(time_entries: &[ChargedTimeEntry], now: DateTime<Utc>) -> String {
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        format!(
+            "PRODID:-//task-reporter//task-reporter {}//JA",
+            env!("CARGO_PKG_VERSION")
+        ),
+        "CALSCALE:GREGORIAN".to_string(),
+        "METHOD:PUBLISH".to_string(),
+    ];
+    for entry in time_entries {
+        // 計測中のタイムエントリーは終了日時が確定していないので出力しない
+        if entry.entry.end <= entry.entry.start {
+            continue;
+        }
+        lines.extend(vevent(entry, now));
+    }
+    lines.push("END:VCALENDAR".to_string());
+
+    lines.iter().fold(String::new(), |mut ics, line| {
+        ics.push_str(&fold(line));
+        ics.push_str(CRLF);
+        ics
+    })
+}
+
+fn vevent(charged: &ChargedTimeEntry, now: DateTime<Utc>) -> Vec<String> {
+    let entry = &charged.entry;
+    let mut lines = vec![
+        "BEGIN:VEVENT".to_string(),
+        format!("UID:{}", escape(&entry.id)),
+        format!("DTSTAMP:{}", utc(now)),
+        format!("DTSTART:{}", utc(entry.start)),
+        format!("DTEND:{}", utc(entry.end)),
+        format!("SUMMARY:{}", escape(&entry.task_name)),
+        format!("CATEGORIES:{}", escape(&charged.charge_name)),
+    ];
+    if !entry.task_url.is_empty() {
+        lines.push(format!("URL:{}", entry.task_url));
+    }
+    lines.push("END:VEVENT".to_string());
+    lines
+}
+
+/// 日時はUTCで出力する
+fn utc<Tz: TimeZone>(dt: DateTime<Tz>) -> String {
+    dt.with_timezone(&Utc).format("%Y%m%dT%H%M%SZ").to_string()
+}
+
+/// TEXT型の値として特別な意味を持つ文字をエスケープする
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace("\r\n", "\\n")
+        .replace(['\r', '\n'], "\\n")
+}
+
+/// 75オクテットを超える行を、マルチバイト文字の途中で分割しないように折り返す
+fn fold(line: &str) -> String {
+    let mut folded = String::with_capacity(line.len());
+    let mut octets = 0;
+    for c in line.chars() {
+        if octets + c.len_utf8() > MAX_LINE_OCTETS {
+            folded.push_str(CRLF);
+            folded.push(' ');
+            // 折り返し後の行頭の空白も1オクテットとして数える
+            octets = 1;
+        }
+        folded.push(c);
+        octets += c.len_utf8();
+    }
+    folded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::model::{clickup::ClickupTimeEntry, TaskLocation};
+    use chrono::Duration;
+
+    /// リスト名はlist-1とし、チャージ名はA社 保守に振り分けたタイムエントリー
+    fn gen_time_entry(id: &str, task_name: &str, start: i64, end: i64) -> ChargedTimeEntry {
+        let entry = ClickupTimeEntry::new(
+            id,
+            Duration::milliseconds(end - start),
+            Utc.timestamp_millis_opt(start).unwrap().fixed_offset(),
+            Utc.timestamp_millis_opt(end).unwrap().fixed_offset(),
+            "task-a",
+            task_name,
+            "https://app.clickup.com/t/task-a",
+            "in progress",
+            TaskLocation::new("space-1", "space", "folder-1", "folder", "list-1", "list-1"),
+            Vec::new(),
+        );
+        ChargedTimeEntry {
+            entry,
+            charge_name: "A社 保守".to_string(),
+        }
+    }
+
+    #[test]
+    fn 特殊文字はエスケープされる() {
+        assert_eq!(escape("a,b;c\\d\ne"), "a\\,b\\;c\\\\d\\ne");
+    }
+
+    #[test]
+    fn 長い行はマルチバイト文字の途中で分割されない() {
+        let line = format!("SUMMARY:{}", "あ".repeat(40));
+        let folded = fold(&line);
+
+        for (i, part) in folded.split(CRLF).enumerate() {
+            assert!(part.len() <= MAX_LINE_OCTETS);
+            assert_eq!(i > 0, part.starts_with(' '));
+        }
+        assert_eq!(folded.replace("\r\n ", ""), line);
+    }
+
+    #[test]
+    fn タイムエントリー毎にveventが出力される() {
+        // 2023/04/03 01:00:00 UTC ～ 2023/04/03 02:30:00 UTC
+        let start = 1680483600000;
+        let end = start + 90 * 60 * 1000;
+        let entries = vec![
+            gen_time_entry("1001", "設計, レビュー", start, end),
+            gen_time_entry("1002", "計測中", end, end - 1),
+        ];
+        let now = Utc.with_ymd_and_hms(2023, 4, 4, 0, 0, 0).unwrap();
+        let ics = render(&entries, now);
+
+        assert!(ics.starts_with("BEGIN:VCALENDAR\r\nVERSION:2.0\r\n"));
+        assert!(ics.ends_with("END:VCALENDAR\r\n"));
+        assert_eq!(ics.matches("BEGIN:VEVENT").count(), 1);
+        assert!(ics.contains("UID:1001\r\n"));
+        assert!(ics.contains("DTSTAMP:20230404T000000Z\r\n"));
+        assert!(ics.contains("DTSTART:20230403T010000Z\r\n"));
+        assert!(ics.contains("DTEND:20230403T023000Z\r\n"));
+        assert!(ics.contains("SUMMARY:設計\\, レビュー\r\n"));
+        // 分類はリスト名ではなく、振り分けたチャージ名とする
+        assert!(ics.contains("CATEGORIES:A社 保守\r\n"));
+        assert!(ics.contains("URL:https://app.clickup.com/t/task-a\r\n"));
+    }
+}
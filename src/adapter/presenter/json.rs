@@ -0,0 +1,370 @@
+use crate::domain::model::{
+    aggregated_by::{AggregatedRecord, AggregatedRecords, PivotRow, PivotTable},
+    DateRange, DurationUnit, Notation, TaskDuration,
+};
+use serde_derive::Serialize;
+
+/// 集計結果をJSON文字列に変換する
+pub(super) fn render<R: AggregatedRecords>(records: &R, notation: &Notation) -> String {
+    serde_json::to_string_pretty(&JsonReport::from_records(records, notation))
+        .expect("Fail to serialize report to JSON")
+}
+
+/// JSON出力の構造。テンプレートに渡すコンテキストとしても利用する
+#[derive(Debug, Serialize)]
+pub(super) struct JsonReport {
+    aggregated_by: &'static str,
+    date_range: JsonDateRange,
+    duration_unit: DurationUnit,
+    records: Vec<JsonRecord>,
+    grand_total: JsonDuration,
+    /// 丸めた場合のみ出力する
+    #[serde(skip_serializing_if = "Option::is_none")]
+    rounded_grand_total: Option<JsonDuration>,
+}
+
+impl JsonReport {
+    pub(super) fn from_records<R: AggregatedRecords>(records: &R, notation: &Notation) -> Self {
+        let duration = |duration: TaskDuration| JsonDuration::new(duration, notation);
+        Self {
+            aggregated_by: records.aggregated_by(),
+            date_range: records.date_range().into(),
+            duration_unit: notation.duration_format().unit(),
+            records: records
+                .records()
+                .iter()
+                .map(|record| JsonRecord::from_record(record, records.is_rounded(), notation))
+                .collect(),
+            grand_total: duration(records.grand_total()),
+            rounded_grand_total: records.rounded_grand_total().map(duration),
+        }
+    }
+}
+
+/// ピボット形式の集計結果をJSON文字列に変換する
+pub(super) fn render_pivot(table: &PivotTable, notation: &Notation) -> String {
+    serde_json::to_string_pretty(&JsonPivotTable::new(table, notation))
+        .expect("Fail to serialize pivot table to JSON")
+}
+
+/// 所要時間を持たないセルはnullとする
+#[derive(Debug, Serialize)]
+struct JsonPivotTable {
+    aggregated_by: &'static str,
+    date_range: JsonDateRange,
+    duration_unit: DurationUnit,
+    target_dates: Vec<String>,
+    rows: Vec<JsonPivotRow>,
+    daily_totals: Vec<Option<JsonDuration>>,
+    grand_total: JsonDuration,
+}
+
+impl JsonPivotTable {
+    fn new(table: &PivotTable, notation: &Notation) -> Self {
+        let duration = |duration: TaskDuration| JsonDuration::new(duration, notation);
+        Self {
+            aggregated_by: table.aggregated_by(),
+            date_range: table.date_range().into(),
+            duration_unit: notation.duration_format().unit(),
+            target_dates: table
+                .target_dates()
+                .iter()
+                .map(|date| date.format("%Y-%m-%d").to_string())
+                .collect(),
+            rows: table
+                .rows()
+                .iter()
+                .map(|row| JsonPivotRow::new(row, notation))
+                .collect(),
+            daily_totals: table
+                .daily_totals()
+                .into_iter()
+                .map(|total| total.map(duration))
+                .collect(),
+            grand_total: duration(table.grand_total()),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct JsonPivotRow {
+    charge_name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    task_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    task_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    task_url: Option<String>,
+    cells: Vec<Option<JsonDuration>>,
+    total: JsonDuration,
+}
+
+impl JsonPivotRow {
+    fn new(row: &PivotRow, notation: &Notation) -> Self {
+        let duration = |duration: TaskDuration| JsonDuration::new(duration, notation);
+        Self {
+            charge_name: row.charge_name().to_string(),
+            task_id: row.task_id().map(str::to_string),
+            task_name: row.task_name().map(str::to_string),
+            task_url: row.task_url().map(str::to_string),
+            cells: row
+                .cells()
+                .iter()
+                .map(|cell| cell.clone().map(duration))
+                .collect(),
+            total: duration(row.total()),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct JsonDateRange {
+    start_date: String,
+    end_date: String,
+    start_datetime: String,
+    end_datetime: String,
+    is_same_date: bool,
+    time_zone: &'static str,
+    /// 請求期間を指定した場合のみ出力する
+    #[serde(skip_serializing_if = "Option::is_none")]
+    period: Option<String>,
+}
+
+impl From<&DateRange> for JsonDateRange {
+    fn from(date_range: &DateRange) -> Self {
+        Self {
+            start_date: date_range.start_date().format("%Y-%m-%d").to_string(),
+            end_date: date_range.end_target_date().format("%Y-%m-%d").to_string(),
+            start_datetime: date_range.start().to_rfc3339(),
+            end_datetime: date_range.end().to_rfc3339(),
+            is_same_date: date_range.is_same_date(),
+            time_zone: date_range.zone().name(),
+            period: date_range.period_label().map(str::to_string),
+        }
+    }
+}
+
+/// 集計単位によって存在しない項目は出力しない
+#[derive(Debug, Serialize)]
+struct JsonRecord {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    target_date: Option<String>,
+    /// 作業の無かった稼働日の行は、更新日時とチャージコードを持たない
+    #[serde(skip_serializing_if = "Option::is_none")]
+    updated_at: Option<String>,
+    total_duration: JsonDuration,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    rounded_duration: Option<JsonDuration>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    charge_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    space_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    folder_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    list_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    task_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    task_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    task_url: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    task_status: Option<String>,
+}
+
+impl JsonRecord {
+    fn from_record<T: AggregatedRecord>(record: &T, rounded: bool, notation: &Notation) -> Self {
+        let duration = |duration: &TaskDuration| JsonDuration::new(duration.clone(), notation);
+        Self {
+            target_date: record
+                .target_date()
+                .map(|date| date.format("%Y-%m-%d").to_string()),
+            updated_at: record.updated_at().map(|datetime| datetime.to_rfc3339()),
+            total_duration: duration(record.total_duration()),
+            rounded_duration: rounded.then(|| duration(record.rounded_duration())),
+            charge_name: record.charge_name().map(str::to_string),
+            space_name: record.space_name().map(str::to_string),
+            folder_name: record.folder_name().map(str::to_string),
+            list_name: record.list_name().map(str::to_string),
+            task_id: record.task_id().map(str::to_string),
+            task_name: record.task_name().map(str::to_string),
+            task_url: record.task_url().map(str::to_string),
+            task_status: record.task_status().map(str::to_string),
+        }
+    }
+}
+
+/// 所要時間は秒数とhh:mi:ss表記に加え、指定した単位・桁数の表記で出力する
+#[derive(Debug, Serialize)]
+struct JsonDuration {
+    seconds: i64,
+    hms: String,
+    formatted: String,
+}
+
+impl JsonDuration {
+    fn new(duration: TaskDuration, notation: &Notation) -> Self {
+        Self {
+            seconds: duration.num_seconds(),
+            hms: duration.to_string(),
+            formatted: notation.duration(&duration),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::adapter::presenter::fixtures::gen_task_records;
+    use crate::domain::model::{
+        aggregated_by::{ChargeAndTotalPeriodRecords, PivotTable, TaskAndDailyRecords},
+        DayBoundary, DurationFormat, Locale, RoundingPolicy, RoundingScope, WorkingCalendar,
+    };
+    use serde_json::Value;
+
+    #[test]
+    fn 日付範囲と総合計が出力される() {
+        let date_range = DateRange::new(
+            Some("2023/04/03"),
+            Some("2023/04/04"),
+            DayBoundary::default(),
+        )
+        .unwrap();
+        let records = TaskAndDailyRecords::new(date_range, gen_task_records());
+        let json: Value = serde_json::from_str(&render(&records, &Notation::default())).unwrap();
+
+        assert_eq!(json["aggregated_by"], "task_and_daily");
+        assert_eq!(json["date_range"]["start_date"], "2023-04-03");
+        assert_eq!(json["date_range"]["end_date"], "2023-04-04");
+        assert_eq!(json["date_range"]["is_same_date"], false);
+        assert_eq!(json["grand_total"]["seconds"], 165 * 60);
+        assert_eq!(json["grand_total"]["hms"], "02:45:00");
+    }
+
+    #[test]
+    fn タスク単位のレコードはタスク情報と対象日を持つ() {
+        let date_range = DateRange::new(
+            Some("2023/04/03"),
+            Some("2023/04/04"),
+            DayBoundary::default(),
+        )
+        .unwrap();
+        let records = TaskAndDailyRecords::new(date_range, gen_task_records());
+        let json: Value = serde_json::from_str(&render(&records, &Notation::default())).unwrap();
+
+        let first = &json["records"][0];
+        assert_eq!(json["records"].as_array().unwrap().len(), 3);
+        assert_eq!(first["target_date"], "2023-04-03");
+        assert_eq!(first["task_id"], "a");
+        assert_eq!(first["task_url"], "https://app.clickup.com/t/a");
+        assert_eq!(first["total_duration"]["seconds"], 30 * 60);
+        assert_eq!(first["total_duration"]["hms"], "00:30:00");
+    }
+
+    #[test]
+    fn チャージ単位のレコードはタスク情報を持たない() {
+        let date_range = DateRange::new(
+            Some("2023/04/03"),
+            Some("2023/04/04"),
+            DayBoundary::default(),
+        )
+        .unwrap();
+        let records = ChargeAndTotalPeriodRecords::new(date_range, gen_task_records());
+        let json: Value = serde_json::from_str(&render(&records, &Notation::default())).unwrap();
+
+        let first = json["records"][0].as_object().unwrap();
+        assert_eq!(first["charge_name"], "charge-1");
+        assert_eq!(first["total_duration"]["hms"], "01:15:00");
+        assert!(!first.contains_key("task_id"));
+        assert!(!first.contains_key("target_date"));
+    }
+
+    #[test]
+    fn 丸めた場合のみ丸めた所要時間と総合計を出力する() {
+        let date_range = DateRange::new(
+            Some("2023/04/03"),
+            Some("2023/04/04"),
+            DayBoundary::default(),
+        )
+        .unwrap();
+        let records = ChargeAndTotalPeriodRecords::new(date_range, gen_task_records());
+        let json: Value = serde_json::from_str(&render(&records, &Notation::default())).unwrap();
+        assert!(json.get("rounded_grand_total").is_none());
+        assert!(json["records"][0].get("rounded_duration").is_none());
+
+        let policy =
+            RoundingPolicy::new(Some("up:60".parse().unwrap()), vec![], RoundingScope::Row)
+                .unwrap();
+        let json: Value =
+            serde_json::from_str(&render(&records.round(&policy), &Notation::default())).unwrap();
+        assert_eq!(json["records"][0]["rounded_duration"]["hms"], "02:00:00");
+        assert_eq!(json["records"][1]["rounded_duration"]["hms"], "02:00:00");
+        assert_eq!(json["grand_total"]["hms"], "02:45:00");
+        assert_eq!(json["rounded_grand_total"]["seconds"], 4 * 60 * 60);
+    }
+
+    #[test]
+    fn 所要時間は指定した単位と桁数の表記も持つ() {
+        let date_range = DateRange::new(
+            Some("2023/04/03"),
+            Some("2023/04/04"),
+            DayBoundary::default(),
+        )
+        .unwrap();
+        let records = ChargeAndTotalPeriodRecords::new(date_range, gen_task_records());
+        let man_days = Notation::new(
+            Locale::Ja,
+            DurationFormat::new(DurationUnit::ManDays, 3, 7.5, 20.0),
+        );
+        let json: Value = serde_json::from_str(&render(&records, &man_days)).unwrap();
+
+        assert_eq!(json["duration_unit"], "man-days");
+        assert_eq!(json["records"][0]["total_duration"]["hms"], "01:15:00");
+        assert_eq!(json["records"][0]["total_duration"]["formatted"], "0.167");
+        assert_eq!(json["grand_total"]["formatted"], "0.367");
+    }
+
+    #[test]
+    fn ピボット形式では所要時間の無いセルがnullになる() {
+        let date_range = DateRange::new(
+            Some("2023/04/03"),
+            Some("2023/04/04"),
+            DayBoundary::default(),
+        )
+        .unwrap();
+        let table = PivotTable::by_charge(date_range, gen_task_records());
+        let json: Value =
+            serde_json::from_str(&render_pivot(&table, &Notation::default())).unwrap();
+
+        assert_eq!(json["aggregated_by"], "charge_and_date_pivot");
+        assert_eq!(json["target_dates"][1], "2023-04-04");
+        assert_eq!(json["rows"][1]["charge_name"], "charge-2");
+        assert!(json["rows"][1]["cells"][0].is_null());
+        assert_eq!(json["rows"][1]["cells"][1]["hms"], "01:30:00");
+        assert_eq!(json["daily_totals"][1]["seconds"], 135 * 60);
+        assert_eq!(json["grand_total"]["hms"], "02:45:00");
+    }
+
+    #[test]
+    fn 作業の無かった稼働日の行は対象日と所要時間0のみを持つ() {
+        let date_range = DateRange::new(
+            Some("2023/04/03"),
+            Some("2023/04/05"),
+            DayBoundary::default(),
+        )
+        .unwrap();
+        let records = TaskAndDailyRecords::new(date_range, gen_task_records())
+            .fill_working_days(&WorkingCalendar::default());
+        let json: Value = serde_json::from_str(&render(&records, &Notation::default())).unwrap();
+
+        let last = json["records"][3].as_object().unwrap();
+        assert_eq!(json["records"].as_array().unwrap().len(), 4);
+        assert_eq!(last["target_date"], "2023-04-05");
+        assert_eq!(last["total_duration"]["seconds"], 0);
+        assert!(!last.contains_key("updated_at"));
+        assert!(!last.contains_key("charge_name"));
+        assert!(!last.contains_key("task_id"));
+        assert_eq!(json["grand_total"]["seconds"], 165 * 60);
+    }
+}
@@ -0,0 +1,297 @@
+use super::grouped_records;
+use crate::domain::model::{
+    aggregated_by::{AggregatedRecord, AggregatedRecords, Column, GroupedRow, PivotTable},
+    Notation,
+};
+use unicode_width::UnicodeWidthStr;
+
+/// 集計結果をGitHub Flavored Markdownの見出しと表に変換する
+pub(super) fn render<R: AggregatedRecords>(
+    records: &R,
+    subtotal: bool,
+    notation: &Notation,
+) -> String {
+    let columns = records.columns();
+
+    let header = columns
+        .iter()
+        .map(|column| column.label(notation.messages()).to_string())
+        .collect::<Vec<_>>();
+    let mut rows = grouped_records(records, subtotal)
+        .iter()
+        .map(|row| match row {
+            GroupedRow::Record(record) => columns
+                .iter()
+                .map(|column| cell(column, record, notation))
+                .collect(),
+            // 小計の行は斜体で出力する
+            GroupedRow::Subtotal(subtotal) => subtotal
+                .line(&columns, notation)
+                .iter()
+                .map(|value| match value.is_empty() {
+                    true => String::new(),
+                    false => format!("*{}*", escape(value)),
+                })
+                .collect(),
+        })
+        .collect::<Vec<Vec<String>>>();
+    // 総合計は所要時間の列に揃え、太字で出力する
+    rows.push(
+        columns
+            .iter()
+            .enumerate()
+            .map(|(i, column)| match records.column_total(column) {
+                Some(total) => format!("**{}**", notation.duration(&total)),
+                None if i == 0 => format!("**{}**", notation.messages().grand_total),
+                None => String::new(),
+            })
+            .collect(),
+    );
+    let right_aligned = columns.iter().map(Column::is_duration).collect::<Vec<_>>();
+
+    format!(
+        "## {}\n\n{}\n",
+        escape(&notation.title(records.date_range())),
+        table_lines(header, rows, &right_aligned)
+    )
+}
+
+/// ピボット形式の集計結果をGitHub Flavored Markdownの見出しと表に変換する
+pub(super) fn render_pivot(table: &PivotTable, notation: &Notation) -> String {
+    let label_count = table.label_columns().len();
+
+    let header = table.header(notation);
+    let mut rows = table
+        .rows()
+        .iter()
+        .map(|row| {
+            let mut line = row
+                .line(notation)
+                .iter()
+                .map(|value| escape(value))
+                .collect::<Vec<_>>();
+            // タスク名はタスクのURLへのリンクにする
+            if let (Some(name), Some(url)) = (row.task_name(), row.task_url()) {
+                if !url.is_empty() {
+                    line[label_count - 1] = format!("[{}]({})", escape(name), escape_url(url));
+                }
+            }
+            line
+        })
+        .collect::<Vec<Vec<String>>>();
+    // 総合計の行は太字で出力する
+    rows.push(
+        table
+            .grand_total_line(notation)
+            .into_iter()
+            .map(|value| match value.is_empty() {
+                true => value,
+                false => format!("**{}**", escape(&value)),
+            })
+            .collect(),
+    );
+    // 行見出し以外は所要時間の列なので右寄せにする
+    let right_aligned = (0..header.len())
+        .map(|i| i >= label_count)
+        .collect::<Vec<_>>();
+
+    format!(
+        "## {}\n\n{}\n",
+        escape(&notation.title(table.date_range())),
+        table_lines(header, rows, &right_aligned)
+    )
+}
+
+/// 各列の表示幅を揃えた表を作成する
+fn table_lines(header: Vec<String>, rows: Vec<Vec<String>>, right_aligned: &[bool]) -> String {
+    let widths = (0..header.len())
+        .map(|i| {
+            rows.iter()
+                .map(|row| row[i].width())
+                .chain([header[i].width(), 3])
+                .max()
+                .unwrap()
+        })
+        .collect::<Vec<_>>();
+    let delimiter = right_aligned
+        .iter()
+        .zip(&widths)
+        .map(|(right_aligned, width)| match right_aligned {
+            true => format!("{}:", "-".repeat(width - 1)),
+            false => "-".repeat(*width),
+        })
+        .collect::<Vec<_>>();
+
+    [header, delimiter]
+        .into_iter()
+        .chain(rows)
+        .map(|row| to_line(&row, &widths))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn cell<R: AggregatedRecord>(column: &Column, record: &R, notation: &Notation) -> String {
+    let text = escape(&column.format(record, notation));
+    match (column, record.task_url()) {
+        // タスク名はタスクのURLへのリンクにする
+        (Column::TaskName, Some(url)) if !url.is_empty() => {
+            format!("[{}]({})", text, escape_url(url))
+        }
+        _ => text,
+    }
+}
+
+fn to_line(row: &[String], widths: &[usize]) -> String {
+    let cells = row
+        .iter()
+        .zip(widths)
+        .map(|(cell, width)| format!("{}{}", cell, " ".repeat(width - cell.width())))
+        .collect::<Vec<_>>();
+    format!("| {} |", cells.join(" | "))
+}
+
+/// 表のセル内でMarkdownとして解釈される記号をバックスラッシュでエスケープする
+/// 表のセル内では改行できないので、改行は空白に置き換える
+fn escape(text: &str) -> String {
+    text.chars()
+        .fold(String::with_capacity(text.len()), |mut escaped, c| {
+            match c {
+                '\\' | '`' | '*' | '_' | '[' | ']' | '<' | '>' | '|' | '~' => {
+                    escaped.push('\\');
+                    escaped.push(c);
+                }
+                '\r' | '\n' | '\t' => escaped.push(' '),
+                _ => escaped.push(c),
+            }
+            escaped
+        })
+}
+
+/// リンク先URLとして解釈が変わる文字をパーセントエンコードする
+fn escape_url(url: &str) -> String {
+    url.replace(' ', "%20")
+        .replace('(', "%28")
+        .replace(')', "%29")
+        .replace('|', "%7C")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::adapter::presenter::fixtures::{gen_task_record, gen_task_records};
+    use crate::domain::model::{
+        aggregated_by::{ChargeAndDailyRecords, PivotTable, TaskAndTotalPeriodRecords},
+        DateRange, DayBoundary, TaskRecords,
+    };
+
+    #[test]
+    fn markdownの記号はエスケープされる() {
+        assert_eq!(escape("a|b"), "a\\|b");
+        assert_eq!(escape("*bold* [link](x)"), "\\*bold\\* \\[link\\](x)");
+        assert_eq!(escape("line\nbreak"), "line break");
+    }
+
+    #[test]
+    fn 見出しと列幅の揃った表が出力される() {
+        let date_range = DateRange::new(Some("2023/04/04"), None, DayBoundary::default()).unwrap();
+        let records = ChargeAndDailyRecords::new(
+            date_range,
+            TaskRecords::new(vec![
+                gen_task_record("a", "charge-1", 4, 45),
+                gen_task_record("b", "charge-2", 4, 90),
+            ]),
+        );
+
+        assert_eq!(
+            render(&records, false, &Notation::default()),
+            [
+                "## 集計対象日付：2023/04/04 (Asia/Tokyo)",
+                "",
+                "| 対象日     | 最終更新日時        | 所要時間     | チャージコード |",
+                "| ---------- | ------------------- | -----------: | -------------- |",
+                "| 2023/04/04 | 2023/04/04 12:00:00 | 00:45:00     | charge-1       |",
+                "| 2023/04/04 | 2023/04/04 12:00:00 | 01:30:00     | charge-2       |",
+                "| **総合計** |                     | **02:15:00** |                |",
+                "",
+            ]
+            .join("\n")
+        );
+    }
+
+    #[test]
+    fn タスク名はタスクのurlへのリンクになる() {
+        let date_range = DateRange::new(
+            Some("2023/04/03"),
+            Some("2023/04/04"),
+            DayBoundary::default(),
+        )
+        .unwrap();
+        let mut record = gen_task_record("a", "charge-1", 3, 30);
+        record.task_name = "fix | pipe".to_string();
+        let records = TaskAndTotalPeriodRecords::new(date_range, TaskRecords::new(vec![record]));
+        let markdown = render(&records, false, &Notation::default());
+
+        assert!(markdown.starts_with("## 集計対象期間：2023/04/03 ～ 2023/04/04 (Asia/Tokyo)\n"));
+        assert!(markdown.contains("[fix \\| pipe](https://app.clickup.com/t/a)"));
+    }
+
+    #[test]
+    fn 総合計の行は太字になる() {
+        let date_range = DateRange::new(
+            Some("2023/04/03"),
+            Some("2023/04/04"),
+            DayBoundary::default(),
+        )
+        .unwrap();
+        let records = TaskAndTotalPeriodRecords::new(date_range, gen_task_records());
+
+        let last_line = render(&records, false, &Notation::default())
+            .lines()
+            .last()
+            .unwrap()
+            .to_string();
+        assert!(last_line.starts_with("| **総合計**"));
+        assert!(last_line.contains("**02:45:00**"));
+    }
+
+    #[test]
+    fn ピボット形式ではタスク毎の行と日付毎の列を持つ() {
+        let date_range = DateRange::new(
+            Some("2023/04/03"),
+            Some("2023/04/04"),
+            DayBoundary::default(),
+        )
+        .unwrap();
+        let table = PivotTable::by_task(date_range, gen_task_records());
+
+        assert_eq!(
+            render_pivot(&table, &Notation::default()),
+            [
+                "## 集計対象期間：2023/04/03 ～ 2023/04/04 (Asia/Tokyo)",
+                "",
+                "| チャージコード | タスク名                              | 2023/04/03   | 2023/04/04   | 所要時間     |",
+                "| -------------- | ------------------------------------- | -----------: | -----------: | -----------: |",
+                "| charge-1       | [task-a](https://app.clickup.com/t/a) | 00:30:00     | 00:45:00     | 01:15:00     |",
+                "| charge-2       | [task-b](https://app.clickup.com/t/b) |              | 01:30:00     | 01:30:00     |",
+                "| **総合計**     |                                       | **00:30:00** | **02:15:00** | **02:45:00** |",
+                "",
+            ]
+            .join("\n")
+        );
+    }
+
+    #[test]
+    fn 小計の行は斜体になる() {
+        let date_range = DateRange::new(Some("2023/04/04"), None, DayBoundary::default()).unwrap();
+        let records = ChargeAndDailyRecords::new(date_range, gen_task_records());
+
+        let lines = render(&records, true, &Notation::default())
+            .lines()
+            .map(str::to_string)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            lines[lines.len() - 2],
+            "| *2023/04/04* | *小計*              | *02:15:00*   |                |"
+        );
+    }
+}
@@ -0,0 +1,201 @@
+mod csv;
+#[cfg(test)]
+mod fixtures;
+mod html;
+mod ics;
+mod json;
+mod markdown;
+mod template;
+mod tsv;
+mod xlsx;
+
+use crate::{
+    cui::Opts,
+    domain::model::{
+        aggregated_by::{AggregatedRecords, AllAggregatedRecords, GroupedRow, PivotTable},
+        clickup::ChargedTimeEntry,
+        DurationFormat, Notation,
+    },
+    error::AppError,
+};
+pub(crate) use csv::CsvOptions;
+
+/// 集計結果の出力形式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub(crate) enum ReportFormat {
+    /// タイトル・総合計付きのTSV形式
+    Tsv,
+    /// 構造化されたJSON形式
+    Json,
+    /// RFC 4180準拠のCSV形式
+    Csv,
+    /// 見出しと表からなるMarkdown形式
+    Markdown,
+    /// 全ての集計単位の表とグラフをまとめたHTML形式
+    Html,
+    /// 全ての集計単位をシート毎に分けたExcelブック形式
+    Xlsx,
+    /// タイムエントリーを1件ずつ予定としたiCalendar形式
+    Ics,
+}
+
+impl ReportFormat {
+    /// 集計単位の指定に依らず、全ての集計単位をまとめて出力する形式か否か
+    pub(crate) fn aggregates_all_views(&self) -> bool {
+        matches!(self, ReportFormat::Html | ReportFormat::Xlsx)
+    }
+
+    /// 集計せずに、タイムエントリーをそのまま出力する形式か否か
+    pub(crate) fn exports_time_entries(&self) -> bool {
+        matches!(self, ReportFormat::Ics)
+    }
+}
+
+/// 出力形式に変換した集計結果
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Report {
+    Text(String),
+    /// xlsx等、テキストとして扱えない形式
+    Binary(Vec<u8>),
+}
+
+impl Report {
+    pub(crate) fn as_bytes(&self) -> &[u8] {
+        match self {
+            Report::Text(text) => text.as_bytes(),
+            Report::Binary(bytes) => bytes,
+        }
+    }
+}
+
+/// 集計結果を指定された出力形式の文字列に変換する
+#[derive(Debug, Clone)]
+pub(crate) struct Presenter {
+    format: ReportFormat,
+    csv_options: CsvOptions,
+    /// 日単位の集計結果に、グループ毎の小計の行を挟むか否か
+    subtotal: bool,
+    /// 指定された場合、出力形式の代わりにテンプレートで出力する
+    template: Option<String>,
+    /// 出力時の言語・所要時間の単位・桁数
+    notation: Notation,
+}
+
+impl Presenter {
+    pub(crate) fn render<R>(&self, records: &R) -> Report
+    where
+        R: AggregatedRecords,
+    {
+        Report::Text(self.render_text(records))
+    }
+
+    fn render_text<R>(&self, records: &R) -> String
+    where
+        R: AggregatedRecords,
+    {
+        if let Some(template) = &self.template {
+            return template::render(template, records, &self.notation);
+        }
+        match self.format {
+            ReportFormat::Tsv => tsv::render(records, self.subtotal, &self.notation),
+            ReportFormat::Json => json::render(records, &self.notation),
+            ReportFormat::Csv => {
+                csv::render(records, &self.csv_options, self.subtotal, &self.notation)
+            }
+            ReportFormat::Markdown => markdown::render(records, self.subtotal, &self.notation),
+            ReportFormat::Html | ReportFormat::Xlsx => {
+                unreachable!("{:?} format must be rendered by render_all()", self.format)
+            }
+            ReportFormat::Ics => {
+                unreachable!("Ics format must be rendered by render_time_entries()")
+            }
+        }
+    }
+
+    pub(crate) fn render_all(&self, records: &AllAggregatedRecords) -> Report {
+        match self.format {
+            ReportFormat::Html => Report::Text(html::render(records, &self.notation)),
+            ReportFormat::Xlsx => Report::Binary(xlsx::render(records, &self.notation)),
+            _ => unreachable!(
+                "{:?} format must be rendered for each aggregation",
+                self.format
+            ),
+        }
+    }
+
+    pub(crate) fn render_pivot(&self, table: &PivotTable) -> Report {
+        match self.format {
+            ReportFormat::Tsv => Report::Text(tsv::render_pivot(table, &self.notation)),
+            ReportFormat::Json => Report::Text(json::render_pivot(table, &self.notation)),
+            ReportFormat::Csv => {
+                Report::Text(csv::render_pivot(table, &self.csv_options, &self.notation))
+            }
+            ReportFormat::Markdown => Report::Text(markdown::render_pivot(table, &self.notation)),
+            _ => unreachable!("{:?} format does not support pivot table", self.format),
+        }
+    }
+
+    pub(crate) fn render_time_entries(&self, time_entries: &[ChargedTimeEntry]) -> Report {
+        match self.format {
+            ReportFormat::Ics => Report::Text(ics::render(time_entries, chrono::Utc::now())),
+            _ => unreachable!(
+                "{:?} format must be rendered from aggregated records",
+                self.format
+            ),
+        }
+    }
+}
+
+/// 小計の行を挟む場合は、グループの階層毎に小計の行を挟んだレコードを返す
+fn grouped_records<R: AggregatedRecords>(
+    records: &R,
+    subtotal: bool,
+) -> Vec<GroupedRow<R::Record>> {
+    if subtotal {
+        records.grouped_records()
+    } else {
+        records
+            .records()
+            .into_iter()
+            .map(GroupedRow::Record)
+            .collect()
+    }
+}
+
+impl TryFrom<Opts> for Presenter {
+    type Error = AppError;
+
+    fn try_from(opts: Opts) -> Result<Self, Self::Error> {
+        Ok(Self {
+            format: opts.format(),
+            csv_options: CsvOptions {
+                bom: opts.bom(),
+                header: !opts.no_header(),
+                grand_total: !opts.no_total(),
+            },
+            subtotal: opts.subtotal(),
+            template: opts
+                .template()
+                .as_ref()
+                .map(|path| {
+                    std::fs::read_to_string(path).map_err(|e| {
+                        AppError::Usage(format!(
+                            "Fail to read template file '{}': {}",
+                            path.display(),
+                            e
+                        ))
+                    })
+                })
+                .transpose()?,
+            notation: Notation::new(
+                opts.locale(),
+                DurationFormat::new(
+                    opts.unit(),
+                    opts.precision(),
+                    opts.hours_per_day(),
+                    opts.days_per_month(),
+                ),
+            ),
+        })
+    }
+}
@@ -0,0 +1,125 @@
+use super::json::JsonReport;
+use crate::domain::model::{aggregated_by::AggregatedRecords, Notation};
+use minijinja::Environment;
+use serde_derive::Serialize;
+
+/// 利用者が用意したテンプレート(Jinja2互換の構文)で集計結果を出力する
+pub(super) fn render<R: AggregatedRecords>(
+    template: &str,
+    records: &R,
+    notation: &Notation,
+) -> String {
+    let mut env = Environment::new();
+    // テンプレート末尾の改行をそのまま出力する
+    env.set_keep_trailing_newline(true);
+    env.render_str(template, TemplateContext::from_records(records, notation))
+        .unwrap_or_else(|e| panic!("Fail to render template: {:#}", e))
+}
+
+/// テンプレートに渡すコンテキスト
+/// JSON出力と同じ項目に加えて、TSV出力と同じ表記のタイトル・ヘッダ・行を持つ
+/// `columns` は言語に依らない項目名、 `headers` は言語毎の見出しとする
+#[derive(Debug, Serialize)]
+struct TemplateContext {
+    title: String,
+    columns: Vec<&'static str>,
+    headers: Vec<&'static str>,
+    rows: Vec<Vec<String>>,
+    #[serde(flatten)]
+    report: JsonReport,
+}
+
+impl TemplateContext {
+    fn from_records<R: AggregatedRecords>(records: &R, notation: &Notation) -> Self {
+        let columns = records.columns();
+        Self {
+            title: notation.title(records.date_range()),
+            columns: columns.iter().map(|column| column.name()).collect(),
+            headers: columns
+                .iter()
+                .map(|column| column.label(notation.messages()))
+                .collect(),
+            rows: records
+                .records()
+                .iter()
+                .map(|record| {
+                    columns
+                        .iter()
+                        .map(|column| column.format(record, notation))
+                        .collect()
+                })
+                .collect(),
+            report: JsonReport::from_records(records, notation),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::adapter::presenter::fixtures::gen_task_records;
+    use crate::domain::model::{
+        aggregated_by::{ChargeAndTotalPeriodRecords, TaskAndDailyRecords},
+        DateRange, DayBoundary,
+    };
+
+    #[test]
+    fn タイトルと総合計をテンプレートに埋め込める() {
+        let date_range = DateRange::new(
+            Some("2023/04/03"),
+            Some("2023/04/04"),
+            DayBoundary::default(),
+        )
+        .unwrap();
+        let records = ChargeAndTotalPeriodRecords::new(date_range, gen_task_records());
+        let template = "{{ title }}\n\
+            {% for record in records %}{{ record.charge_name }}={{ record.total_duration.hms }}\n{% endfor %}\
+            total={{ grand_total.seconds }}\n";
+
+        assert_eq!(
+            render(template, &records, &Notation::default()),
+            "集計対象期間：2023/04/03 ～ 2023/04/04 (Asia/Tokyo)\ncharge-1=01:15:00\ncharge-2=01:30:00\ntotal=9900\n"
+        );
+    }
+
+    #[test]
+    fn 列と行はtsv出力と同じ並び順と表記になる() {
+        let date_range = DateRange::new(
+            Some("2023/04/03"),
+            Some("2023/04/04"),
+            DayBoundary::default(),
+        )
+        .unwrap();
+        let records = TaskAndDailyRecords::new(date_range, gen_task_records());
+        let template =
+            "{{ columns | join(',') }}\n{{ headers | join(',') }}\n{% for row in rows %}{{ row | join(',') }}\n{% endfor %}";
+        let output = render(template, &records, &Notation::default());
+        let lines = output.lines().collect::<Vec<_>>();
+
+        assert_eq!(
+            lines[0],
+            "target_date,updated_at,total_duration,charge_name,task_status,task_name"
+        );
+        assert_eq!(
+            lines[1],
+            "対象日,最終更新日時,所要時間,チャージコード,ステータス,タスク名"
+        );
+        assert_eq!(
+            lines[2],
+            "2023/04/03,2023/04/03 12:00:00,00:30:00,charge-1,in progress,task-a"
+        );
+        assert_eq!(lines.len(), 5);
+    }
+
+    #[test]
+    #[should_panic(expected = "Fail to render template")]
+    fn 構文誤りのテンプレートはエラーになる() {
+        let date_range = DateRange::new(Some("2023/04/03"), None, DayBoundary::default()).unwrap();
+        let records = ChargeAndTotalPeriodRecords::new(date_range, gen_task_records());
+        render(
+            "{% for record in records %}",
+            &records,
+            &Notation::default(),
+        );
+    }
+}
@@ -0,0 +1,440 @@
+use super::grouped_records;
+use crate::domain::model::{
+    aggregated_by::{AggregatedRecords, Column, GroupedRow, PivotTable},
+    Notation,
+};
+
+/// 集計結果をタイトル・総合計付きのTSV形式に変換する
+pub(super) fn render<R: AggregatedRecords>(
+    records: &R,
+    subtotal: bool,
+    notation: &Notation,
+) -> String {
+    let columns = records.columns();
+
+    // 列名は機械的に扱えるよう、言語に依らない名前とする
+    let header = columns.iter().map(Column::name).collect::<Vec<_>>();
+    let mut tsv = vec![header.join("\t")];
+    for row in grouped_records(records, subtotal) {
+        let line = match row {
+            GroupedRow::Record(record) => columns
+                .iter()
+                .map(|column| column.format(&record, notation))
+                .collect::<Vec<_>>(),
+            GroupedRow::Subtotal(subtotal) => subtotal.line(&columns, notation),
+        };
+        tsv.push(line.join("\t"));
+    }
+    tsv.push(records.grand_total_line(notation).join("\t"));
+
+    format!(
+        "\n{}\n[\n{}\n]",
+        notation.title(records.date_range()),
+        tsv.join("\n")
+    )
+}
+
+/// ピボット形式の集計結果をタイトル・総合計付きのTSV形式に変換する
+pub(super) fn render_pivot(table: &PivotTable, notation: &Notation) -> String {
+    let tsv = [table.column_names(notation)]
+        .into_iter()
+        .chain(table.rows().iter().map(|row| row.line(notation)))
+        .chain([table.grand_total_line(notation)])
+        .map(|line| line.join("\t"))
+        .collect::<Vec<_>>();
+
+    format!(
+        "\n{}\n[\n{}\n]",
+        notation.title(table.date_range()),
+        tsv.join("\n")
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::adapter::presenter::fixtures::{gen_task_record, gen_task_records};
+    use crate::domain::model::{
+        aggregated_by::{
+            ChargeAndDailyRecords, ChargeAndTotalPeriodRecords, LocationLevel, LocationRecords,
+            TaskAndDailyRecords, TaskAndTotalPeriodRecords,
+        },
+        Bucketing, CompanyHolidays, DateRange, DayBoundary, DurationFormat, DurationUnit,
+        Granularity, Locale, RoundingPolicy, RoundingScope, TaskRecords, WeekStart,
+        WorkingCalendar,
+    };
+
+    fn gen_policy(scope: RoundingScope) -> RoundingPolicy {
+        RoundingPolicy::new(
+            Some("nearest:60".parse().unwrap()),
+            vec!["charge-2=down:60".parse().unwrap()],
+            scope,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn 日付毎の小計の行が挟まれる() {
+        let date_range = DateRange::new(
+            Some("2023/04/03"),
+            Some("2023/04/04"),
+            DayBoundary::default(),
+        )
+        .unwrap();
+        let records = ChargeAndDailyRecords::new(date_range, gen_task_records());
+
+        assert_eq!(
+            render(&records, true, &Notation::default()),
+            [
+                "",
+                "集計対象期間：2023/04/03 ～ 2023/04/04 (Asia/Tokyo)",
+                "[",
+                "target_date\tupdated_at\ttotal_duration\tcharge_name",
+                "2023/04/03\t2023/04/03 12:00:00\t00:30:00\tcharge-1",
+                "2023/04/03\t小計\t00:30:00\t",
+                "2023/04/04\t2023/04/04 12:00:00\t00:45:00\tcharge-1",
+                "2023/04/04\t2023/04/04 12:00:00\t01:30:00\tcharge-2",
+                "2023/04/04\t小計\t02:15:00\t",
+                "総合計\t02:45:00",
+                "]",
+            ]
+            .join("\n")
+        );
+    }
+
+    #[test]
+    fn 週単位では対象日の代わりに週を見出しとする() {
+        let date_range = DateRange::new(
+            Some("2023/04/01"),
+            Some("2023/04/04"),
+            DayBoundary::default(),
+        )
+        .unwrap();
+        let records = ChargeAndDailyRecords::by_bucket(
+            date_range,
+            TaskRecords::new(vec![
+                gen_task_record("a", "charge-1", 1, 30),
+                gen_task_record("a", "charge-1", 3, 45),
+                gen_task_record("b", "charge-2", 4, 90),
+            ]),
+            Bucketing::new(Granularity::Weekly, WeekStart::Mon),
+        );
+
+        assert_eq!(
+            render(&records, true, &Notation::default()),
+            [
+                "",
+                "集計対象期間：2023/04/01 ～ 2023/04/04 (Asia/Tokyo)",
+                "[",
+                "bucket\tupdated_at\ttotal_duration\tcharge_name",
+                "2023-W13\t2023/04/01 12:00:00\t00:30:00\tcharge-1",
+                "2023-W13\t小計\t00:30:00\t",
+                "2023-W14\t2023/04/03 12:00:00\t00:45:00\tcharge-1",
+                "2023-W14\t2023/04/04 12:00:00\t01:30:00\tcharge-2",
+                "2023-W14\t小計\t02:15:00\t",
+                "総合計\t02:45:00",
+                "]",
+            ]
+            .join("\n")
+        );
+    }
+
+    #[test]
+    fn 作業の無かった稼働日は対象日と所要時間0のみの行として表示する() {
+        let date_range = DateRange::new(
+            Some("2023/04/03"),
+            Some("2023/04/08"),
+            DayBoundary::default(),
+        )
+        .unwrap();
+        // 04/05は会社の休日、04/08は土曜日
+        let calendar = WorkingCalendar::new(CompanyHolidays::parse_text("2023/04/05").unwrap());
+        let records = ChargeAndDailyRecords::new(
+            date_range.clone(),
+            TaskRecords::new(vec![
+                gen_task_record("a", "charge-1", 3, 30),
+                gen_task_record("b", "charge-2", 4, 90),
+            ]),
+        )
+        .fill_working_days(&calendar);
+
+        assert_eq!(
+            render(&records, false, &Notation::default()),
+            [
+                "",
+                "集計対象期間：2023/04/03 ～ 2023/04/08 (Asia/Tokyo)",
+                "[",
+                "target_date\tupdated_at\ttotal_duration\tcharge_name",
+                "2023/04/03\t2023/04/03 12:00:00\t00:30:00\tcharge-1",
+                "2023/04/04\t2023/04/04 12:00:00\t01:30:00\tcharge-2",
+                "2023/04/06\t\t00:00:00\t",
+                "2023/04/07\t\t00:00:00\t",
+                "総合計\t02:00:00",
+                "]",
+            ]
+            .join("\n")
+        );
+    }
+
+    #[test]
+    fn 作業の無かった稼働日の行には小計の行を挟まない() {
+        let date_range = DateRange::new(
+            Some("2023/04/04"),
+            Some("2023/04/05"),
+            DayBoundary::default(),
+        )
+        .unwrap();
+        let calendar = WorkingCalendar::default();
+        let records = TaskAndDailyRecords::new(
+            date_range,
+            TaskRecords::new(vec![gen_task_record("a", "charge-1", 4, 45)]),
+        )
+        .fill_working_days(&calendar);
+        let lines = render(&records, true, &Notation::default())
+            .lines()
+            .skip(4)
+            .map(str::to_string)
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            lines,
+            vec![
+                "2023/04/04\t2023/04/04 12:00:00\t00:45:00\tcharge-1\tin progress\ttask-a",
+                "2023/04/04\t小計\t00:45:00\tcharge-1\t\t",
+                "2023/04/04\t小計\t00:45:00\t\t\t",
+                "2023/04/05\t\t00:00:00\t\t\t",
+                "総合計\t00:45:00",
+                "]",
+            ]
+        );
+    }
+
+    #[test]
+    fn タスク単位では日付毎とチャージコード毎の小計の行が挟まれる() {
+        let date_range = DateRange::new(Some("2023/04/04"), None, DayBoundary::default()).unwrap();
+        let records = TaskAndDailyRecords::new(
+            date_range,
+            TaskRecords::new(vec![
+                gen_task_record("a", "charge-1", 4, 45),
+                gen_task_record("c", "charge-1", 4, 15),
+                gen_task_record("b", "charge-2", 4, 90),
+            ]),
+        );
+        let lines = render(&records, true, &Notation::default())
+            .lines()
+            .skip(4)
+            .map(str::to_string)
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            lines,
+            vec![
+                "2023/04/04\t2023/04/04 12:00:00\t00:15:00\tcharge-1\tin progress\ttask-c",
+                "2023/04/04\t2023/04/04 12:00:00\t00:45:00\tcharge-1\tin progress\ttask-a",
+                "2023/04/04\t小計\t01:00:00\tcharge-1\t\t",
+                "2023/04/04\t2023/04/04 12:00:00\t01:30:00\tcharge-2\tin progress\ttask-b",
+                "2023/04/04\t小計\t01:30:00\tcharge-2\t\t",
+                "2023/04/04\t小計\t02:30:00\t\t\t",
+                "総合計\t02:30:00",
+                "]",
+            ]
+        );
+    }
+
+    #[test]
+    fn 階層毎ではスペースとフォルダとリスト毎の小計の行が挟まれる() {
+        let date_range = DateRange::new(Some("2023/04/04"), None, DayBoundary::default()).unwrap();
+        let records = LocationRecords::new(
+            date_range,
+            TaskRecords::new(vec![
+                gen_task_record("a", "charge-1", 4, 45),
+                gen_task_record("c", "charge-1", 4, 15),
+                gen_task_record("b", "charge-2", 4, 90),
+            ]),
+            LocationLevel::Tree,
+        );
+        let lines = render(&records, true, &Notation::default())
+            .lines()
+            .skip(3)
+            .map(str::to_string)
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            lines,
+            vec![
+                "updated_at\ttotal_duration\tspace_name\tfolder_name\tlist_name\ttask_status\ttask_name",
+                "2023/04/04 12:00:00\t00:15:00\tspace\tfolder\tcharge-1\tin progress\ttask-c",
+                "2023/04/04 12:00:00\t00:45:00\tspace\tfolder\tcharge-1\tin progress\ttask-a",
+                "小計\t01:00:00\tspace\tfolder\tcharge-1\t\t",
+                "2023/04/04 12:00:00\t01:30:00\tspace\tfolder\tcharge-2\tin progress\ttask-b",
+                "小計\t01:30:00\tspace\tfolder\tcharge-2\t\t",
+                "小計\t02:30:00\tspace\tfolder\t\t\t",
+                "小計\t02:30:00\tspace\t\t\t\t",
+                "総合計\t02:30:00",
+                "]",
+            ]
+        );
+    }
+
+    #[test]
+    fn 丸めた所要時間の列と丸めた行の合計が総合計に並ぶ() {
+        let date_range = DateRange::new(
+            Some("2023/04/03"),
+            Some("2023/04/04"),
+            DayBoundary::default(),
+        )
+        .unwrap();
+        let records = ChargeAndDailyRecords::new(date_range, gen_task_records())
+            .round(&gen_policy(RoundingScope::Row));
+
+        assert_eq!(
+            render(&records, true, &Notation::default()),
+            [
+                "",
+                "集計対象期間：2023/04/03 ～ 2023/04/04 (Asia/Tokyo)",
+                "[",
+                "target_date\tupdated_at\ttotal_duration\trounded_duration\tcharge_name",
+                "2023/04/03\t2023/04/03 12:00:00\t00:30:00\t01:00:00\tcharge-1",
+                "2023/04/03\t小計\t00:30:00\t01:00:00\t",
+                "2023/04/04\t2023/04/04 12:00:00\t00:45:00\t01:00:00\tcharge-1",
+                "2023/04/04\t2023/04/04 12:00:00\t01:30:00\t01:00:00\tcharge-2",
+                "2023/04/04\t小計\t02:15:00\t02:00:00\t",
+                "総合計\t02:45:00\t03:00:00",
+                "]",
+            ]
+            .join("\n")
+        );
+    }
+
+    #[test]
+    fn タイムエントリー毎に丸めると丸めた所要時間の合計を行の値とする() {
+        let date_range = DateRange::new(
+            Some("2023/04/03"),
+            Some("2023/04/04"),
+            DayBoundary::default(),
+        )
+        .unwrap();
+        let by_entry = ChargeAndTotalPeriodRecords::new(
+            date_range.clone(),
+            gen_task_records().round_each(&gen_policy(RoundingScope::Entry)),
+        )
+        .round(&gen_policy(RoundingScope::Entry));
+        let by_row = ChargeAndTotalPeriodRecords::new(date_range, gen_task_records())
+            .round(&gen_policy(RoundingScope::Row));
+
+        // charge-1 は 30分 + 45分。エントリー毎なら 1時間 + 1時間、行毎なら 1時間15分 を丸めて 1時間
+        assert_eq!(
+            by_entry.grand_total_line(&Notation::default()),
+            vec!["総合計", "02:45:00", "03:00:00"]
+        );
+        assert_eq!(
+            by_row.grand_total_line(&Notation::default()),
+            vec!["総合計", "02:45:00", "02:00:00"]
+        );
+    }
+
+    #[test]
+    fn 小計と総合計も指定した単位で出力される() {
+        let date_range = DateRange::new(
+            Some("2023/04/03"),
+            Some("2023/04/04"),
+            DayBoundary::default(),
+        )
+        .unwrap();
+        let records = ChargeAndDailyRecords::new(date_range, gen_task_records());
+        let hours = Notation::new(
+            Locale::Ja,
+            DurationFormat::new(DurationUnit::Hours, 2, 8.0, 20.0),
+        );
+        let lines = render(&records, true, &hours)
+            .lines()
+            .skip(4)
+            .map(str::to_string)
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            lines,
+            vec![
+                "2023/04/03\t2023/04/03 12:00:00\t0.50\tcharge-1",
+                "2023/04/03\t小計\t0.50\t",
+                "2023/04/04\t2023/04/04 12:00:00\t0.75\tcharge-1",
+                "2023/04/04\t2023/04/04 12:00:00\t1.50\tcharge-2",
+                "2023/04/04\t小計\t2.25\t",
+                "総合計\t2.75",
+                "]",
+            ]
+        );
+    }
+
+    #[test]
+    fn ピボット形式では空欄のセルと総合計の行を出力する() {
+        let date_range = DateRange::new(
+            Some("2023/04/03"),
+            Some("2023/04/05"),
+            DayBoundary::default(),
+        )
+        .unwrap();
+        let table = PivotTable::by_charge(date_range, gen_task_records());
+
+        assert_eq!(
+            render_pivot(&table, &Notation::default()),
+            [
+                "",
+                "集計対象期間：2023/04/03 ～ 2023/04/05 (Asia/Tokyo)",
+                "[",
+                "charge_name\t2023/04/03\t2023/04/04\t2023/04/05\ttotal_duration",
+                "charge-1\t00:30:00\t00:45:00\t\t01:15:00",
+                "charge-2\t\t01:30:00\t\t01:30:00",
+                "総合計\t00:30:00\t02:15:00\t\t02:45:00",
+                "]",
+            ]
+            .join("\n")
+        );
+    }
+
+    #[test]
+    fn グループを持たない集計単位では小計の行が無い() {
+        let date_range = DateRange::new(
+            Some("2023/04/03"),
+            Some("2023/04/04"),
+            DayBoundary::default(),
+        )
+        .unwrap();
+        let records = TaskAndTotalPeriodRecords::new(date_range, gen_task_records());
+
+        assert_eq!(
+            render(&records, true, &Notation::default()),
+            render(&records, false, &Notation::default())
+        );
+    }
+
+    #[test]
+    fn 英語でも列名は変えずに日付と合計の表記を英語にする() {
+        let date_range = DateRange::new(
+            Some("2023/04/03"),
+            Some("2023/04/04"),
+            DayBoundary::default(),
+        )
+        .unwrap();
+        let records = ChargeAndDailyRecords::new(date_range, gen_task_records());
+        let en = Notation::new(Locale::En, DurationFormat::default());
+
+        assert_eq!(
+            render(&records, true, &en),
+            [
+                "",
+                "Target period: 2023-04-03 to 2023-04-04 (Asia/Tokyo)",
+                "[",
+                "target_date\tupdated_at\ttotal_duration\tcharge_name",
+                "2023-04-03\t2023-04-03 12:00:00\t00:30:00\tcharge-1",
+                "2023-04-03\tSubtotal\t00:30:00\t",
+                "2023-04-04\t2023-04-04 12:00:00\t00:45:00\tcharge-1",
+                "2023-04-04\t2023-04-04 12:00:00\t01:30:00\tcharge-2",
+                "2023-04-04\tSubtotal\t02:15:00\t",
+                "Grand total\t02:45:00",
+                "]",
+            ]
+            .join("\n")
+        );
+    }
+}
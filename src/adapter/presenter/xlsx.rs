@@ -0,0 +1,218 @@
+use crate::domain::model::{
+    aggregated_by::{AggregatedRecord, AggregatedRecords, AllAggregatedRecords, Column},
+    DurationFormat, DurationUnit, Notation, TaskDuration,
+};
+use rust_xlsxwriter::{utility::column_number_to_name, Format, Formula, Workbook, Worksheet};
+
+/// タイトル行と空行の後にヘッダ行を置く
+const HEADER_ROW: u32 = 2;
+/// Excelの時刻値は1日を1とする小数で表す
+const SECONDS_PER_DAY: f64 = 24.0 * 60.0 * 60.0;
+
+/// 全ての集計単位をシート毎に分けたExcelブックを作成する
+pub(super) fn render(records: &AllAggregatedRecords, notation: &Notation) -> Vec<u8> {
+    let mut workbook = Workbook::new();
+    let title = notation.title(records.date_range());
+
+    write_sheet(
+        workbook.add_worksheet(),
+        &title,
+        records.task_and_total_period(),
+        notation,
+    );
+    write_sheet(
+        workbook.add_worksheet(),
+        &title,
+        records.task_and_daily(),
+        notation,
+    );
+    write_sheet(
+        workbook.add_worksheet(),
+        &title,
+        records.charge_and_total_period(),
+        notation,
+    );
+    write_sheet(
+        workbook.add_worksheet(),
+        &title,
+        records.charge_and_daily(),
+        notation,
+    );
+
+    workbook
+        .save_to_buffer()
+        .expect("Fail to create xlsx workbook")
+}
+
+fn write_sheet<R: AggregatedRecords>(
+    sheet: &mut Worksheet,
+    title: &str,
+    records: &R,
+    notation: &Notation,
+) {
+    let bold = Format::new().set_bold();
+    let messages = notation.messages();
+    let date_format = Format::new().set_num_format(messages.excel_date_format);
+    let datetime_format = Format::new().set_num_format(messages.excel_datetime_format);
+    let number_format =
+        Format::new().set_num_format(duration_num_format(notation.duration_format()));
+    let total_format = number_format.clone().set_bold();
+
+    let columns = records.columns();
+    let sorted_records = records.records();
+
+    sheet
+        .set_name(records.aggregated_by())
+        .expect("Fail to set worksheet name");
+    sheet
+        .write_string_with_format(0, 0, title, &bold)
+        .expect("Fail to write title");
+
+    for (col, column) in (0_u16..).zip(&columns) {
+        sheet
+            .write_string_with_format(HEADER_ROW, col, column.label(messages), &bold)
+            .expect("Fail to write header");
+    }
+
+    for (row, record) in (HEADER_ROW + 1..).zip(&sorted_records) {
+        for (col, column) in (0_u16..).zip(&columns) {
+            if let Some(duration) = column.duration(record) {
+                sheet
+                    .write_number_with_format(
+                        row,
+                        col,
+                        duration_value(duration, notation.duration_format()),
+                        &number_format,
+                    )
+                    .expect("Fail to write record");
+                continue;
+            }
+            match column {
+                Column::TargetDate => match record.target_date() {
+                    Some(date) => sheet.write_datetime_with_format(row, col, date, &date_format),
+                    None => sheet.write_blank(row, col, &date_format),
+                },
+                Column::UpdatedAt => match record.updated_at() {
+                    Some(datetime) => sheet.write_datetime_with_format(
+                        row,
+                        col,
+                        datetime.naive_local(),
+                        &datetime_format,
+                    ),
+                    None => sheet.write_blank(row, col, &datetime_format),
+                },
+                Column::TaskName => match record.task_url() {
+                    Some(url) if !url.is_empty() => {
+                        sheet.write_url_with_text(row, col, url, column.format(record, notation))
+                    }
+                    _ => sheet.write_string(row, col, column.format(record, notation)),
+                },
+                _ => sheet.write_string(row, col, column.format(record, notation)),
+            }
+            .expect("Fail to write record");
+        }
+    }
+
+    // 総合計は計算済みの値ではなく、SUM関数で集計する
+    let total_row = HEADER_ROW + 1 + sorted_records.len() as u32;
+    for (col, column) in (0_u16..).zip(&columns) {
+        match records.column_total(column) {
+            Some(total) => {
+                let formula = Formula::new(sum_formula(col, sorted_records.len() as u32))
+                    .set_result(notation.duration(&total));
+                sheet.write_formula_with_format(total_row, col, formula, &total_format)
+            }
+            None if col == 0 => {
+                sheet.write_string_with_format(total_row, col, messages.grand_total, &bold)
+            }
+            None => continue,
+        }
+        .expect("Fail to write grand total");
+    }
+
+    sheet.autofit();
+}
+
+/// 所要時間の列を合計する数式
+fn sum_formula(col: u16, record_count: u32) -> String {
+    if record_count == 0 {
+        return "=0".to_string();
+    }
+    let column_name = column_number_to_name(col);
+    // 行番号は1始まりなので、ヘッダ行の次の行は HEADER_ROW + 2 となる
+    let first_row = HEADER_ROW + 2;
+    let last_row = HEADER_ROW + 1 + record_count;
+    format!(
+        "=SUM({}{}:{}{})",
+        column_name, first_row, column_name, last_row
+    )
+}
+
+/// hh:mi:ss表記の場合は時刻値の表示形式、それ以外は指定した桁数の小数の表示形式とする
+fn duration_num_format(duration_format: &DurationFormat) -> String {
+    match (duration_format.unit(), duration_format.precision()) {
+        (DurationUnit::Hms, _) => "[h]:mm:ss".to_string(),
+        (_, 0) => "0".to_string(),
+        (_, precision) => format!("0.{}", "0".repeat(precision)),
+    }
+}
+
+/// hh:mi:ss表記の場合は時刻値、それ以外は指定した単位に換算した値とする
+fn duration_value(duration: &TaskDuration, duration_format: &DurationFormat) -> f64 {
+    duration_format
+        .decimal(duration)
+        .unwrap_or_else(|| excel_time(duration.num_seconds()))
+}
+
+fn excel_time(seconds: i64) -> f64 {
+    seconds as f64 / SECONDS_PER_DAY
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::adapter::presenter::fixtures::gen_task_records;
+    use crate::domain::model::{DateRange, DayBoundary};
+
+    #[test]
+    fn 総合計は所要時間の列を合計する数式になる() {
+        assert_eq!(sum_formula(2, 3), "=SUM(C4:C6)");
+        assert_eq!(sum_formula(0, 1), "=SUM(A4:A4)");
+        assert_eq!(sum_formula(2, 0), "=0");
+    }
+
+    #[test]
+    fn 所要時間は1日を1とする時刻値になる() {
+        assert_eq!(excel_time(12 * 60 * 60), 0.5);
+        assert_eq!(excel_time(36 * 60 * 60), 1.5);
+    }
+
+    #[test]
+    fn 所要時間の表示形式は単位と桁数に従う() {
+        let hours = DurationFormat::new(DurationUnit::Hours, 2, 8.0, 20.0);
+        let minutes = DurationFormat::new(DurationUnit::Minutes, 0, 8.0, 20.0);
+        assert_eq!(duration_num_format(&DurationFormat::default()), "[h]:mm:ss");
+        assert_eq!(duration_num_format(&hours), "0.00");
+        assert_eq!(duration_num_format(&minutes), "0");
+
+        let duration = chrono::Duration::minutes(90).into();
+        assert_eq!(duration_value(&duration, &hours), 1.5);
+    }
+
+    #[test]
+    fn xlsxファイルが作成される() {
+        let date_range = DateRange::new(
+            Some("2023/04/03"),
+            Some("2023/04/04"),
+            DayBoundary::default(),
+        )
+        .unwrap();
+        let xlsx = render(
+            &AllAggregatedRecords::new(date_range, gen_task_records()),
+            &Notation::default(),
+        );
+
+        // xlsxファイルはzip形式である
+        assert!(xlsx.starts_with(b"PK"));
+    }
+}
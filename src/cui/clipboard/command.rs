@@ -0,0 +1,84 @@
+use super::Clipboard;
+use anyhow::{bail, Context, Result};
+use std::{
+    io::Write,
+    process::{Command, Stdio},
+};
+
+/// 標準入力から受け取った文字列をクリップボードに書き込む外部コマンド
+pub(super) struct CommandClipboard {
+    program: &'static str,
+    args: &'static [&'static str],
+    /// 利用に必要な環境変数（表示サーバへの接続先等）
+    required_env: Option<&'static str>,
+}
+
+impl CommandClipboard {
+    pub(super) fn wl_copy() -> Self {
+        Self {
+            program: "wl-copy",
+            args: &[],
+            required_env: Some("WAYLAND_DISPLAY"),
+        }
+    }
+    pub(super) fn xclip() -> Self {
+        Self {
+            program: "xclip",
+            args: &["-selection", "clipboard"],
+            required_env: Some("DISPLAY"),
+        }
+    }
+    pub(super) fn xsel() -> Self {
+        Self {
+            program: "xsel",
+            args: &["--clipboard", "--input"],
+            required_env: Some("DISPLAY"),
+        }
+    }
+    pub(super) fn pbcopy() -> Self {
+        Self {
+            program: "pbcopy",
+            args: &[],
+            required_env: None,
+        }
+    }
+
+    /// 環境変数PATHのディレクトリにコマンドが存在するか否か
+    fn exists_in_path(&self) -> bool {
+        let Some(paths) = std::env::var_os("PATH") else {
+            return false;
+        };
+        std::env::split_paths(&paths).any(|dir| {
+            dir.join(self.program).is_file() || dir.join(format!("{}.exe", self.program)).is_file()
+        })
+    }
+}
+
+impl Clipboard for CommandClipboard {
+    fn is_available(&self) -> bool {
+        let has_env = self
+            .required_env
+            .is_none_or(|name| std::env::var_os(name).is_some());
+        has_env && self.exists_in_path()
+    }
+
+    fn set_text(&self, text: &str) -> Result<()> {
+        let mut child = Command::new(self.program)
+            .args(self.args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .spawn()
+            .with_context(|| format!("Fail to spawn {}", self.program))?;
+        child
+            .stdin
+            .take()
+            .context("Fail to open stdin")?
+            .write_all(text.as_bytes())
+            .with_context(|| format!("Fail to write to {}", self.program))?;
+        let status = child.wait()?;
+        if !status.success() {
+            bail!("{} exited with {}", self.program, status);
+        }
+        Ok(())
+    }
+}
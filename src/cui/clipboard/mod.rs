@@ -0,0 +1,111 @@
+#[cfg(feature = "clipboard-command")]
+mod command;
+#[cfg(feature = "clipboard-osc52")]
+mod osc52;
+#[cfg(all(windows, feature = "clipboard-windows"))]
+mod windows;
+
+use crate::error::AppError;
+use anyhow::{bail, Result};
+
+/// クリップボードへの書き込み方法
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub(crate) enum ClipboardBackend {
+    /// Windowsのクリップボード
+    Windows,
+    /// Waylandのwl-copyコマンド
+    WlCopy,
+    /// X11のxclipコマンド
+    Xclip,
+    /// X11のxselコマンド
+    Xsel,
+    /// macOSのpbcopyコマンド
+    Pbcopy,
+    /// 端末のOSC 52エスケープシーケンス（SSH越しでも利用できる）
+    Osc52,
+}
+
+impl ClipboardBackend {
+    /// 自動選択する場合の優先順
+    const ALL: [ClipboardBackend; 6] = [
+        ClipboardBackend::Windows,
+        ClipboardBackend::WlCopy,
+        ClipboardBackend::Xclip,
+        ClipboardBackend::Xsel,
+        ClipboardBackend::Pbcopy,
+        ClipboardBackend::Osc52,
+    ];
+
+    fn clipboard(&self) -> Option<Box<dyn Clipboard>> {
+        match self {
+            #[cfg(all(windows, feature = "clipboard-windows"))]
+            ClipboardBackend::Windows => Some(Box::new(windows::WindowsClipboard)),
+            #[cfg(feature = "clipboard-command")]
+            ClipboardBackend::WlCopy => Some(Box::new(command::CommandClipboard::wl_copy())),
+            #[cfg(feature = "clipboard-command")]
+            ClipboardBackend::Xclip => Some(Box::new(command::CommandClipboard::xclip())),
+            #[cfg(feature = "clipboard-command")]
+            ClipboardBackend::Xsel => Some(Box::new(command::CommandClipboard::xsel())),
+            #[cfg(feature = "clipboard-command")]
+            ClipboardBackend::Pbcopy => Some(Box::new(command::CommandClipboard::pbcopy())),
+            #[cfg(feature = "clipboard-osc52")]
+            ClipboardBackend::Osc52 => Some(Box::new(osc52::Osc52Clipboard)),
+            // 対応するfeatureを有効にしてビルドしていない
+            #[allow(unreachable_patterns)]
+            _ => None,
+        }
+    }
+}
+
+/// クリップボードに書き込むためのトレイト
+pub(crate) trait Clipboard {
+    /// 実行中の環境で利用できるか否か
+    fn is_available(&self) -> bool;
+    fn set_text(&self, text: &str) -> Result<()>;
+}
+
+/// クリップボードに書き込む
+/// 書き込めない場合は、書き込み方法を使えないものとしてエラーを返す
+pub(crate) fn set_text(backend: Option<ClipboardBackend>, text: &str) -> Result<(), AppError> {
+    try_set_text(backend, text)
+        .map_err(|e| AppError::Unavailable(format!("Fail to set clipboard: {:#}", e)))
+}
+
+/// 書き込み方法が指定されない場合は、実行中の環境で利用できるものを優先順に選ぶ
+fn try_set_text(backend: Option<ClipboardBackend>, text: &str) -> Result<()> {
+    let candidates = match backend {
+        Some(backend) => vec![backend],
+        None => ClipboardBackend::ALL.to_vec(),
+    };
+    for candidate in &candidates {
+        match candidate.clipboard() {
+            Some(clipboard) if clipboard.is_available() => {
+                tracing::debug!("Set clipboard with: {:?}", candidate);
+                return clipboard.set_text(text);
+            }
+            _ => tracing::debug!("Clipboard backend is not available: {:?}", candidate),
+        }
+    }
+    bail!(
+        "No clipboard backend is available. Tried: {:?}. \
+        Install wl-copy/xclip/xsel/pbcopy, run in a terminal supporting OSC 52, \
+        or build with the corresponding clipboard feature.",
+        candidates
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(not(windows))]
+    #[test]
+    fn 利用できない書き込み方法を指定するとエラーになる() {
+        let err = set_text(Some(ClipboardBackend::Windows), "text").unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("No clipboard backend is available"));
+        // 設定の誤りではなく、サービスを使えないものとして終了する
+        assert_eq!(err.exit_code(), 69);
+    }
+}
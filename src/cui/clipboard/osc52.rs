@@ -0,0 +1,36 @@
+use super::Clipboard;
+use anyhow::{Context, Result};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use std::io::{IsTerminal, Write};
+
+/// 端末エミュレータにOSC 52エスケープシーケンスを送り、クリップボードに書き込ませる
+/// 標準出力は集計結果の出力に用いるため、標準エラー出力に書き込む
+pub(super) struct Osc52Clipboard;
+
+impl Clipboard for Osc52Clipboard {
+    fn is_available(&self) -> bool {
+        std::io::stderr().is_terminal()
+    }
+
+    fn set_text(&self, text: &str) -> Result<()> {
+        let mut stderr = std::io::stderr().lock();
+        stderr
+            .write_all(sequence(text).as_bytes())
+            .and_then(|_| stderr.flush())
+            .context("Fail to write OSC 52 sequence")
+    }
+}
+
+fn sequence(text: &str) -> String {
+    format!("\x1b]52;c;{}\x07", STANDARD.encode(text))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn base64エンコードした文字列をエスケープシーケンスで囲む() {
+        assert_eq!(sequence("タスク"), "\x1b]52;c;44K/44K544Kv\x07");
+    }
+}
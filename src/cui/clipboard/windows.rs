@@ -0,0 +1,17 @@
+use super::Clipboard;
+use anyhow::{anyhow, Result};
+use clipboard_win::{formats, set_clipboard};
+
+/// Windowsのクリップボードに直接書き込む
+pub(super) struct WindowsClipboard;
+
+impl Clipboard for WindowsClipboard {
+    fn is_available(&self) -> bool {
+        true
+    }
+
+    fn set_text(&self, text: &str) -> Result<()> {
+        set_clipboard(formats::Unicode, text)
+            .map_err(|e| anyhow!("Fail to set Windows clipboard: {}", e))
+    }
+}
@@ -1,82 +1,203 @@
+mod clipboard;
 mod options;
 
 use super::adapter::dto::RequestDto;
 use crate::{
-    adapter::Controller,
+    adapter::{presenter::Report, Controller, Presenter},
     cui::options::AggregateCondition,
+    domain::model::aggregated_by::LocationLevel,
+    error::AppError,
     infra::{repository_impl::RepositoryImpls, web::BasicClient},
     usecase::UsecaseImpls,
 };
-use clap::Parser;
-use clipboard_win::{formats, set_clipboard};
+use anyhow::{Context, Result};
+use clap::ValueEnum;
 pub(super) use options::Opts;
+use std::io::{IsTerminal, Write};
 
 #[derive(Debug, Clone)]
 pub(super) struct Cui {
     controller: Controller<UsecaseImpls>,
+    presenter: Presenter,
     opts: Opts,
 }
 
+/// コマンドライン引数を解析する
+/// ヘルプ・バージョンの表示はClickUpやログの設定が無くても行えるよう、他の初期化より先に行う
+pub(super) fn parse_opts() -> Result<Opts> {
+    let opts = Opts::try_parse_args(std::env::args_os()).or_else(usage_error)?;
+    if let Some(option) = opts.unsupported_by_format() {
+        return Err(AppError::Usage(format!(
+            "{} cannot be used with --format {}",
+            option,
+            opts.format().to_possible_value().unwrap().get_name()
+        ))
+        .into());
+    }
+    Ok(opts)
+}
+
 impl Cui {
-    pub(super) async fn new() -> Self {
+    pub(super) async fn new(opts: Opts) -> Result<Self> {
+        let presenter = opts.clone().try_into()?;
         let client = BasicClient::new();
-        let repositories = RepositoryImpls::new(client);
+        let repositories = RepositoryImpls::new(client)?;
         let usecases = UsecaseImpls::new(repositories);
         let controller = Controller::new(usecases).await;
-        Self {
+        Ok(Self {
             controller,
-            opts: Opts::parse(),
-        }
+            presenter,
+            opts,
+        })
     }
 
-    pub(super) async fn process(&self) {
+    pub(super) async fn process(&self) -> Result<()> {
         tracing::debug!("cli args: {:#?}", self.opts);
-        let dto: RequestDto = self.opts.clone().into();
+        let dto: RequestDto = self.opts.clone().try_into()?;
         let result = match self.opts.clone().into() {
-            AggregateCondition::TaskAndTotalPeriod => self.by_task_and_total_period(dto).await,
-            AggregateCondition::ChargeAndTotalPeriod => self.by_charge_and_total_period(dto).await,
-            AggregateCondition::TaskAndDaily => self.by_task_and_daily(dto).await,
-            AggregateCondition::ChargeAndDaily => self.by_charge_and_daily(dto).await,
+            AggregateCondition::TimeEntries => self.time_entries(dto).await?,
+            AggregateCondition::All => self.by_all(dto).await?,
+            AggregateCondition::WorkingDayCheck => self.check_working_days(dto).await?,
+            AggregateCondition::Location(level) => self.by_location(dto, level).await?,
+            AggregateCondition::TaskAndTotalPeriod => self.by_task_and_total_period(dto).await?,
+            AggregateCondition::ChargeAndTotalPeriod => {
+                self.by_charge_and_total_period(dto).await?
+            }
+            AggregateCondition::TaskAndDaily => self.by_task_and_daily(dto).await?,
+            AggregateCondition::ChargeAndDaily => self.by_charge_and_daily(dto).await?,
+            AggregateCondition::TaskAndDatePivot => self.by_task_and_date_pivot(dto).await?,
+            AggregateCondition::ChargeAndDatePivot => self.by_charge_and_date_pivot(dto).await?,
+        };
+
+        // クリップボードに書き込めなくても集計結果は失われないよう、出力を終えてからエラーとする
+        let clipboard_result = match (self.opts.set_clipboard(), &result) {
+            (false, _) => Ok(()),
+            // クリップボードにコピーする
+            (true, Report::Text(text)) => clipboard::set_text(self.opts.clipboard_backend(), text),
+            (true, Report::Binary(_)) => Err(AppError::Usage(
+                "Binary report cannot be set to clipboard".to_string(),
+            )),
         };
 
-        if self.opts.set_clipboard() {
-            // クリップボードにコピーする（Windows限定）
-            set_clipboard(formats::Unicode, result.to_string()).expect("Fail to set clipboard.");
+        match (self.opts.output(), &result) {
+            // ファイルに書き込む
+            (Some(path), _) => {
+                std::fs::write(path, result.as_bytes())
+                    .with_context(|| format!("Fail to write report file: {}", path.display()))?;
+                tracing::info!("Report is written to: {}", path.display());
+            }
+            // バイナリを端末に表示しても読めないので、リダイレクトされていない場合は書き込まない
+            (None, Report::Binary(_)) if std::io::stdout().is_terminal() => {
+                return Err(AppError::Usage(
+                    "Binary report cannot be written to terminal. Specify --output or redirect stdout"
+                        .to_string(),
+                )
+                .into())
+            }
+            // 標準出力に書き込む
+            (None, _) => write_stdout(&result).context("Fail to write report to stdout")?,
         }
+        Ok(clipboard_result?)
+    }
+
+    pub(super) async fn time_entries(&self, dto: RequestDto) -> Result<Report> {
+        tracing::debug!("time_entries");
+        let time_entries = self.controller.list_time_entries(dto).await?;
+        Ok(self.presenter.render_time_entries(&time_entries))
+    }
+
+    pub(super) async fn by_all(&self, dto: RequestDto) -> Result<Report> {
+        tracing::debug!("by_all");
+        let records = self.controller.aggregate_by_all(dto).await?;
+        Ok(self.presenter.render_all(&records))
+    }
+
+    pub(super) async fn check_working_days(&self, dto: RequestDto) -> Result<Report> {
+        tracing::debug!("check_working_days");
+        let records = self.controller.check_working_days(dto).await?;
+        Ok(self.presenter.render(&records))
+    }
 
-        // ログ出力する
-        tracing::info!("{}", result);
+    pub(super) async fn by_location(
+        &self,
+        dto: RequestDto,
+        level: LocationLevel,
+    ) -> Result<Report> {
+        tracing::debug!("by_location");
+        let records = self.controller.aggregate_by_location(dto, level).await?;
+        Ok(self.presenter.render(&records))
     }
 
-    pub(super) async fn by_task_and_total_period(&self, dto: RequestDto) -> String {
+    pub(super) async fn by_task_and_total_period(&self, dto: RequestDto) -> Result<Report> {
         tracing::debug!("by_task_and_total_period");
-        self.controller
+        let records = self
+            .controller
             .aggregate_by_task_and_total_period(dto)
-            .await
-            .to_string()
+            .await?;
+        Ok(self.presenter.render(&records))
     }
 
-    pub(super) async fn by_task_and_daily(&self, dto: RequestDto) -> String {
+    pub(super) async fn by_task_and_daily(&self, dto: RequestDto) -> Result<Report> {
         tracing::debug!("by_task_and_daily");
-        self.controller
-            .aggregate_by_task_and_daily(dto)
-            .await
-            .to_string()
+        let records = self.controller.aggregate_by_task_and_daily(dto).await?;
+        Ok(self.presenter.render(&records))
     }
 
-    pub(super) async fn by_charge_and_total_period(&self, dto: RequestDto) -> String {
+    pub(super) async fn by_charge_and_total_period(&self, dto: RequestDto) -> Result<Report> {
         tracing::debug!("by_charge_and_total_period");
-        self.controller
+        let records = self
+            .controller
             .aggregate_by_charge_and_total_period(dto)
-            .await
-            .to_string()
+            .await?;
+        Ok(self.presenter.render(&records))
     }
 
-    pub(super) async fn by_charge_and_daily(&self, dto: RequestDto) -> String {
+    pub(super) async fn by_charge_and_daily(&self, dto: RequestDto) -> Result<Report> {
         tracing::debug!("by_charge_and_daily");
-        self.controller
-            .aggregate_by_charge_and_daily(dto)
-            .await
-            .to_string()
+        let records = self.controller.aggregate_by_charge_and_daily(dto).await?;
+        Ok(self.presenter.render(&records))
+    }
+
+    pub(super) async fn by_task_and_date_pivot(&self, dto: RequestDto) -> Result<Report> {
+        tracing::debug!("by_task_and_date_pivot");
+        let table = self
+            .controller
+            .aggregate_by_task_and_date_pivot(dto)
+            .await?;
+        Ok(self.presenter.render_pivot(&table))
+    }
+
+    pub(super) async fn by_charge_and_date_pivot(&self, dto: RequestDto) -> Result<Report> {
+        tracing::debug!("by_charge_and_date_pivot");
+        let table = self
+            .controller
+            .aggregate_by_charge_and_date_pivot(dto)
+            .await?;
+        Ok(self.presenter.render_pivot(&table))
+    }
+}
+
+/// 集計結果を標準出力に書き込む
+/// ログは標準エラー出力に書き込まれるため、集計結果だけをリダイレクト・パイプできる
+fn write_stdout(report: &Report) -> std::io::Result<()> {
+    let mut stdout = std::io::stdout().lock();
+    stdout.write_all(report.as_bytes())?;
+    // テキストは末尾を改行で終える
+    if let Report::Text(text) = report {
+        if !text.ends_with('\n') {
+            stdout.write_all(b"\n")?;
+        }
+    }
+    stdout.flush()
+}
+
+/// 引数の誤りは1行のメッセージにまとめる
+/// ヘルプ・バージョンの表示はclapに任せて終了する
+fn usage_error(error: clap::Error) -> Result<Opts> {
+    if !error.use_stderr() {
+        error.exit()
     }
+    let message = error.to_string();
+    let first_line = message.lines().next().unwrap_or_default();
+    Err(AppError::Usage(first_line.trim_start_matches("error: ").to_string()).into())
 }
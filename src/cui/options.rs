@@ -1,4 +1,20 @@
+use super::clipboard::ClipboardBackend;
+use crate::adapter::presenter::ReportFormat;
+use crate::domain::model::aggregated_by::LocationLevel;
+use crate::domain::model::{
+    Bucketing, ChargeRounding, DayBoundary, DurationUnit, Granularity, Locale, PeriodDefinition,
+    PeriodSelection, Rounding, RoundingScope, WeekStart, Zone,
+};
 use clap::Parser;
+use regex::Regex;
+use std::ffi::OsString;
+use std::path::PathBuf;
+use std::sync::LazyLock;
+
+/// `-3d` のようなN日前の表現。フラグと同じくハイフンで始まる
+static DAYS_AGO: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^-\d+d$").unwrap());
+/// パースの間だけ、N日前の表現の先頭のハイフンを置き換える文字
+const ESCAPED_HYPHEN: char = '\u{E000}';
 
 /// コマンドライン引数のパース用構造体
 #[derive(Debug, Clone, Parser)]
@@ -10,10 +26,49 @@ use clap::Parser;
 )]
 #[clap(propagate_version = true)]
 pub(crate) struct Opts {
-    #[clap(help = "始端日をYYYY/MM/DD形式で指定する")]
+    #[clap(
+        help = "始端日をYYYY/MM/DD形式で指定する。today, yesterday, -3d, this-week, last-week, this-month, last-month, 2023-W14, 2023/04, 2023/04/01..2023/04/15 のような表現も指定でき、期間を表す場合はその全体を対象とする"
+    )]
     start_date: Option<String>,
-    #[clap(help = "終端日をYYYY/MM/DD形式で指定する")]
+    #[clap(
+        help = "終端日をYYYY/MM/DD形式で指定する。始端日と同じ表現を指定でき、期間を表す場合はその末日を終端日とする"
+    )]
     end_date: Option<String>,
+    #[clap(
+        long,
+        value_parser,
+        value_name = "NAME:PERIOD",
+        conflicts_with_all = &["start-date", "end-date"],
+        help = "始端日・終端日の代わりに、--period-definition で定義した請求期間を \"client-a:current\" のように指定する。期間は current（今日を含む期間）, previous（その前の期間）, YYYY-MM から選ぶ"
+    )]
+    period: Option<PeriodSelection>,
+    #[clap(
+        long,
+        value_parser,
+        value_name = "NAME=RULE",
+        env = "APP_PERIOD_DEFINITIONS",
+        value_delimiter = ',',
+        help = "請求期間の名前と区切り方を、締め日で区切る場合は \"client-a=closes:20\" 、週数で区切った会計月の場合は \"client-b=4-4-5:2023/04/03\" （週数の並びと会計年度の初日）のように指定する。複数回指定でき、環境変数ではカンマ区切りで指定する"
+    )]
+    period_definition: Vec<PeriodDefinition>,
+    #[clap(
+        long,
+        value_parser,
+        value_name = "HH:MM",
+        env = "APP_DAY_BOUNDARY",
+        default_value = "05:00",
+        help = "1日の区切りとなる時刻を、タイムゾーンの現地時刻のHH:MM形式で指定する。区切りより前の作業は前日の作業として扱う"
+    )]
+    day_boundary: DayBoundary,
+    #[clap(
+        long,
+        value_parser,
+        value_name = "IANA_NAME",
+        env = "APP_TIME_ZONE",
+        default_value = "Asia/Tokyo",
+        help = "対象日付の判定と日時の表記に用いるタイムゾーンを Europe/Berlin のようなIANA名で指定する"
+    )]
+    time_zone: Zone,
     #[clap(
         short = 'c',
         long,
@@ -26,51 +81,396 @@ pub(crate) struct Opts {
         help = "デフォルトでは対象期間単位で集計する。このフラグを指定すると、日単位で集計する"
     )]
     by_daily: bool,
+    #[clap(
+        long,
+        value_enum,
+        value_name = "LEVEL",
+        conflicts_with_all = &["by-charge", "by-daily", "pivot", "granularity", "check"],
+        help = "このオプションを指定すると、タスクの所属するスペース毎（space）、フォルダ毎（folder）、あるいはスペース > フォルダ > リスト > タスクの階層毎（tree）に集計する。tree の場合は階層毎の小計の行を挟む"
+    )]
+    by_location: Option<LocationLevel>,
+    #[clap(
+        long,
+        value_enum,
+        conflicts_with = "pivot",
+        help = "このオプションを指定すると、日単位の代わりに週・月・四半期単位で集計する"
+    )]
+    granularity: Option<Granularity>,
+    #[clap(
+        long,
+        value_enum,
+        env = "APP_WEEK_START",
+        default_value = "mon",
+        help = "週単位で集計する場合と、this-week, last-week, 2023-W14 のような週を表す日付の表現の、週の始まりの曜日を指定する"
+    )]
+    week_start: WeekStart,
+    #[clap(
+        short = 'p',
+        long,
+        conflicts_with = "template",
+        help = "このフラグを指定すると、日単位の集計結果を行×日付の表（ピボット）形式で出力する"
+    )]
+    pivot: bool,
+    #[clap(
+        short = 'g',
+        long,
+        conflicts_with_all = &["pivot", "template"],
+        help = "このフラグを指定すると、日単位の集計結果に日付毎（タスク単位の場合はチャージコード毎も）の小計の行を挟む"
+    )]
+    subtotal: bool,
     #[clap(
         short = 'a',
         long,
         help = "デフォルトでは対象期間内のタイムエントリーのみを表示する。このフラグを指定すると、最終更新日時が対象期間内であるタスク情報も表示する"
     )]
     all: bool,
+    #[clap(
+        long,
+        help = "このフラグを指定すると、1日の区切りを跨ぐタイムエントリーを区切り毎に分割して所要時間を按分し、対象期間外の部分を切り落とす"
+    )]
+    split_entries: bool,
+    #[clap(
+        long,
+        value_name = "PATH",
+        env = "APP_HOLIDAYS_FILE",
+        help = "会社独自の休日を記述したファイルを指定する。1行に1日ずつ YYYY/MM/DD 形式で記述したテキストか、拡張子が .ics のiCalendar形式とする。土日・国民の祝日とこの休日を除いた日を稼働日とする"
+    )]
+    holidays: Option<PathBuf>,
+    #[clap(
+        long,
+        value_name = "PATH",
+        env = "APP_CHARGE_RULES_FILE",
+        help = "チャージコードを振り分ける規則を記述したJSONファイルを指定する。スペース・フォルダ・リストの名前かID、タスク名の正規表現、タグ、ステータスで合致を判定し、最初に合致した規則のチャージコードを用いる。指定しない場合はリスト名をチャージコードとする"
+    )]
+    charge_rules: Option<PathBuf>,
+    #[clap(
+        long,
+        value_name = "ID_OR_NAME",
+        env = "APP_CHARGE_FIELD",
+        help = "チャージコードを持つClickUpのカスタムフィールドをIDか名前で指定する。ドロップダウン・ラベルは選択肢の表示名を用いる。値を持たないタスクは、規則ファイルあるいはリスト名でチャージコードを決める"
+    )]
+    charge_field: Option<String>,
+    #[clap(
+        long,
+        conflicts_with_all = &["pivot", "granularity", "subtotal"],
+        help = "このフラグを指定すると、集計結果の代わりに、対象期間内の稼働日のうちタイムエントリーが無い日、あるいは所要時間の合計が --min-hours に満たない日を出力する"
+    )]
+    check: bool,
+    #[clap(
+        long,
+        value_name = "HOURS",
+        env = "APP_MIN_HOURS",
+        default_value = "0",
+        value_parser = parse_non_negative_number,
+        help = "稼働日を確認する場合の、1日あたりの最低時間数を指定する。0の場合はタイムエントリーの無い日のみを出力する"
+    )]
+    min_hours: f64,
     #[clap(
         short = 's',
         long,
         help = "このフラグを指定すると、結果をクリップボードにセットする"
     )]
     set_clipboard: bool,
+    #[clap(
+        long,
+        value_enum,
+        help = "クリップボードへの書き込み方法を指定する。デフォルトでは実行中の環境で利用できるものを自動で選ぶ"
+    )]
+    clipboard_backend: Option<ClipboardBackend>,
+    #[clap(
+        short = 'f',
+        long,
+        value_enum,
+        default_value = "tsv",
+        help = "出力形式を指定する。デフォルトではTSV形式で出力する"
+    )]
+    format: ReportFormat,
+    #[clap(
+        long,
+        help = "CSV形式で出力する場合、先頭にUTF-8のBOMを付与する（Excelで開く場合に指定する）"
+    )]
+    bom: bool,
+    #[clap(long, help = "CSV形式で出力する場合、ヘッダ行を出力しない")]
+    no_header: bool,
+    #[clap(long, help = "CSV形式で出力する場合、総合計の行を出力しない")]
+    no_total: bool,
+    #[clap(
+        short = 'o',
+        long,
+        help = "このオプションを指定すると、結果を標準出力の代わりに指定したパスのファイルに書き込む"
+    )]
+    output: Option<PathBuf>,
+    #[clap(
+        short = 't',
+        long,
+        conflicts_with_all = &["format", "bom", "no-header", "no-total"],
+        help = "このオプションを指定すると、出力形式の代わりに指定したテンプレートファイルで出力する"
+    )]
+    template: Option<PathBuf>,
+    #[clap(
+        long,
+        value_parser,
+        value_name = "MODE:MINUTES",
+        conflicts_with = "pivot",
+        help = "所要時間をN分単位で丸める。丸め方は up（切り上げ）, down（切り捨て）, nearest（四捨五入）から選び、\"up:15\" のように指定する"
+    )]
+    rounding: Option<Rounding>,
+    #[clap(
+        long,
+        value_parser,
+        value_name = "CHARGE=MODE:MINUTES",
+        conflicts_with = "pivot",
+        help = "チャージコード毎の丸め設定を \"charge-a=up:30\" のように指定する。表示名ではなくチャージコードで指定する。複数回指定でき、--rounding の設定より優先する"
+    )]
+    charge_rounding: Vec<ChargeRounding>,
+    #[clap(
+        long,
+        value_enum,
+        default_value = "row",
+        help = "丸めを適用する単位を指定する。デフォルトでは集計結果の行毎に丸める"
+    )]
+    rounding_scope: RoundingScope,
+    #[clap(
+        short = 'u',
+        long,
+        value_enum,
+        default_value = "hms",
+        help = "所要時間の単位を指定する。デフォルトではhh:mi:ss表記で出力する"
+    )]
+    unit: DurationUnit,
+    #[clap(
+        long,
+        env = "APP_DURATION_PRECISION",
+        default_value = "2",
+        help = "所要時間を小数で出力する場合の、小数点以下の桁数を指定する"
+    )]
+    precision: usize,
+    #[clap(
+        long,
+        env = "APP_HOURS_PER_DAY",
+        default_value = "8",
+        value_parser = parse_positive_number,
+        help = "人日・人月に換算する場合の、1日あたりの時間数を指定する"
+    )]
+    hours_per_day: f64,
+    #[clap(
+        long,
+        env = "APP_DAYS_PER_MONTH",
+        default_value = "20",
+        value_parser = parse_positive_number,
+        help = "人月に換算する場合の、1ヶ月あたりの日数を指定する"
+    )]
+    days_per_month: f64,
+    #[clap(
+        long,
+        value_enum,
+        env = "APP_LOCALE",
+        default_value = "ja",
+        help = "見出し・列名・合計行・日付の表記に用いる言語を指定する。TSV・CSV出力の列名は言語に依らない"
+    )]
+    locale: Locale,
+}
+
+/// 換算に用いる時間数・日数は正の数とする
+fn parse_positive_number(s: &str) -> Result<f64, String> {
+    match s.parse::<f64>() {
+        Ok(number) if number > 0.0 && number.is_finite() => Ok(number),
+        _ => Err(format!("must be a positive number: {}", s)),
+    }
+}
+
+/// 最低時間数は0以上の数とする
+fn parse_non_negative_number(s: &str) -> Result<f64, String> {
+    match s.parse::<f64>() {
+        Ok(number) if number >= 0.0 && number.is_finite() => Ok(number),
+        _ => Err(format!("must be a non-negative number: {}", s)),
+    }
 }
 
 impl Opts {
+    /// コマンドライン引数をパースする
+    /// 位置引数に allow_hyphen_values を指定すると後続のフラグまで日付として受け付けてしまうので、
+    /// N日前の表現に限って先頭のハイフンを置き換えてからパースし、パース後に戻す
+    pub(crate) fn try_parse_args<I, T>(args: I) -> Result<Self, clap::Error>
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<OsString>,
+    {
+        let args = args.into_iter().map(|arg| {
+            let arg = arg.into();
+            match arg.to_str() {
+                Some(s) if DAYS_AGO.is_match(s) => {
+                    s.replacen('-', &ESCAPED_HYPHEN.to_string(), 1).into()
+                }
+                _ => arg,
+            }
+        });
+        let mut opts = Self::try_parse_from(args)?;
+        let restore = |date: String| date.replacen(ESCAPED_HYPHEN, "-", 1);
+        opts.start_date = opts.start_date.map(restore);
+        opts.end_date = opts.end_date.map(restore);
+        Ok(opts)
+    }
+
+    /// 出力形式が対応していない集計方法のオプション
+    /// 稼働日の確認結果・階層毎の集計結果・ピボット形式は、集計単位毎の表を出力する形式でのみ出力できる
+    pub(crate) fn unsupported_by_format(&self) -> Option<&'static str> {
+        let format = self.format();
+        if !format.aggregates_all_views() && !format.exports_time_entries() {
+            return None;
+        }
+        if self.check {
+            Some("--check")
+        } else if self.by_location.is_some() {
+            Some("--by-location")
+        } else if self.pivot {
+            Some("--pivot")
+        } else {
+            None
+        }
+    }
+
     pub(crate) fn start_date(&self) -> &Option<String> {
         &self.start_date
     }
     pub(crate) fn end_date(&self) -> &Option<String> {
         &self.end_date
     }
+    pub(crate) fn period(&self) -> &Option<PeriodSelection> {
+        &self.period
+    }
+    pub(crate) fn period_definitions(&self) -> &[PeriodDefinition] {
+        &self.period_definition
+    }
+    pub(crate) fn day_boundary(&self) -> DayBoundary {
+        self.day_boundary
+    }
+    pub(crate) fn time_zone(&self) -> Zone {
+        self.time_zone
+    }
     pub(crate) fn by_charge(&self) -> bool {
         self.by_charge
     }
+    pub(crate) fn by_location(&self) -> Option<LocationLevel> {
+        self.by_location
+    }
+    /// 週・月・四半期単位で集計する場合も、日単位の集計結果と同じ形式で出力する
     pub(crate) fn by_daily(&self) -> bool {
-        self.by_daily
+        self.by_daily || self.granularity.is_some()
+    }
+    /// 週・月・四半期単位の指定がなければ日単位とする
+    pub(crate) fn bucketing(&self) -> Bucketing {
+        Bucketing::new(
+            self.granularity.unwrap_or(Granularity::Daily),
+            self.week_start,
+        )
+    }
+    pub(crate) fn pivot(&self) -> bool {
+        self.pivot
+    }
+    /// 階層毎に集計する場合は、指定が無くても階層毎の小計の行を挟む
+    pub(crate) fn subtotal(&self) -> bool {
+        self.subtotal || self.by_location == Some(LocationLevel::Tree)
     }
     pub(crate) fn all(&self) -> bool {
         self.all
     }
+    pub(crate) fn split_entries(&self) -> bool {
+        self.split_entries
+    }
+    pub(crate) fn holidays(&self) -> &Option<PathBuf> {
+        &self.holidays
+    }
+    pub(crate) fn charge_rules(&self) -> &Option<PathBuf> {
+        &self.charge_rules
+    }
+    pub(crate) fn charge_field(&self) -> &Option<String> {
+        &self.charge_field
+    }
+    pub(crate) fn check(&self) -> bool {
+        self.check
+    }
+    pub(crate) fn min_hours(&self) -> f64 {
+        self.min_hours
+    }
     pub(crate) fn set_clipboard(&self) -> bool {
         self.set_clipboard
     }
+    pub(crate) fn clipboard_backend(&self) -> Option<ClipboardBackend> {
+        self.clipboard_backend
+    }
+    pub(crate) fn format(&self) -> ReportFormat {
+        self.format
+    }
+    pub(crate) fn bom(&self) -> bool {
+        self.bom
+    }
+    pub(crate) fn no_header(&self) -> bool {
+        self.no_header
+    }
+    pub(crate) fn no_total(&self) -> bool {
+        self.no_total
+    }
+    pub(crate) fn output(&self) -> &Option<PathBuf> {
+        &self.output
+    }
+    pub(crate) fn template(&self) -> &Option<PathBuf> {
+        &self.template
+    }
+    pub(crate) fn rounding(&self) -> Option<Rounding> {
+        self.rounding
+    }
+    pub(crate) fn charge_rounding(&self) -> &[ChargeRounding] {
+        &self.charge_rounding
+    }
+    pub(crate) fn rounding_scope(&self) -> RoundingScope {
+        self.rounding_scope
+    }
+    pub(crate) fn unit(&self) -> DurationUnit {
+        self.unit
+    }
+    pub(crate) fn precision(&self) -> usize {
+        self.precision
+    }
+    pub(crate) fn hours_per_day(&self) -> f64 {
+        self.hours_per_day
+    }
+    pub(crate) fn days_per_month(&self) -> f64 {
+        self.days_per_month
+    }
+    pub(crate) fn locale(&self) -> Locale {
+        self.locale
+    }
 }
 
 pub(super) enum AggregateCondition {
+    TimeEntries,
+    All,
+    WorkingDayCheck,
+    Location(LocationLevel),
     TaskAndTotalPeriod,
     ChargeAndTotalPeriod,
     TaskAndDaily,
     ChargeAndDaily,
+    TaskAndDatePivot,
+    ChargeAndDatePivot,
 }
 
 impl From<Opts> for AggregateCondition {
     fn from(opts: Opts) -> Self {
-        if !opts.by_charge() && !opts.by_daily() {
+        if opts.format().exports_time_entries() {
+            AggregateCondition::TimeEntries
+        } else if opts.format().aggregates_all_views() {
+            AggregateCondition::All
+        } else if opts.check() {
+            AggregateCondition::WorkingDayCheck
+        } else if let Some(level) = opts.by_location() {
+            AggregateCondition::Location(level)
+        } else if opts.pivot() && !opts.by_charge() {
+            AggregateCondition::TaskAndDatePivot
+        } else if opts.pivot() && opts.by_charge() {
+            AggregateCondition::ChargeAndDatePivot
+        } else if !opts.by_charge() && !opts.by_daily() {
             AggregateCondition::TaskAndTotalPeriod
         } else if opts.by_charge() && !opts.by_daily() {
             AggregateCondition::ChargeAndTotalPeriod
@@ -83,3 +483,37 @@ impl From<Opts> for AggregateCondition {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ハイフンで始まる日付の表現をオプションと区別する() {
+        let opts = Opts::try_parse_args(["task-reporter", "-c", "-3d", "-1d", "-d"]).unwrap();
+        assert_eq!(opts.start_date.as_deref(), Some("-3d"));
+        assert_eq!(opts.end_date.as_deref(), Some("-1d"));
+        assert!(opts.by_charge);
+        assert!(opts.by_daily);
+    }
+
+    #[test]
+    fn 未知のオプションは日付の表現として受け付けない() {
+        let err = Opts::try_parse_args(["task-reporter", "--bogus"]).unwrap_err();
+        assert_eq!(err.kind(), clap::ErrorKind::UnknownArgument);
+
+        let err = Opts::try_parse_args(["task-reporter", "-c", "-3d", "--bogus"]).unwrap_err();
+        assert_eq!(err.kind(), clap::ErrorKind::UnknownArgument);
+    }
+
+    #[test]
+    fn 集計単位毎の表を出力しない形式ではピボット形式を受け付けない() {
+        for format in ["html", "xlsx", "ics"] {
+            let opts =
+                Opts::try_parse_args(["task-reporter", "--pivot", "--format", format]).unwrap();
+            assert_eq!(opts.unsupported_by_format(), Some("--pivot"));
+        }
+        let opts = Opts::try_parse_args(["task-reporter", "--pivot", "--format", "csv"]).unwrap();
+        assert_eq!(opts.unsupported_by_format(), None);
+    }
+}
@@ -0,0 +1,67 @@
+use super::{
+    ChargeAndDailyRecords, ChargeAndTotalPeriodRecords, TaskAndDailyRecords,
+    TaskAndTotalPeriodRecords,
+};
+use crate::domain::model::{DateRange, RoundingPolicy, TaskRecords, WorkingCalendar};
+
+/// 同じ集計元データから、全ての集計単位で集計した結果
+/// 複数の集計結果を1つのファイルにまとめて出力する場合に利用する
+#[derive(Debug, Clone, Eq, PartialEq, PartialOrd, Ord)]
+pub(crate) struct AllAggregatedRecords {
+    date_range: DateRange,
+    task_and_total_period: TaskAndTotalPeriodRecords,
+    task_and_daily: TaskAndDailyRecords,
+    charge_and_total_period: ChargeAndTotalPeriodRecords,
+    charge_and_daily: ChargeAndDailyRecords,
+}
+
+impl AllAggregatedRecords {
+    pub(crate) fn new(date_range: DateRange, task_records: TaskRecords) -> Self {
+        Self {
+            task_and_total_period: TaskAndTotalPeriodRecords::new(
+                date_range.clone(),
+                task_records.clone(),
+            ),
+            task_and_daily: TaskAndDailyRecords::new(date_range.clone(), task_records.clone()),
+            charge_and_total_period: ChargeAndTotalPeriodRecords::new(
+                date_range.clone(),
+                task_records.clone(),
+            ),
+            charge_and_daily: ChargeAndDailyRecords::new(date_range.clone(), task_records),
+            date_range,
+        }
+    }
+    /// 日毎の集計単位にのみ、作業の無かった稼働日の行を加える
+    pub(crate) fn fill_working_days(self, calendar: &WorkingCalendar) -> Self {
+        Self {
+            task_and_daily: self.task_and_daily.fill_working_days(calendar),
+            charge_and_daily: self.charge_and_daily.fill_working_days(calendar),
+            ..self
+        }
+    }
+    /// 全ての集計単位で、行毎に丸めた所要時間を求める
+    pub(crate) fn round(self, policy: &RoundingPolicy) -> Self {
+        Self {
+            task_and_total_period: self.task_and_total_period.round(policy),
+            task_and_daily: self.task_and_daily.round(policy),
+            charge_and_total_period: self.charge_and_total_period.round(policy),
+            charge_and_daily: self.charge_and_daily.round(policy),
+            date_range: self.date_range,
+        }
+    }
+    pub(crate) fn date_range(&self) -> &DateRange {
+        &self.date_range
+    }
+    pub(crate) fn task_and_total_period(&self) -> &TaskAndTotalPeriodRecords {
+        &self.task_and_total_period
+    }
+    pub(crate) fn task_and_daily(&self) -> &TaskAndDailyRecords {
+        &self.task_and_daily
+    }
+    pub(crate) fn charge_and_total_period(&self) -> &ChargeAndTotalPeriodRecords {
+        &self.charge_and_total_period
+    }
+    pub(crate) fn charge_and_daily(&self) -> &ChargeAndDailyRecords {
+        &self.charge_and_daily
+    }
+}
@@ -1,27 +1,73 @@
 use std::collections::HashMap;
 
-use crate::domain::model::{AsVec, DateRange, TaskDuration, TaskRecord, TaskRecords};
+use super::{date_column, with_rounded_column, AggregatedRecord, AggregatedRecords, Column};
+use crate::domain::model::{
+    AsVec, Bucketing, DateRange, Granularity, RoundingPolicy, TaskDuration, TaskRecord,
+    TaskRecords, WorkingCalendar,
+};
 use chrono::{DateTime, FixedOffset, NaiveDate};
-use derive_new::new;
 
-#[derive(new, Debug, Clone, Eq, PartialEq, PartialOrd, Ord)]
+#[derive(Debug, Clone, Eq, PartialEq, PartialOrd, Ord)]
 pub(crate) struct ChargeAndDailyRecord {
     target_date: NaiveDate,
-    updated_at: DateTime<FixedOffset>,
+    /// 作業の無かった稼働日の行はNone
+    charge: Option<DailyCharge>,
     total_duration: TaskDuration,
+    rounded_duration: TaskDuration,
+}
+
+/// 対象日に作業したチャージコード
+#[derive(Debug, Clone, Eq, PartialEq, PartialOrd, Ord)]
+struct DailyCharge {
+    updated_at: DateTime<FixedOffset>,
+    charge_code: String,
     charge_name: String,
 }
 
-impl std::fmt::Display for ChargeAndDailyRecord {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(
-            f,
-            "{}\t{}\t{}\t{}",
-            self.target_date.format("%Y/%m/%d"),
-            self.updated_at.format("%Y/%m/%d %H:%M:%S"),
-            self.total_duration,
-            self.charge_name,
-        )
+impl ChargeAndDailyRecord {
+    /// 作業の無かった稼働日の、所要時間0の行
+    fn no_work_day(target_date: NaiveDate) -> Self {
+        Self {
+            target_date,
+            charge: None,
+            total_duration: TaskDuration::new(),
+            rounded_duration: TaskDuration::new(),
+        }
+    }
+}
+
+impl AggregatedRecord for ChargeAndDailyRecord {
+    fn target_date(&self) -> Option<NaiveDate> {
+        Some(self.target_date)
+    }
+    fn updated_at(&self) -> Option<DateTime<FixedOffset>> {
+        self.charge.as_ref().map(|charge| charge.updated_at)
+    }
+    fn total_duration(&self) -> &TaskDuration {
+        &self.total_duration
+    }
+    fn rounded_duration(&self) -> &TaskDuration {
+        &self.rounded_duration
+    }
+    fn charge_name(&self) -> Option<&str> {
+        self.charge
+            .as_ref()
+            .map(|charge| charge.charge_name.as_str())
+    }
+    fn task_id(&self) -> Option<&str> {
+        None
+    }
+    fn task_name(&self) -> Option<&str> {
+        None
+    }
+    fn task_url(&self) -> Option<&str> {
+        None
+    }
+    fn task_status(&self) -> Option<&str> {
+        None
+    }
+    fn is_no_work_day(&self) -> bool {
+        self.charge.is_none()
     }
 }
 
@@ -29,20 +75,35 @@ impl std::fmt::Display for ChargeAndDailyRecord {
 pub(crate) struct ChargeAndDailyRecords {
     date_range: DateRange,
     records: Vec<ChargeAndDailyRecord>,
+    rounded: bool,
+    bucketing: Bucketing,
 }
 
 impl ChargeAndDailyRecords {
     pub(crate) fn new(date_range: DateRange, task_records: TaskRecords) -> Self {
+        Self::by_bucket(date_range, task_records, Bucketing::default())
+    }
+
+    /// 対象日を週・月・四半期の区切りにまとめて集計する
+    /// 集計結果の対象日は、区切りの初日とする
+    pub(crate) fn by_bucket(
+        date_range: DateRange,
+        task_records: TaskRecords,
+        bucketing: Bucketing,
+    ) -> Self {
         let mut map = HashMap::<(String, chrono::NaiveDate), Vec<TaskRecord>>::new();
 
         for task_record in task_records.into_inner() {
-            let key = (task_record.charge_name.clone(), task_record.target_date);
+            let key = (
+                task_record.charge_code.clone(),
+                bucketing.first_date_of(task_record.target_date),
+            );
             map.entry(key).or_default().push(task_record);
         }
 
         let mut aggregated_records = Vec::new();
 
-        for ((charge_name, target_date), records) in map {
+        for ((charge_code, target_date), records) in map {
             let latest_record = records
                 .iter()
                 .max_by_key(|record| record.updated_at)
@@ -52,63 +113,110 @@ impl ChargeAndDailyRecords {
                 .map(|record| record.duration.clone())
                 .reduce(|total, duration| total.add(duration))
                 .unwrap();
-            aggregated_records.push(ChargeAndDailyRecord::new(
+            let rounded_duration = records
+                .iter()
+                .map(|record| record.rounded_duration.clone())
+                .reduce(|total, duration| total.add(duration))
+                .unwrap();
+            aggregated_records.push(ChargeAndDailyRecord {
                 target_date,
-                latest_record.updated_at,
+                charge: Some(DailyCharge {
+                    updated_at: latest_record.updated_at,
+                    charge_code,
+                    charge_name: latest_record.charge_name.clone(),
+                }),
                 total_duration,
-                charge_name,
-            ))
+                rounded_duration,
+            })
         }
         Self {
             date_range,
             records: aggregated_records,
+            rounded: false,
+            bucketing,
+        }
+    }
+
+    /// 対象期間内の稼働日のうち、作業の無かった日に対象日のみの行を加える
+    /// 日毎に集計する場合のみ加え、週・月・四半期毎の場合は何もしない
+    pub(crate) fn fill_working_days(mut self, calendar: &WorkingCalendar) -> Self {
+        if !self.bucketing.is_daily() {
+            return self;
         }
+        let no_work_days = calendar
+            .working_days(&self.date_range)
+            .into_iter()
+            .filter(|date| !self.records.iter().any(|r| r.target_date == *date))
+            .collect::<Vec<_>>();
+        self.records.extend(
+            no_work_days
+                .into_iter()
+                .map(ChargeAndDailyRecord::no_work_day),
+        );
+        self
+    }
+
+    /// 丸め方針に従って、行毎に丸めた所要時間を求める
+    pub(crate) fn round(mut self, policy: &RoundingPolicy) -> Self {
+        for record in self.records.iter_mut() {
+            let Some(charge) = &record.charge else {
+                continue;
+            };
+            record.rounded_duration = policy.apply_to_row(
+                &charge.charge_code,
+                &record.total_duration,
+                &record.rounded_duration,
+            );
+        }
+        self.rounded = true;
+        self
     }
 }
 
-impl std::fmt::Display for ChargeAndDailyRecords {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let title = if self.date_range.is_same_date() {
-            format!("集計対象日付：{}", self.date_range.start_date_str())
-        } else {
-            format!(
-                "集計対象期間：{} ～ {}",
-                self.date_range.start_date_str(),
-                self.date_range.end_target_date_str()
-            )
-        };
+impl AggregatedRecords for ChargeAndDailyRecords {
+    type Record = ChargeAndDailyRecord;
 
+    fn aggregated_by(&self) -> &'static str {
+        match self.bucketing.granularity() {
+            Granularity::Daily => "charge_and_daily",
+            Granularity::Weekly => "charge_and_weekly",
+            Granularity::Monthly => "charge_and_monthly",
+            Granularity::Quarterly => "charge_and_quarterly",
+        }
+    }
+    fn date_range(&self) -> &DateRange {
+        &self.date_range
+    }
+    fn is_rounded(&self) -> bool {
+        self.rounded
+    }
+    fn columns(&self) -> Vec<Column> {
+        with_rounded_column(
+            vec![
+                date_column(self.bucketing),
+                Column::UpdatedAt,
+                Column::TotalDuration,
+                Column::ChargeName,
+            ],
+            self.rounded,
+        )
+    }
+    fn records(&self) -> Vec<Self::Record> {
         // 並び替える
         let mut records = self.records.clone();
         records.sort_by_key(|record| {
             (
                 record.target_date,
-                record.charge_name.clone(),
+                record
+                    .charge
+                    .as_ref()
+                    .map(|charge| charge.charge_code.clone()),
                 record.total_duration.clone(),
             )
         });
-
-        let mut tsv = records.iter().map(|record| record.to_string()).fold(
-            vec![vec![
-                "target_date".to_string(),
-                "updated_at".to_string(),
-                "total_duration".to_string(),
-                "charge_name".to_string(),
-            ]
-            .join("\t")],
-            |mut records, record| {
-                records.push(record);
-                records
-            },
-        );
-        let grand_total = format!(
-            "総合計\t{}",
-            records
-                .into_iter()
-                .map(|record| record.total_duration)
-                .fold(TaskDuration::new(), |accum, duration| accum.add(duration))
-        );
-        tsv.push(grand_total);
-        write!(f, "\n{}\n[\n{}\n]", title, tsv.join("\n"))
+        records
+    }
+    fn group_by(&self) -> Vec<Column> {
+        vec![date_column(self.bucketing)]
     }
 }
@@ -1,26 +1,49 @@
 use std::collections::HashMap;
 
-use chrono::{DateTime, FixedOffset};
+use chrono::{DateTime, FixedOffset, NaiveDate};
 
-use crate::domain::model::{AsVec, DateRange, TaskDuration, TaskRecord, TaskRecords};
+use super::{with_rounded_column, AggregatedRecord, AggregatedRecords, Column};
+use crate::domain::model::{
+    AsVec, DateRange, RoundingPolicy, TaskDuration, TaskRecord, TaskRecords,
+};
 use derive_new::new;
 
 #[derive(new, Debug, Clone, Eq, PartialEq, PartialOrd, Ord)]
 pub(crate) struct ChargeAndTotalPeriodRecord {
     updated_at: DateTime<FixedOffset>,
     total_duration: TaskDuration,
+    rounded_duration: TaskDuration,
+    charge_code: String,
     charge_name: String,
 }
 
-impl std::fmt::Display for ChargeAndTotalPeriodRecord {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(
-            f,
-            "{}\t{}\t{}",
-            self.updated_at.format("%Y/%m/%d %H:%M:%S"),
-            self.total_duration,
-            self.charge_name,
-        )
+impl AggregatedRecord for ChargeAndTotalPeriodRecord {
+    fn target_date(&self) -> Option<NaiveDate> {
+        None
+    }
+    fn updated_at(&self) -> Option<DateTime<FixedOffset>> {
+        Some(self.updated_at)
+    }
+    fn total_duration(&self) -> &TaskDuration {
+        &self.total_duration
+    }
+    fn rounded_duration(&self) -> &TaskDuration {
+        &self.rounded_duration
+    }
+    fn charge_name(&self) -> Option<&str> {
+        Some(&self.charge_name)
+    }
+    fn task_id(&self) -> Option<&str> {
+        None
+    }
+    fn task_name(&self) -> Option<&str> {
+        None
+    }
+    fn task_url(&self) -> Option<&str> {
+        None
+    }
+    fn task_status(&self) -> Option<&str> {
+        None
     }
 }
 
@@ -28,6 +51,7 @@ impl std::fmt::Display for ChargeAndTotalPeriodRecord {
 pub(crate) struct ChargeAndTotalPeriodRecords {
     date_range: DateRange,
     records: Vec<ChargeAndTotalPeriodRecord>,
+    rounded: bool,
 }
 
 impl ChargeAndTotalPeriodRecords {
@@ -35,13 +59,13 @@ impl ChargeAndTotalPeriodRecords {
         let mut map = HashMap::<String, Vec<TaskRecord>>::new();
 
         for task_record in task_records.into_inner() {
-            let key = task_record.charge_name.clone();
+            let key = task_record.charge_code.clone();
             map.entry(key).or_default().push(task_record);
         }
 
         let mut aggregated_records = Vec::new();
 
-        for (charge_name, records) in map {
+        for (charge_code, records) in map {
             let latest_record = records
                 .iter()
                 .max_by_key(|record| record.updated_at)
@@ -51,55 +75,62 @@ impl ChargeAndTotalPeriodRecords {
                 .map(|record| record.duration.clone())
                 .reduce(|total, duration| total.add(duration))
                 .unwrap();
+            let rounded_duration = records
+                .iter()
+                .map(|record| record.rounded_duration.clone())
+                .reduce(|total, duration| total.add(duration))
+                .unwrap();
             aggregated_records.push(ChargeAndTotalPeriodRecord::new(
                 latest_record.updated_at,
                 total_duration,
-                charge_name,
+                rounded_duration,
+                charge_code,
+                latest_record.charge_name.clone(),
             ))
         }
         Self {
             date_range,
             records: aggregated_records,
+            rounded: false,
         }
     }
+
+    /// 丸め方針に従って、行毎に丸めた所要時間を求める
+    pub(crate) fn round(mut self, policy: &RoundingPolicy) -> Self {
+        for record in self.records.iter_mut() {
+            record.rounded_duration = policy.apply_to_row(
+                &record.charge_code,
+                &record.total_duration,
+                &record.rounded_duration,
+            );
+        }
+        self.rounded = true;
+        self
+    }
 }
 
-impl std::fmt::Display for ChargeAndTotalPeriodRecords {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let title = if self.date_range.is_same_date() {
-            format!("集計対象日付：{}", self.date_range.start_date_str())
-        } else {
-            format!(
-                "集計対象期間：{} ～ {}",
-                self.date_range.start_date_str(),
-                self.date_range.end_target_date_str()
-            )
-        };
+impl AggregatedRecords for ChargeAndTotalPeriodRecords {
+    type Record = ChargeAndTotalPeriodRecord;
 
+    fn aggregated_by(&self) -> &'static str {
+        "charge_and_total_period"
+    }
+    fn date_range(&self) -> &DateRange {
+        &self.date_range
+    }
+    fn is_rounded(&self) -> bool {
+        self.rounded
+    }
+    fn columns(&self) -> Vec<Column> {
+        with_rounded_column(
+            vec![Column::UpdatedAt, Column::TotalDuration, Column::ChargeName],
+            self.rounded,
+        )
+    }
+    fn records(&self) -> Vec<Self::Record> {
         // 並び替える
         let mut records = self.records.clone();
-        records.sort_by_key(|record| (record.charge_name.clone(), record.total_duration.clone()));
-
-        let mut tsv = records.iter().map(|record| record.to_string()).fold(
-            vec![vec![
-                "updated_at".to_string(),
-                "total_duration".to_string(),
-                "charge_name".to_string(),
-            ]
-            .join("\t")],
-            |mut records, record| {
-                records.push(record);
-                records
-            },
-        );
-        let grand_total = format!(
-            "総合計\t{}",
-            records
-                .into_iter()
-                .map(|record| record.total_duration)
-                .fold(TaskDuration::new(), |accum, duration| accum.add(duration))
-        );
-        tsv.push(grand_total);
-        write!(f, "\n{}\n[\n{}\n]", title, tsv.join("\n"))
+        records.sort_by_key(|record| (record.charge_code.clone(), record.total_duration.clone()));
+        records
     }
 }
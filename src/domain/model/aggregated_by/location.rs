@@ -0,0 +1,328 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, FixedOffset, NaiveDate};
+
+use super::{with_rounded_column, AggregatedRecord, AggregatedRecords, Column};
+use crate::domain::model::{
+    AsVec, DateRange, RoundingPolicy, TaskDuration, TaskLocation, TaskRecord, TaskRecords,
+};
+
+/// スペース・フォルダ・リストの階層のどこまでで集計するか
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, clap::ValueEnum)]
+pub(crate) enum LocationLevel {
+    /// スペース毎
+    Space,
+    /// フォルダ毎
+    Folder,
+    /// スペース > フォルダ > リスト > タスクの階層毎
+    Tree,
+}
+
+/// タスクの情報。タスク単位で集計する場合のみ持つ
+#[derive(Debug, Clone, Eq, PartialEq, PartialOrd, Ord)]
+struct LocationTask {
+    task_id: String,
+    task_name: String,
+    task_url: String,
+    task_status: String,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, PartialOrd, Ord)]
+pub(crate) struct LocationRecord {
+    level: LocationLevel,
+    updated_at: DateTime<FixedOffset>,
+    total_duration: TaskDuration,
+    rounded_duration: TaskDuration,
+    /// 集計する階層より下の項目は空文字列とする
+    location: TaskLocation,
+    task: Option<LocationTask>,
+}
+
+impl AggregatedRecord for LocationRecord {
+    fn target_date(&self) -> Option<NaiveDate> {
+        None
+    }
+    fn updated_at(&self) -> Option<DateTime<FixedOffset>> {
+        Some(self.updated_at)
+    }
+    fn total_duration(&self) -> &TaskDuration {
+        &self.total_duration
+    }
+    fn rounded_duration(&self) -> &TaskDuration {
+        &self.rounded_duration
+    }
+    /// リストまで集計する場合はリスト名をチャージコードとし、それ以外は空文字列とする
+    fn charge_name(&self) -> Option<&str> {
+        Some(&self.location.list_name)
+    }
+    fn task_id(&self) -> Option<&str> {
+        self.task.as_ref().map(|task| task.task_id.as_str())
+    }
+    fn task_name(&self) -> Option<&str> {
+        self.task.as_ref().map(|task| task.task_name.as_str())
+    }
+    fn task_url(&self) -> Option<&str> {
+        self.task.as_ref().map(|task| task.task_url.as_str())
+    }
+    fn task_status(&self) -> Option<&str> {
+        self.task.as_ref().map(|task| task.task_status.as_str())
+    }
+    fn space_name(&self) -> Option<&str> {
+        Some(&self.location.space_name)
+    }
+    fn folder_name(&self) -> Option<&str> {
+        match self.level {
+            LocationLevel::Space => None,
+            _ => Some(&self.location.folder_name),
+        }
+    }
+    fn list_name(&self) -> Option<&str> {
+        match self.level {
+            LocationLevel::Tree => Some(&self.location.list_name),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, PartialOrd, Ord)]
+pub(crate) struct LocationRecords {
+    date_range: DateRange,
+    level: LocationLevel,
+    records: Vec<LocationRecord>,
+    rounded: bool,
+}
+
+impl LocationRecords {
+    pub(crate) fn new(
+        date_range: DateRange,
+        task_records: TaskRecords,
+        level: LocationLevel,
+    ) -> Self {
+        let mut map = HashMap::<(TaskLocation, Option<String>), Vec<TaskRecord>>::new();
+
+        for task_record in task_records.into_inner() {
+            let key = match level {
+                LocationLevel::Space => (
+                    TaskLocation {
+                        space_id: task_record.location.space_id.clone(),
+                        space_name: task_record.location.space_name.clone(),
+                        ..TaskLocation::default()
+                    },
+                    None,
+                ),
+                LocationLevel::Folder => (
+                    TaskLocation {
+                        list_id: String::new(),
+                        list_name: String::new(),
+                        ..task_record.location.clone()
+                    },
+                    None,
+                ),
+                LocationLevel::Tree => (
+                    task_record.location.clone(),
+                    Some(task_record.task_id.clone()),
+                ),
+            };
+            map.entry(key).or_default().push(task_record);
+        }
+
+        let mut aggregated_records = Vec::new();
+
+        for ((location, task_id), records) in map {
+            let latest_record = records
+                .iter()
+                .max_by_key(|record| record.updated_at)
+                .unwrap();
+            let total_duration = records
+                .iter()
+                .map(|record| record.duration.clone())
+                .reduce(|total, duration| total.add(duration))
+                .unwrap();
+            let rounded_duration = records
+                .iter()
+                .map(|record| record.rounded_duration.clone())
+                .reduce(|total, duration| total.add(duration))
+                .unwrap();
+            let task = task_id.map(|task_id| LocationTask {
+                task_id,
+                task_name: latest_record.task_name.clone(),
+                task_url: latest_record.task_url.clone(),
+                task_status: latest_record.task_status.clone(),
+            });
+            aggregated_records.push(LocationRecord {
+                level,
+                updated_at: latest_record.updated_at,
+                total_duration,
+                rounded_duration,
+                location,
+                task,
+            })
+        }
+        Self {
+            date_range,
+            level,
+            records: aggregated_records,
+            rounded: false,
+        }
+    }
+
+    /// 丸め方針に従って、行毎に丸めた所要時間を求める
+    pub(crate) fn round(mut self, policy: &RoundingPolicy) -> Self {
+        for record in self.records.iter_mut() {
+            record.rounded_duration = policy.apply_to_row(
+                &record.location.list_name,
+                &record.total_duration,
+                &record.rounded_duration,
+            );
+        }
+        self.rounded = true;
+        self
+    }
+}
+
+impl AggregatedRecords for LocationRecords {
+    type Record = LocationRecord;
+
+    fn aggregated_by(&self) -> &'static str {
+        match self.level {
+            LocationLevel::Space => "space",
+            LocationLevel::Folder => "folder",
+            LocationLevel::Tree => "location_tree",
+        }
+    }
+    fn date_range(&self) -> &DateRange {
+        &self.date_range
+    }
+    fn is_rounded(&self) -> bool {
+        self.rounded
+    }
+    fn columns(&self) -> Vec<Column> {
+        let location_columns = match self.level {
+            LocationLevel::Space => vec![Column::SpaceName],
+            LocationLevel::Folder => vec![Column::SpaceName, Column::FolderName],
+            LocationLevel::Tree => vec![
+                Column::SpaceName,
+                Column::FolderName,
+                Column::ListName,
+                Column::TaskStatus,
+                Column::TaskName,
+            ],
+        };
+        with_rounded_column(
+            [Column::UpdatedAt, Column::TotalDuration]
+                .into_iter()
+                .chain(location_columns)
+                .collect(),
+            self.rounded,
+        )
+    }
+    fn records(&self) -> Vec<Self::Record> {
+        // 小計のグループと一致するよう、名前で並び替える
+        let mut records = self.records.clone();
+        records.sort_by_key(|record| {
+            (
+                record.location.space_name.clone(),
+                record.location.folder_name.clone(),
+                record.location.list_name.clone(),
+                record.task_status().unwrap_or_default().to_string(),
+                record.total_duration.clone(),
+            )
+        });
+        records
+    }
+    fn group_by(&self) -> Vec<Column> {
+        match self.level {
+            LocationLevel::Space => Vec::new(),
+            LocationLevel::Folder => vec![Column::SpaceName],
+            LocationLevel::Tree => vec![Column::SpaceName, Column::FolderName, Column::ListName],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::model::{fixtures, DayBoundary};
+
+    fn gen_task_record(task_id: &str, location: TaskLocation, hours: i64) -> TaskRecord {
+        let record = fixtures::gen_task_record(task_id, &location.list_name, 3, hours * 60);
+        TaskRecord { location, ..record }
+    }
+
+    fn gen_location_records(level: LocationLevel) -> Vec<(String, String, String, i64)> {
+        let date_range = DateRange::new(
+            Some("2023/04/03"),
+            Some("2023/04/03"),
+            DayBoundary::default(),
+        )
+        .unwrap();
+        let client_a =
+            |list_id: &str| TaskLocation::new("s1", "client", "f1", "client-a", list_id, list_id);
+        let task_records = TaskRecords::new(vec![
+            gen_task_record("a", client_a("design"), 1),
+            gen_task_record("b", client_a("design"), 2),
+            gen_task_record("c", client_a("build"), 3),
+            // フォルダに属さないリスト
+            gen_task_record(
+                "d",
+                TaskLocation::new("s1", "client", "", "", "misc", "misc"),
+                4,
+            ),
+            gen_task_record(
+                "e",
+                TaskLocation::new("s2", "internal", "f2", "admin", "mtg", "mtg"),
+                5,
+            ),
+        ]);
+
+        LocationRecords::new(date_range, task_records, level)
+            .records()
+            .iter()
+            .map(|record| {
+                (
+                    record.space_name().unwrap_or_default().to_string(),
+                    record.folder_name().unwrap_or_default().to_string(),
+                    record.list_name().unwrap_or_default().to_string(),
+                    record.total_duration().num_seconds() / 3600,
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn スペース毎とフォルダ毎に所要時間を合計する() {
+        let row = |space: &str, folder: &str, hours| {
+            (space.to_string(), folder.to_string(), String::new(), hours)
+        };
+
+        assert_eq!(
+            gen_location_records(LocationLevel::Space),
+            vec![row("client", "", 10), row("internal", "", 5)]
+        );
+        // フォルダに属さないリストは、フォルダ名が空のフォルダとしてまとめる
+        assert_eq!(
+            gen_location_records(LocationLevel::Folder),
+            vec![
+                row("client", "", 4),
+                row("client", "client-a", 6),
+                row("internal", "admin", 5)
+            ]
+        );
+    }
+
+    #[test]
+    fn 階層毎ではリスト内のタスク単位で集計する() {
+        let records = gen_location_records(LocationLevel::Tree);
+
+        assert_eq!(records.len(), 5);
+        assert_eq!(
+            records[1],
+            (
+                "client".to_string(),
+                "client-a".to_string(),
+                "build".to_string(),
+                3
+            )
+        );
+    }
+}
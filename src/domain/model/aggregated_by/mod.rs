@@ -1,9 +1,235 @@
+mod all;
 mod charge_and_daily;
 mod charge_and_total_period;
+mod location;
+mod pivot;
+mod subtotal;
 mod task_and_daily;
 mod task_and_total_period;
+mod working_day_check;
 
+pub(crate) use all::AllAggregatedRecords;
 pub(crate) use charge_and_daily::ChargeAndDailyRecords;
 pub(crate) use charge_and_total_period::ChargeAndTotalPeriodRecords;
+pub(crate) use location::{LocationLevel, LocationRecords};
+pub(crate) use pivot::{PivotRow, PivotTable};
+pub(crate) use subtotal::GroupedRow;
 pub(crate) use task_and_daily::TaskAndDailyRecords;
 pub(crate) use task_and_total_period::TaskAndTotalPeriodRecords;
+pub(crate) use working_day_check::WorkingDayCheckRecords;
+
+use crate::domain::model::{Bucketing, DateRange, Messages, Notation, TaskDuration};
+use chrono::{DateTime, FixedOffset, NaiveDate};
+
+/// 集計結果の1行分の情報を取り出すためのトレイト
+/// 集計単位によって持たない項目はNoneを返す
+/// 作業の無かった稼働日の行は、対象日と所要時間0のみを持つ
+pub(crate) trait AggregatedRecord {
+    fn target_date(&self) -> Option<NaiveDate>;
+    fn updated_at(&self) -> Option<DateTime<FixedOffset>>;
+    fn total_duration(&self) -> &TaskDuration;
+    /// 丸めた所要時間（丸めない場合は所要時間と同じ）
+    fn rounded_duration(&self) -> &TaskDuration;
+    fn charge_name(&self) -> Option<&str>;
+    fn task_id(&self) -> Option<&str>;
+    fn task_name(&self) -> Option<&str>;
+    fn task_url(&self) -> Option<&str>;
+    fn task_status(&self) -> Option<&str>;
+    /// スペース・フォルダ・リストの階層で集計する場合のみ持つ
+    fn space_name(&self) -> Option<&str> {
+        None
+    }
+    fn folder_name(&self) -> Option<&str> {
+        None
+    }
+    fn list_name(&self) -> Option<&str> {
+        None
+    }
+    /// 作業の無かった稼働日の行かどうか
+    fn is_no_work_day(&self) -> bool {
+        false
+    }
+}
+
+/// 集計結果全体の情報を取り出すためのトレイト
+/// 出力形式(TSV, JSON等)に依らず、同じ並び順・同じ項目で出力するために利用する
+pub(crate) trait AggregatedRecords {
+    type Record: AggregatedRecord + Clone;
+
+    /// 集計単位を表す名前
+    fn aggregated_by(&self) -> &'static str;
+    fn date_range(&self) -> &DateRange;
+    /// 所要時間を丸めたかどうか
+    fn is_rounded(&self) -> bool;
+    /// 出力する項目とその順序
+    fn columns(&self) -> Vec<Column>;
+    /// 出力順に並び替えたレコード
+    fn records(&self) -> Vec<Self::Record>;
+
+    /// 小計を出力するグループの項目（上位の階層から順に並べる）
+    /// レコードの並び順と一致している必要がある
+    fn group_by(&self) -> Vec<Column> {
+        Vec::new()
+    }
+
+    /// グループの階層毎に小計の行を挟んだレコード
+    fn grouped_records(&self) -> Vec<GroupedRow<Self::Record>> {
+        subtotal::group(self.records(), &self.columns(), &self.group_by())
+    }
+
+    /// 所要時間の総合計
+    fn grand_total(&self) -> TaskDuration {
+        self.column_total(&Column::TotalDuration)
+            .unwrap_or_else(TaskDuration::new)
+    }
+
+    /// 丸めた所要時間の総合計。行毎に丸めた所要時間の合計とする
+    fn rounded_grand_total(&self) -> Option<TaskDuration> {
+        match self.is_rounded() {
+            true => self.column_total(&Column::RoundedDuration),
+            false => None,
+        }
+    }
+
+    /// TSV出力の総合計の行の値
+    /// 丸めた場合は総合計の後ろに丸めた所要時間の総合計を並べる
+    fn grand_total_line(&self, notation: &Notation) -> Vec<String> {
+        [
+            notation.messages().grand_total.to_string(),
+            notation.duration(&self.grand_total()),
+        ]
+        .into_iter()
+        .chain(
+            self.rounded_grand_total()
+                .map(|total| notation.duration(&total)),
+        )
+        .collect()
+    }
+
+    /// 所要時間の列の総合計。所要時間以外の列はNoneを返す
+    fn column_total(&self, column: &Column) -> Option<TaskDuration> {
+        if !column.is_duration() {
+            return None;
+        }
+        Some(
+            self.records()
+                .iter()
+                .filter_map(|record| column.duration(record).cloned())
+                .fold(TaskDuration::new(), |accum, duration| accum.add(duration)),
+        )
+    }
+}
+
+/// 対象日の列。日毎でない場合は、対象日の代わりに週・月・四半期の列とする
+fn date_column(bucketing: Bucketing) -> Column {
+    match bucketing.is_daily() {
+        true => Column::TargetDate,
+        false => Column::Bucket(bucketing),
+    }
+}
+
+/// 所要時間の列の後ろに、丸めた所要時間の列を挟む
+fn with_rounded_column(columns: Vec<Column>, rounded: bool) -> Vec<Column> {
+    columns
+        .into_iter()
+        .flat_map(|column| match column {
+            Column::TotalDuration if rounded => vec![column, Column::RoundedDuration],
+            _ => vec![column],
+        })
+        .collect()
+}
+
+/// 集計結果の出力項目
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub(crate) enum Column {
+    TargetDate,
+    /// 対象日をまとめた週・月・四半期
+    Bucket(Bucketing),
+    UpdatedAt,
+    TotalDuration,
+    RoundedDuration,
+    ChargeName,
+    SpaceName,
+    FolderName,
+    ListName,
+    TaskStatus,
+    TaskName,
+}
+
+impl Column {
+    pub(crate) fn name(&self) -> &'static str {
+        match self {
+            Column::TargetDate => "target_date",
+            Column::Bucket(_) => "bucket",
+            Column::UpdatedAt => "updated_at",
+            Column::TotalDuration => "total_duration",
+            Column::RoundedDuration => "rounded_duration",
+            Column::ChargeName => "charge_name",
+            Column::SpaceName => "space_name",
+            Column::FolderName => "folder_name",
+            Column::ListName => "list_name",
+            Column::TaskStatus => "task_status",
+            Column::TaskName => "task_name",
+        }
+    }
+
+    /// 言語毎の列見出し
+    pub(crate) fn label(&self, messages: &Messages) -> &'static str {
+        match self {
+            Column::TargetDate => messages.target_date,
+            Column::Bucket(bucketing) => bucketing.column_label(messages),
+            Column::UpdatedAt => messages.updated_at,
+            Column::TotalDuration => messages.total_duration,
+            Column::RoundedDuration => messages.rounded_duration,
+            Column::ChargeName => messages.charge_name,
+            Column::SpaceName => messages.space_name,
+            Column::FolderName => messages.folder_name,
+            Column::ListName => messages.list_name,
+            Column::TaskStatus => messages.task_status,
+            Column::TaskName => messages.task_name,
+        }
+    }
+
+    /// 所要時間を表す列かどうか
+    pub(crate) fn is_duration(&self) -> bool {
+        matches!(self, Column::TotalDuration | Column::RoundedDuration)
+    }
+
+    /// レコードから所要時間の列の値を取り出す。所要時間以外の列はNoneを返す
+    pub(crate) fn duration<'a, R: AggregatedRecord>(
+        &self,
+        record: &'a R,
+    ) -> Option<&'a TaskDuration> {
+        match self {
+            Column::TotalDuration => Some(record.total_duration()),
+            Column::RoundedDuration => Some(record.rounded_duration()),
+            _ => None,
+        }
+    }
+
+    /// レコードから該当項目の値を取り出し、TSV出力と同じ表記の文字列にする
+    pub(crate) fn format<R: AggregatedRecord>(&self, record: &R, notation: &Notation) -> String {
+        match self {
+            Column::TargetDate => record
+                .target_date()
+                .map(|date| notation.date(&date))
+                .unwrap_or_default(),
+            Column::Bucket(bucketing) => record
+                .target_date()
+                .map(|date| bucketing.label(date))
+                .unwrap_or_default(),
+            Column::UpdatedAt => record
+                .updated_at()
+                .map(|datetime| notation.datetime(&datetime))
+                .unwrap_or_default(),
+            Column::TotalDuration => notation.duration(record.total_duration()),
+            Column::RoundedDuration => notation.duration(record.rounded_duration()),
+            Column::ChargeName => record.charge_name().unwrap_or_default().to_string(),
+            Column::SpaceName => record.space_name().unwrap_or_default().to_string(),
+            Column::FolderName => record.folder_name().unwrap_or_default().to_string(),
+            Column::ListName => record.list_name().unwrap_or_default().to_string(),
+            Column::TaskStatus => record.task_status().unwrap_or_default().to_string(),
+            Column::TaskName => record.task_name().unwrap_or_default().to_string(),
+        }
+    }
+}
@@ -0,0 +1,318 @@
+use std::collections::BTreeMap;
+
+use super::Column;
+use crate::domain::model::{AsVec, DateRange, Notation, TaskDuration, TaskRecords};
+use chrono::NaiveDate;
+
+/// 行見出し×対象日付の表（ピボット）形式の集計結果
+/// 所要時間を持たない日付のセルはNoneとする
+#[derive(Debug, Clone, Eq, PartialEq, PartialOrd, Ord)]
+pub(crate) struct PivotTable {
+    aggregated_by: &'static str,
+    date_range: DateRange,
+    target_dates: Vec<NaiveDate>,
+    label_columns: Vec<Column>,
+    rows: Vec<PivotRow>,
+}
+
+/// ピボット形式の集計結果の1行分
+#[derive(Debug, Clone, Eq, PartialEq, PartialOrd, Ord)]
+pub(crate) struct PivotRow {
+    charge_name: String,
+    task_id: Option<String>,
+    task_name: Option<String>,
+    task_url: Option<String>,
+    cells: Vec<Option<TaskDuration>>,
+}
+
+impl PivotTable {
+    /// チャージコードを行、対象日付を列とする
+    pub(crate) fn by_charge(date_range: DateRange, task_records: TaskRecords) -> Self {
+        let target_dates = date_range.target_dates();
+        let mut map = BTreeMap::<String, PivotRow>::new();
+
+        for task_record in task_records.into_inner() {
+            map.entry(task_record.charge_code.clone())
+                .or_insert_with(|| PivotRow {
+                    charge_name: task_record.charge_name.clone(),
+                    task_id: None,
+                    task_name: None,
+                    task_url: None,
+                    cells: vec![None; target_dates.len()],
+                })
+                .add(&target_dates, task_record.target_date, task_record.duration);
+        }
+
+        Self {
+            aggregated_by: "charge_and_date_pivot",
+            date_range,
+            target_dates,
+            label_columns: vec![Column::ChargeName],
+            rows: map.into_values().collect(),
+        }
+    }
+
+    /// タスクを行、対象日付を列とする
+    pub(crate) fn by_task(date_range: DateRange, task_records: TaskRecords) -> Self {
+        let target_dates = date_range.target_dates();
+        let mut map = BTreeMap::<(String, String, String), PivotRow>::new();
+
+        for task_record in task_records.into_inner() {
+            // チャージコード・タスク名の順に並べる
+            let key = (
+                task_record.charge_code.clone(),
+                task_record.task_name.clone(),
+                task_record.task_id.clone(),
+            );
+            map.entry(key)
+                .or_insert_with(|| PivotRow {
+                    charge_name: task_record.charge_name.clone(),
+                    task_id: Some(task_record.task_id.clone()),
+                    task_name: Some(task_record.task_name.clone()),
+                    task_url: Some(task_record.task_url.clone()),
+                    cells: vec![None; target_dates.len()],
+                })
+                .add(&target_dates, task_record.target_date, task_record.duration);
+        }
+
+        Self {
+            aggregated_by: "task_and_date_pivot",
+            date_range,
+            target_dates,
+            label_columns: vec![Column::ChargeName, Column::TaskName],
+            rows: map.into_values().collect(),
+        }
+    }
+
+    /// 集計単位を表す名前
+    pub(crate) fn aggregated_by(&self) -> &'static str {
+        self.aggregated_by
+    }
+    pub(crate) fn date_range(&self) -> &DateRange {
+        &self.date_range
+    }
+    /// 列となる対象日付
+    pub(crate) fn target_dates(&self) -> &[NaiveDate] {
+        &self.target_dates
+    }
+    /// 行見出しの項目
+    pub(crate) fn label_columns(&self) -> &[Column] {
+        &self.label_columns
+    }
+    pub(crate) fn rows(&self) -> &[PivotRow] {
+        &self.rows
+    }
+
+    /// 対象日付毎の合計。所要時間を持つ行が無い日付はNoneとする
+    pub(crate) fn daily_totals(&self) -> Vec<Option<TaskDuration>> {
+        (0..self.target_dates.len())
+            .map(|i| {
+                self.rows
+                    .iter()
+                    .filter_map(|row| row.cells[i].clone())
+                    .reduce(|total, duration| total.add(duration))
+            })
+            .collect()
+    }
+
+    /// 所要時間の総合計
+    pub(crate) fn grand_total(&self) -> TaskDuration {
+        self.rows
+            .iter()
+            .fold(TaskDuration::new(), |total, row| total.add(row.total()))
+    }
+
+    /// 言語毎のヘッダ行の値
+    pub(crate) fn header(&self, notation: &Notation) -> Vec<String> {
+        let messages = notation.messages();
+        self.header_with(
+            |column| column.label(messages),
+            messages.total_duration,
+            notation,
+        )
+    }
+
+    /// TSV・CSV出力のヘッダ行の値。行見出しと合計の列は言語に依らない列名とする
+    pub(crate) fn column_names(&self, notation: &Notation) -> Vec<String> {
+        self.header_with(Column::name, Column::TotalDuration.name(), notation)
+    }
+
+    fn header_with(
+        &self,
+        label: impl Fn(&Column) -> &'static str,
+        total: &str,
+        notation: &Notation,
+    ) -> Vec<String> {
+        self.label_columns
+            .iter()
+            .map(|column| label(column).to_string())
+            .chain(self.target_dates.iter().map(|date| notation.date(date)))
+            .chain([total.to_string()])
+            .collect()
+    }
+
+    /// 総合計の行の値。行見出しの列を詰め、日付毎の合計と総合計を並べる
+    pub(crate) fn grand_total_line(&self, notation: &Notation) -> Vec<String> {
+        [notation.messages().grand_total.to_string()]
+            .into_iter()
+            .chain(vec![String::new(); self.label_columns.len() - 1])
+            .chain(
+                self.daily_totals()
+                    .iter()
+                    .map(|cell| format_cell(cell, notation)),
+            )
+            .chain([notation.duration(&self.grand_total())])
+            .collect()
+    }
+}
+
+impl PivotRow {
+    fn add(&mut self, target_dates: &[NaiveDate], target_date: NaiveDate, duration: TaskDuration) {
+        // 対象期間外の日付（最終更新日時で抽出したタスク情報等）は列を持たない
+        if let Some(i) = target_dates.iter().position(|date| *date == target_date) {
+            self.cells[i] = Some(match self.cells[i].take() {
+                Some(total) => total.add(duration),
+                None => duration,
+            });
+        }
+    }
+
+    /// 行見出しの値。 `PivotTable::label_columns` と同じ並び順とする
+    pub(crate) fn labels(&self) -> Vec<&str> {
+        [Some(self.charge_name.as_str()), self.task_name.as_deref()]
+            .into_iter()
+            .flatten()
+            .collect()
+    }
+    pub(crate) fn charge_name(&self) -> &str {
+        &self.charge_name
+    }
+    pub(crate) fn task_id(&self) -> Option<&str> {
+        self.task_id.as_deref()
+    }
+    pub(crate) fn task_name(&self) -> Option<&str> {
+        self.task_name.as_deref()
+    }
+    pub(crate) fn task_url(&self) -> Option<&str> {
+        self.task_url.as_deref()
+    }
+    pub(crate) fn cells(&self) -> &[Option<TaskDuration>] {
+        &self.cells
+    }
+
+    /// 行の合計
+    pub(crate) fn total(&self) -> TaskDuration {
+        self.cells
+            .iter()
+            .flatten()
+            .fold(TaskDuration::new(), |total, duration| {
+                total.add(duration.clone())
+            })
+    }
+
+    /// 行見出し・日付毎の所要時間・行の合計を、TSV出力と同じ表記の文字列にする
+    pub(crate) fn line(&self, notation: &Notation) -> Vec<String> {
+        self.labels()
+            .into_iter()
+            .map(str::to_string)
+            .chain(self.cells.iter().map(|cell| format_cell(cell, notation)))
+            .chain([notation.duration(&self.total())])
+            .collect()
+    }
+}
+
+/// 所要時間を持たないセルは空欄とする
+fn format_cell(cell: &Option<TaskDuration>, notation: &Notation) -> String {
+    cell.as_ref()
+        .map(|duration| notation.duration(duration))
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::model::{fixtures::gen_task_record, DayBoundary};
+    use chrono::Duration;
+
+    fn gen_task_records() -> TaskRecords {
+        TaskRecords::new(vec![
+            gen_task_record("a", "charge-1", 3, 30),
+            gen_task_record("a", "charge-1", 5, 45),
+            gen_task_record("c", "charge-1", 5, 15),
+            gen_task_record("b", "charge-2", 4, 90),
+        ])
+    }
+
+    #[test]
+    fn チャージコード毎に対象期間の日付を列に持つ() {
+        let date_range = DateRange::new(
+            Some("2023/04/03"),
+            Some("2023/04/05"),
+            DayBoundary::default(),
+        )
+        .unwrap();
+        let table = PivotTable::by_charge(date_range, gen_task_records());
+
+        assert_eq!(table.target_dates().len(), 3);
+        assert_eq!(table.rows().len(), 2);
+
+        let row = &table.rows()[0];
+        assert_eq!(row.labels(), vec!["charge-1"]);
+        assert_eq!(row.cells()[0], Some(Duration::minutes(30).into()));
+        // 所要時間を持たない日付は空欄とする
+        assert_eq!(row.cells()[1], None);
+        assert_eq!(row.cells()[2], Some(Duration::minutes(60).into()));
+        assert_eq!(row.total(), Duration::minutes(90).into());
+    }
+
+    #[test]
+    fn 日付毎の合計と総合計を持つ() {
+        let date_range = DateRange::new(
+            Some("2023/04/03"),
+            Some("2023/04/06"),
+            DayBoundary::default(),
+        )
+        .unwrap();
+        let table = PivotTable::by_charge(date_range, gen_task_records());
+
+        assert_eq!(
+            table.daily_totals(),
+            vec![
+                Some(Duration::minutes(30).into()),
+                Some(Duration::minutes(90).into()),
+                Some(Duration::minutes(60).into()),
+                None,
+            ]
+        );
+        assert_eq!(table.grand_total(), Duration::minutes(180).into());
+    }
+
+    #[test]
+    fn タスク毎の行はチャージコード順に並ぶ() {
+        let date_range = DateRange::new(
+            Some("2023/04/03"),
+            Some("2023/04/05"),
+            DayBoundary::default(),
+        )
+        .unwrap();
+        let table = PivotTable::by_task(date_range, gen_task_records());
+
+        let labels = table
+            .rows()
+            .iter()
+            .map(|row| row.labels())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            labels,
+            vec![
+                vec!["charge-1", "task-a"],
+                vec!["charge-1", "task-c"],
+                vec!["charge-2", "task-b"],
+            ]
+        );
+        assert_eq!(
+            table.label_columns(),
+            &[Column::ChargeName, Column::TaskName]
+        );
+    }
+}
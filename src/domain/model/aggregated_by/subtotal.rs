@@ -0,0 +1,131 @@
+use super::{AggregatedRecord, Column};
+use crate::domain::model::{Notation, TaskDuration};
+
+/// 小計の行を挟んだ集計結果の1行分
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum GroupedRow<R> {
+    Record(R),
+    Subtotal(Subtotal<R>),
+}
+
+/// グループ毎の所要時間の小計
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Subtotal<R> {
+    /// グループを表す項目（上位の階層から順に並べる）
+    keys: Vec<Column>,
+    /// グループを表す項目の値を取り出すための、グループの先頭のレコード
+    record: R,
+    /// 所要時間の列とその小計
+    totals: Vec<(Column, TaskDuration)>,
+}
+
+impl<R: AggregatedRecord> Subtotal<R> {
+    /// 小計の行の値を、TSV出力と同じ表記の文字列にする
+    /// グループを表す項目にはその値を、所要時間の列には小計を、
+    /// それ以外の最初の列には「小計」を出力する
+    pub(crate) fn line(&self, columns: &[Column], notation: &Notation) -> Vec<String> {
+        let label_index = columns
+            .iter()
+            .position(|column| !column.is_duration() && !self.keys.contains(column));
+        columns
+            .iter()
+            .enumerate()
+            .map(|(i, column)| match column {
+                _ if self.keys.contains(column) => column.format(&self.record, notation),
+                _ if column.is_duration() => self
+                    .totals
+                    .iter()
+                    .find(|(total_column, _)| total_column == column)
+                    .map(|(_, total)| notation.duration(total))
+                    .unwrap_or_default(),
+                _ if Some(i) == label_index => notation.messages().subtotal.to_string(),
+                _ => String::new(),
+            })
+            .collect()
+    }
+}
+
+/// 集計中のグループ
+struct Group<R> {
+    key: String,
+    record: R,
+    totals: Vec<(Column, TaskDuration)>,
+}
+
+/// 並び替え済みのレコードの間に、グループの階層毎の小計の行を挟む
+/// レコードはグループを表す項目の順に並んでいる必要がある
+pub(super) fn group<R: AggregatedRecord + Clone>(
+    records: Vec<R>,
+    columns: &[Column],
+    group_by: &[Column],
+) -> Vec<GroupedRow<R>> {
+    let duration_columns = columns
+        .iter()
+        .copied()
+        .filter(Column::is_duration)
+        .collect::<Vec<_>>();
+    let mut rows = Vec::new();
+    // 現在のグループ（上位の階層から順に並べる）
+    let mut groups = Vec::<Group<R>>::new();
+
+    for record in records {
+        // 作業の無かった稼働日の行は、どのグループにも含めず小計も出力しない
+        if record.is_no_work_day() {
+            close_groups(&mut rows, &mut groups, 0, group_by);
+            rows.push(GroupedRow::Record(record));
+            continue;
+        }
+        // グループの判定にのみ用いるので、表記は問わない
+        let keys = group_by
+            .iter()
+            .map(|column| column.format(&record, &Notation::default()))
+            .collect::<Vec<_>>();
+        // 値が変わった最上位の階層以下のグループを閉じる
+        let changed = groups
+            .iter()
+            .zip(&keys)
+            .position(|(group, key)| group.key != *key)
+            .unwrap_or(groups.len());
+        close_groups(&mut rows, &mut groups, changed, group_by);
+
+        for key in keys.into_iter().skip(groups.len()) {
+            let totals = duration_columns
+                .iter()
+                .map(|column| (*column, TaskDuration::new()))
+                .collect();
+            groups.push(Group {
+                key,
+                record: record.clone(),
+                totals,
+            });
+        }
+        for group in groups.iter_mut() {
+            for (column, total) in group.totals.iter_mut() {
+                if let Some(duration) = column.duration(&record) {
+                    *total = total.add(duration.clone());
+                }
+            }
+        }
+        rows.push(GroupedRow::Record(record));
+    }
+    close_groups(&mut rows, &mut groups, 0, group_by);
+
+    rows
+}
+
+/// 指定した階層より下位のグループの小計の行を、下位の階層から順に追加する
+fn close_groups<R>(
+    rows: &mut Vec<GroupedRow<R>>,
+    groups: &mut Vec<Group<R>>,
+    level: usize,
+    group_by: &[Column],
+) {
+    while groups.len() > level {
+        let group = groups.pop().unwrap();
+        rows.push(GroupedRow::Subtotal(Subtotal {
+            keys: group_by[..=groups.len()].to_vec(),
+            record: group.record,
+            totals: group.totals,
+        }));
+    }
+}
@@ -2,34 +2,75 @@ use std::collections::HashMap;
 
 use chrono::{DateTime, FixedOffset, NaiveDate};
 
-use crate::domain::model::{AsVec, DateRange, TaskDuration, TaskRecord, TaskRecords};
-use derive_new::new;
+use super::{date_column, with_rounded_column, AggregatedRecord, AggregatedRecords, Column};
+use crate::domain::model::{
+    AsVec, Bucketing, DateRange, Granularity, RoundingPolicy, TaskDuration, TaskRecord,
+    TaskRecords, WorkingCalendar,
+};
 
-#[allow(clippy::too_many_arguments)]
-#[derive(new, Debug, Clone, Eq, PartialEq, PartialOrd, Ord)]
+#[derive(Debug, Clone, Eq, PartialEq, PartialOrd, Ord)]
 pub(crate) struct TaskAndDailyRecord {
     target_date: NaiveDate,
+    /// 作業の無かった稼働日の行はNone
+    task: Option<DailyTask>,
+    total_duration: TaskDuration,
+    rounded_duration: TaskDuration,
+}
+
+/// 対象日に作業したタスク
+#[derive(Debug, Clone, Eq, PartialEq, PartialOrd, Ord)]
+struct DailyTask {
     updated_at: DateTime<FixedOffset>,
+    charge_code: String,
     charge_name: String,
     task_id: String,
     task_name: String,
     task_url: String,
     task_status: String,
-    total_duration: TaskDuration,
 }
 
-impl std::fmt::Display for TaskAndDailyRecord {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(
-            f,
-            "{}\t{}\t{}\t{}\t{}\t{}",
-            self.target_date.format("%Y/%m/%d"),
-            self.updated_at.format("%Y/%m/%d %H:%M:%S"),
-            self.total_duration,
-            self.charge_name,
-            self.task_status,
-            self.task_name,
-        )
+impl TaskAndDailyRecord {
+    /// 作業の無かった稼働日の、所要時間0の行
+    fn no_work_day(target_date: NaiveDate) -> Self {
+        Self {
+            target_date,
+            task: None,
+            total_duration: TaskDuration::new(),
+            rounded_duration: TaskDuration::new(),
+        }
+    }
+}
+
+impl AggregatedRecord for TaskAndDailyRecord {
+    fn target_date(&self) -> Option<NaiveDate> {
+        Some(self.target_date)
+    }
+    fn updated_at(&self) -> Option<DateTime<FixedOffset>> {
+        self.task.as_ref().map(|task| task.updated_at)
+    }
+    fn total_duration(&self) -> &TaskDuration {
+        &self.total_duration
+    }
+    fn rounded_duration(&self) -> &TaskDuration {
+        &self.rounded_duration
+    }
+    fn charge_name(&self) -> Option<&str> {
+        self.task.as_ref().map(|task| task.charge_name.as_str())
+    }
+    fn task_id(&self) -> Option<&str> {
+        self.task.as_ref().map(|task| task.task_id.as_str())
+    }
+    fn task_name(&self) -> Option<&str> {
+        self.task.as_ref().map(|task| task.task_name.as_str())
+    }
+    fn task_url(&self) -> Option<&str> {
+        self.task.as_ref().map(|task| task.task_url.as_str())
+    }
+    fn task_status(&self) -> Option<&str> {
+        self.task.as_ref().map(|task| task.task_status.as_str())
+    }
+    fn is_no_work_day(&self) -> bool {
+        self.task.is_none()
     }
 }
 
@@ -37,14 +78,29 @@ impl std::fmt::Display for TaskAndDailyRecord {
 pub(crate) struct TaskAndDailyRecords {
     date_range: DateRange,
     records: Vec<TaskAndDailyRecord>,
+    rounded: bool,
+    bucketing: Bucketing,
 }
 
 impl TaskAndDailyRecords {
     pub(crate) fn new(date_range: DateRange, task_records: TaskRecords) -> Self {
+        Self::by_bucket(date_range, task_records, Bucketing::default())
+    }
+
+    /// 対象日を週・月・四半期の区切りにまとめて集計する
+    /// 集計結果の対象日は、区切りの初日とする
+    pub(crate) fn by_bucket(
+        date_range: DateRange,
+        task_records: TaskRecords,
+        bucketing: Bucketing,
+    ) -> Self {
         let mut map = HashMap::<(String, chrono::NaiveDate), Vec<TaskRecord>>::new();
 
         for task_record in task_records.into_inner() {
-            let key = (task_record.task_id.clone(), task_record.target_date);
+            let key = (
+                task_record.task_id.clone(),
+                bucketing.first_date_of(task_record.target_date),
+            );
             map.entry(key).or_default().push(task_record);
         }
 
@@ -60,70 +116,114 @@ impl TaskAndDailyRecords {
                 .map(|record| record.duration.clone())
                 .reduce(|total, duration| total.add(duration))
                 .unwrap();
-            aggregated_records.push(TaskAndDailyRecord::new(
+            let rounded_duration = records
+                .iter()
+                .map(|record| record.rounded_duration.clone())
+                .reduce(|total, duration| total.add(duration))
+                .unwrap();
+            aggregated_records.push(TaskAndDailyRecord {
                 target_date,
-                latest_record.updated_at,
-                latest_record.charge_name.clone(),
-                task_id,
-                latest_record.task_name.clone(),
-                latest_record.task_url.clone(),
-                latest_record.task_status.clone(),
+                task: Some(DailyTask {
+                    updated_at: latest_record.updated_at,
+                    charge_code: latest_record.charge_code.clone(),
+                    charge_name: latest_record.charge_name.clone(),
+                    task_id,
+                    task_name: latest_record.task_name.clone(),
+                    task_url: latest_record.task_url.clone(),
+                    task_status: latest_record.task_status.clone(),
+                }),
                 total_duration,
-            ))
+                rounded_duration,
+            })
         }
         Self {
             date_range,
             records: aggregated_records,
+            rounded: false,
+            bucketing,
+        }
+    }
+
+    /// 対象期間内の稼働日のうち、作業の無かった日に対象日のみの行を加える
+    /// 日毎に集計する場合のみ加え、週・月・四半期毎の場合は何もしない
+    pub(crate) fn fill_working_days(mut self, calendar: &WorkingCalendar) -> Self {
+        if !self.bucketing.is_daily() {
+            return self;
         }
+        let no_work_days = calendar
+            .working_days(&self.date_range)
+            .into_iter()
+            .filter(|date| !self.records.iter().any(|r| r.target_date == *date))
+            .collect::<Vec<_>>();
+        self.records.extend(
+            no_work_days
+                .into_iter()
+                .map(TaskAndDailyRecord::no_work_day),
+        );
+        self
+    }
+
+    /// 丸め方針に従って、行毎に丸めた所要時間を求める
+    pub(crate) fn round(mut self, policy: &RoundingPolicy) -> Self {
+        for record in self.records.iter_mut() {
+            let Some(task) = &record.task else {
+                continue;
+            };
+            record.rounded_duration = policy.apply_to_row(
+                &task.charge_code,
+                &record.total_duration,
+                &record.rounded_duration,
+            );
+        }
+        self.rounded = true;
+        self
     }
 }
 
-impl std::fmt::Display for TaskAndDailyRecords {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let title = if self.date_range.is_same_date() {
-            format!("集計対象日付：{}", self.date_range.start_date_str())
-        } else {
-            format!(
-                "集計対象期間：{} ～ {}",
-                self.date_range.start_date_str(),
-                self.date_range.end_target_date_str()
-            )
-        };
+impl AggregatedRecords for TaskAndDailyRecords {
+    type Record = TaskAndDailyRecord;
 
+    fn aggregated_by(&self) -> &'static str {
+        match self.bucketing.granularity() {
+            Granularity::Daily => "task_and_daily",
+            Granularity::Weekly => "task_and_weekly",
+            Granularity::Monthly => "task_and_monthly",
+            Granularity::Quarterly => "task_and_quarterly",
+        }
+    }
+    fn date_range(&self) -> &DateRange {
+        &self.date_range
+    }
+    fn is_rounded(&self) -> bool {
+        self.rounded
+    }
+    fn columns(&self) -> Vec<Column> {
+        with_rounded_column(
+            vec![
+                date_column(self.bucketing),
+                Column::UpdatedAt,
+                Column::TotalDuration,
+                Column::ChargeName,
+                Column::TaskStatus,
+                Column::TaskName,
+            ],
+            self.rounded,
+        )
+    }
+    fn records(&self) -> Vec<Self::Record> {
         // 並び替える
         let mut records = self.records.clone();
         records.sort_by_key(|record| {
             (
                 record.target_date,
-                record.charge_name.clone(),
-                record.task_status.clone(),
+                record.task.as_ref().map(|task| task.charge_code.clone()),
+                record.task_status().map(str::to_string),
                 record.total_duration.clone(),
             )
         });
-
-        let mut tsv = records.iter().map(|record| record.to_string()).fold(
-            vec![vec![
-                "target_date".to_string(),
-                "updated_at".to_string(),
-                "total_duration".to_string(),
-                "charge_name".to_string(),
-                "task_status".to_string(),
-                "task_name".to_string(),
-            ]
-            .join("\t")],
-            |mut records, record| {
-                records.push(record);
-                records
-            },
-        );
-        let grand_total = format!(
-            "総合計\t{}",
-            records
-                .into_iter()
-                .map(|record| record.total_duration)
-                .fold(TaskDuration::new(), |accum, duration| accum.add(duration))
-        );
-        tsv.push(grand_total);
-        write!(f, "\n{}\n[\n{}\n]", title, tsv.join("\n"))
+        records
+    }
+    fn group_by(&self) -> Vec<Column> {
+        vec![date_column(self.bucketing), Column::ChargeName]
     }
 }
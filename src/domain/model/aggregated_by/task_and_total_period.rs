@@ -1,32 +1,54 @@
 use std::collections::HashMap;
 
-use chrono::{DateTime, FixedOffset};
+use chrono::{DateTime, FixedOffset, NaiveDate};
 
-use crate::domain::model::{AsVec, DateRange, TaskDuration, TaskRecord, TaskRecords};
+use super::{with_rounded_column, AggregatedRecord, AggregatedRecords, Column};
+use crate::domain::model::{
+    AsVec, DateRange, RoundingPolicy, TaskDuration, TaskRecord, TaskRecords,
+};
 use derive_new::new;
 
+#[allow(clippy::too_many_arguments)]
 #[derive(new, Debug, Clone, Eq, PartialEq, PartialOrd, Ord)]
 pub(crate) struct TaskAndTotalPeriodRecord {
     updated_at: DateTime<FixedOffset>,
+    charge_code: String,
     charge_name: String,
     task_id: String,
     task_name: String,
     task_url: String,
     task_status: String,
     total_duration: TaskDuration,
+    rounded_duration: TaskDuration,
 }
 
-impl std::fmt::Display for TaskAndTotalPeriodRecord {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(
-            f,
-            "{}\t{}\t{}\t{}\t{}",
-            self.updated_at.format("%Y/%m/%d %H:%M:%S"),
-            self.total_duration,
-            self.charge_name,
-            self.task_status,
-            self.task_name,
-        )
+impl AggregatedRecord for TaskAndTotalPeriodRecord {
+    fn target_date(&self) -> Option<NaiveDate> {
+        None
+    }
+    fn updated_at(&self) -> Option<DateTime<FixedOffset>> {
+        Some(self.updated_at)
+    }
+    fn total_duration(&self) -> &TaskDuration {
+        &self.total_duration
+    }
+    fn rounded_duration(&self) -> &TaskDuration {
+        &self.rounded_duration
+    }
+    fn charge_name(&self) -> Option<&str> {
+        Some(&self.charge_name)
+    }
+    fn task_id(&self) -> Option<&str> {
+        Some(&self.task_id)
+    }
+    fn task_name(&self) -> Option<&str> {
+        Some(&self.task_name)
+    }
+    fn task_url(&self) -> Option<&str> {
+        Some(&self.task_url)
+    }
+    fn task_status(&self) -> Option<&str> {
+        Some(&self.task_status)
     }
 }
 
@@ -34,6 +56,7 @@ impl std::fmt::Display for TaskAndTotalPeriodRecord {
 pub(crate) struct TaskAndTotalPeriodRecords {
     date_range: DateRange,
     records: Vec<TaskAndTotalPeriodRecord>,
+    rounded: bool,
 }
 
 impl TaskAndTotalPeriodRecords {
@@ -57,67 +80,78 @@ impl TaskAndTotalPeriodRecords {
                 .map(|record| record.duration.clone())
                 .reduce(|total, duration| total.add(duration))
                 .unwrap();
+            let rounded_duration = records
+                .iter()
+                .map(|record| record.rounded_duration.clone())
+                .reduce(|total, duration| total.add(duration))
+                .unwrap();
             aggregated_records.push(TaskAndTotalPeriodRecord::new(
                 latest_record.updated_at,
+                latest_record.charge_code.clone(),
                 latest_record.charge_name.clone(),
                 task_id,
                 latest_record.task_name.clone(),
                 latest_record.task_url.clone(),
                 latest_record.task_status.clone(),
                 total_duration,
+                rounded_duration,
             ))
         }
         Self {
             date_range,
             records: aggregated_records,
+            rounded: false,
+        }
+    }
+
+    /// 丸め方針に従って、行毎に丸めた所要時間を求める
+    pub(crate) fn round(mut self, policy: &RoundingPolicy) -> Self {
+        for record in self.records.iter_mut() {
+            record.rounded_duration = policy.apply_to_row(
+                &record.charge_code,
+                &record.total_duration,
+                &record.rounded_duration,
+            );
         }
+        self.rounded = true;
+        self
     }
 }
 
-impl std::fmt::Display for TaskAndTotalPeriodRecords {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let title = if self.date_range.is_same_date() {
-            format!("集計対象日付：{}", self.date_range.start_date_str())
-        } else {
-            format!(
-                "集計対象期間：{} ～ {}",
-                self.date_range.start_date_str(),
-                self.date_range.end_target_date_str()
-            )
-        };
+impl AggregatedRecords for TaskAndTotalPeriodRecords {
+    type Record = TaskAndTotalPeriodRecord;
 
+    fn aggregated_by(&self) -> &'static str {
+        "task_and_total_period"
+    }
+    fn date_range(&self) -> &DateRange {
+        &self.date_range
+    }
+    fn is_rounded(&self) -> bool {
+        self.rounded
+    }
+    fn columns(&self) -> Vec<Column> {
+        with_rounded_column(
+            vec![
+                Column::UpdatedAt,
+                Column::TotalDuration,
+                Column::ChargeName,
+                Column::TaskStatus,
+                Column::TaskName,
+            ],
+            self.rounded,
+        )
+    }
+    fn records(&self) -> Vec<Self::Record> {
         // 並び替える
         let mut records = self.records.clone();
         records.sort_by_key(|record| {
             (
-                record.charge_name.clone(),
+                record.charge_code.clone(),
                 record.task_status.clone(),
                 record.total_duration.clone(),
             )
         });
-
-        let mut tsv = records.iter().map(|record| record.to_string()).fold(
-            vec![vec![
-                "updated_at".to_string(),
-                "total_duration".to_string(),
-                "charge_name".to_string(),
-                "task_status".to_string(),
-                "task_name".to_string(),
-            ]
-            .join("\t")],
-            |mut records, record| {
-                records.push(record);
-                records
-            },
-        );
-        let grand_total = format!(
-            "総合計\t{}",
-            records
-                .into_iter()
-                .map(|record| record.total_duration)
-                .fold(TaskDuration::new(), |accum, duration| accum.add(duration))
-        );
-        tsv.push(grand_total);
-        write!(f, "\n{}\n[\n{}\n]", title, tsv.join("\n"))
+        records
     }
 }
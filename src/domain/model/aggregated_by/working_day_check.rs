@@ -0,0 +1,156 @@
+use std::collections::BTreeMap;
+
+use super::{AggregatedRecord, AggregatedRecords, Column};
+use crate::domain::model::{AsVec, DateRange, TaskDuration, TaskRecords, WorkingCalendar};
+use chrono::{DateTime, FixedOffset, NaiveDate};
+use derive_new::new;
+
+/// 記録の不足している稼働日
+#[derive(new, Debug, Clone, Eq, PartialEq, PartialOrd, Ord)]
+pub(crate) struct WorkingDayCheckRecord {
+    target_date: NaiveDate,
+    /// 稼働日の始まりの日時
+    updated_at: DateTime<FixedOffset>,
+    total_duration: TaskDuration,
+}
+
+impl AggregatedRecord for WorkingDayCheckRecord {
+    fn target_date(&self) -> Option<NaiveDate> {
+        Some(self.target_date)
+    }
+    fn updated_at(&self) -> Option<DateTime<FixedOffset>> {
+        Some(self.updated_at)
+    }
+    fn total_duration(&self) -> &TaskDuration {
+        &self.total_duration
+    }
+    fn rounded_duration(&self) -> &TaskDuration {
+        &self.total_duration
+    }
+    fn charge_name(&self) -> Option<&str> {
+        None
+    }
+    fn task_id(&self) -> Option<&str> {
+        None
+    }
+    fn task_name(&self) -> Option<&str> {
+        None
+    }
+    fn task_url(&self) -> Option<&str> {
+        None
+    }
+    fn task_status(&self) -> Option<&str> {
+        None
+    }
+}
+
+/// 対象期間内の稼働日のうち、タイムエントリーが無い日、
+/// あるいは所要時間の合計が最低時間に満たない日の一覧
+#[derive(Debug, Clone, Eq, PartialEq, PartialOrd, Ord)]
+pub(crate) struct WorkingDayCheckRecords {
+    date_range: DateRange,
+    records: Vec<WorkingDayCheckRecord>,
+}
+
+impl WorkingDayCheckRecords {
+    pub(crate) fn new(
+        date_range: DateRange,
+        task_records: TaskRecords,
+        calendar: &WorkingCalendar,
+        min_duration: &TaskDuration,
+    ) -> Self {
+        let mut totals = BTreeMap::<NaiveDate, TaskDuration>::new();
+        for record in task_records.into_inner() {
+            let total = totals
+                .entry(record.target_date)
+                .or_insert_with(TaskDuration::new);
+            *total = total.add(record.duration);
+        }
+
+        let records = calendar
+            .working_days(&date_range)
+            .into_iter()
+            .filter_map(|date| match totals.get(&date) {
+                Some(total) if total >= min_duration => None,
+                total => Some(WorkingDayCheckRecord::new(
+                    date,
+                    date_range.start_of(date),
+                    total.cloned().unwrap_or_else(TaskDuration::new),
+                )),
+            })
+            .collect();
+        Self {
+            date_range,
+            records,
+        }
+    }
+}
+
+impl AggregatedRecords for WorkingDayCheckRecords {
+    type Record = WorkingDayCheckRecord;
+
+    fn aggregated_by(&self) -> &'static str {
+        "working_day_check"
+    }
+    fn date_range(&self) -> &DateRange {
+        &self.date_range
+    }
+    fn is_rounded(&self) -> bool {
+        false
+    }
+    fn columns(&self) -> Vec<Column> {
+        vec![Column::TargetDate, Column::TotalDuration]
+    }
+    fn records(&self) -> Vec<Self::Record> {
+        self.records.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::model::{fixtures::gen_task_record, CompanyHolidays, DayBoundary};
+    use chrono::Duration;
+
+    #[test]
+    fn 記録の無い稼働日と最低時間に満たない稼働日を列挙する() {
+        // 2023/04/03(月)〜04/09(日)。04/06は会社の休日とする
+        let date_range = DateRange::new(
+            Some("2023/04/03"),
+            Some("2023/04/09"),
+            DayBoundary::default(),
+        )
+        .unwrap();
+        let calendar = WorkingCalendar::new(CompanyHolidays::parse_text("2023/04/06").unwrap());
+        let task_records = TaskRecords::new(vec![
+            gen_task_record("a", "charge-1", 3, 8 * 60),
+            gen_task_record("a", "charge-1", 4, 3 * 60),
+            gen_task_record("a", "charge-1", 4, 3 * 60),
+            gen_task_record("a", "charge-1", 5, 5 * 60),
+            // 休日の作業は対象外とする
+            gen_task_record("a", "charge-1", 8, 60),
+        ]);
+
+        let checked = WorkingDayCheckRecords::new(
+            date_range,
+            task_records,
+            &calendar,
+            &Duration::hours(6).into(),
+        );
+        let records = checked
+            .records()
+            .iter()
+            .map(|record| {
+                (
+                    record.target_date.format("%m/%d").to_string(),
+                    record.total_duration.num_seconds() / 3600,
+                )
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            records,
+            vec![("04/05".to_string(), 5), ("04/07".to_string(), 0)]
+        );
+    }
+}
@@ -0,0 +1,432 @@
+use std::str::FromStr;
+
+use anyhow::{anyhow, bail, Error, Result};
+use chrono::{Datelike, Duration, NaiveDate};
+use regex::Regex;
+
+/// 請求期間の名前と区切り方
+/// "client-a=closes:20" のように、名前と区切り方をイコール区切りで指定する
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub(crate) struct PeriodDefinition {
+    name: String,
+    rule: PeriodRule,
+}
+
+/// 請求期間の区切り方
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum PeriodRule {
+    /// 毎月の締め日。締め日が月末より後の場合は月末を締め日とする
+    /// "closes:20" のように指定し、締め日の属する月をその期間の月とする
+    ClosingDay(u32),
+    /// 4-4-5 等、四半期を週数で区切った会計月
+    /// "4-4-5:2023/04/03" のように、週数の並びと会計年度の初日を指定する
+    /// 以降の会計年度は、初日と同じ曜日のうち毎年の同じ月日に最も近い日から始まる52週か53週とし、
+    /// 53週の会計年度は最後の会計月を1週延ばす。会計年度の初日の属する年をその会計年度の年とする
+    Weeks {
+        pattern: [u32; 3],
+        fiscal_year_start: NaiveDate,
+    },
+}
+
+impl FromStr for PeriodDefinition {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (name, rule) = s.split_once('=').ok_or_else(|| {
+            anyhow!(
+                "Period definition must be formatted as <name>=closes:<day> or <name>=<4-4-5>:<YYYY/MM/DD>: {}",
+                s
+            )
+        })?;
+        let name = name.trim();
+        if name.is_empty() {
+            bail!("Period name must not be empty: {}", s)
+        }
+        Ok(Self {
+            name: name.to_string(),
+            rule: rule.trim().parse()?,
+        })
+    }
+}
+
+impl FromStr for PeriodRule {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let weeks = Regex::new(r"^(?P<a>[45])-(?P<b>[45])-(?P<c>[45]):(?P<start>.+)$").unwrap();
+        if let Some(day) = s.strip_prefix("closes:") {
+            return match day.parse::<u32>() {
+                Ok(day @ 1..=31) => Ok(PeriodRule::ClosingDay(day)),
+                _ => bail!("Closing day must be between 1 and 31: {}", day),
+            };
+        }
+        if let Some(caps) = weeks.captures(s) {
+            let pattern = [&caps["a"], &caps["b"], &caps["c"]].map(|n| n.parse::<u32>().unwrap());
+            if pattern.iter().sum::<u32>() != 13 {
+                bail!("Weeks of a quarter must sum to 13: {}", s)
+            }
+            let fiscal_year_start = NaiveDate::parse_from_str(&caps["start"], "%Y/%m/%d")
+                .map_err(|e| anyhow!("Fiscal year start must be YYYY/MM/DD: {} ({})", s, e))?;
+            return Ok(PeriodRule::Weeks {
+                pattern,
+                fiscal_year_start,
+            });
+        }
+        bail!(
+            "Period rule must be closes:<day> or <4-4-5>:<YYYY/MM/DD>: {}",
+            s
+        )
+    }
+}
+
+/// どの請求期間を対象とするか
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PeriodSelector {
+    /// 今日を含む期間
+    Current,
+    /// 今日を含む期間の1つ前の期間
+    Previous,
+    /// 年月を指定した期間
+    Month(PeriodKey),
+}
+
+/// 請求期間の指定。"client-a:current" のように、名前と期間をコロン区切りで指定する
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct PeriodSelection {
+    name: String,
+    selector: PeriodSelector,
+}
+
+impl FromStr for PeriodSelection {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let year_month = Regex::new(r"^(?P<y>\d{4})[/-](?P<m>\d{1,2})$").unwrap();
+        let (name, selector) = s.rsplit_once(':').ok_or_else(|| {
+            anyhow!(
+                "Period must be formatted as <name>:<current|previous|YYYY-MM>: {}",
+                s
+            )
+        })?;
+        let selector = match selector.to_lowercase().as_str() {
+            "current" => PeriodSelector::Current,
+            "previous" => PeriodSelector::Previous,
+            _ => {
+                let caps = year_month.captures(selector).ok_or_else(|| {
+                    anyhow!("Period must be current, previous or YYYY-MM: {}", selector)
+                })?;
+                let month = caps["m"].parse::<u32>().unwrap();
+                if !(1..=12).contains(&month) {
+                    bail!("No such month. Input is '{}'", selector)
+                }
+                PeriodSelector::Month(PeriodKey {
+                    year: caps["y"].parse().unwrap(),
+                    month,
+                })
+            }
+        };
+        Ok(Self {
+            name: name.to_string(),
+            selector,
+        })
+    }
+}
+
+/// 名前に対応する区切り方で解決する請求期間
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct BillingPeriod {
+    definition: PeriodDefinition,
+    selector: PeriodSelector,
+}
+
+impl BillingPeriod {
+    /// 請求期間の指定に合う名前の定義を探す
+    pub(crate) fn find(
+        selection: &PeriodSelection,
+        definitions: &[PeriodDefinition],
+    ) -> Result<Self> {
+        let definition = definitions
+            .iter()
+            .find(|definition| definition.name == selection.name)
+            .ok_or_else(|| anyhow!("Period '{}' is not defined", selection.name))?;
+        Ok(Self {
+            definition: definition.clone(),
+            selector: selection.selector,
+        })
+    }
+
+    /// 今日を基準に期間を解決し、見出しと初日・末日を返す
+    /// 見出しは "client-a:2023-04" のように、名前と期間の年月を表す
+    pub(crate) fn resolve(&self, today: NaiveDate) -> (String, NaiveDate, NaiveDate) {
+        let rule = self.definition.rule;
+        let key = match self.selector {
+            PeriodSelector::Current => rule.key_of(today),
+            PeriodSelector::Previous => rule.key_of(today).previous(),
+            PeriodSelector::Month(key) => key,
+        };
+        let (first, last) = rule.range_of(key);
+        (format!("{}:{}", self.definition.name, key), first, last)
+    }
+}
+
+/// 請求期間の年月。会計月の場合は、会計年度の年と何番目の会計月かを表す
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+struct PeriodKey {
+    year: i32,
+    month: u32,
+}
+
+impl PeriodKey {
+    fn previous(self) -> Self {
+        match self.month {
+            1 => Self {
+                year: self.year - 1,
+                month: 12,
+            },
+            month => Self {
+                month: month - 1,
+                ..self
+            },
+        }
+    }
+
+    fn next(self) -> Self {
+        match self.month {
+            12 => Self {
+                year: self.year + 1,
+                month: 1,
+            },
+            month => Self {
+                month: month + 1,
+                ..self
+            },
+        }
+    }
+}
+
+impl std::fmt::Display for PeriodKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}-{:02}", self.year, self.month)
+    }
+}
+
+impl PeriodRule {
+    /// 対象日を含む期間の年月
+    fn key_of(&self, date: NaiveDate) -> PeriodKey {
+        let key = PeriodKey {
+            year: date.year(),
+            month: date.month(),
+        };
+        match *self {
+            PeriodRule::ClosingDay(_) => match date <= self.range_of(key).1 {
+                true => key,
+                false => key.next(),
+            },
+            PeriodRule::Weeks {
+                fiscal_year_start, ..
+            } => {
+                let mut year = date.year();
+                while date < fiscal_year_start_of(fiscal_year_start, year) {
+                    year -= 1;
+                }
+                while fiscal_year_start_of(fiscal_year_start, year + 1) <= date {
+                    year += 1;
+                }
+                let mut key = PeriodKey { year, month: 1 };
+                while self.range_of(key).1 < date {
+                    key = key.next();
+                }
+                key
+            }
+        }
+    }
+
+    /// 期間の初日と末日
+    fn range_of(&self, key: PeriodKey) -> (NaiveDate, NaiveDate) {
+        match *self {
+            PeriodRule::ClosingDay(day) => {
+                let closing_date = |key: PeriodKey| {
+                    let last_day = last_day_of_month(key.year, key.month);
+                    NaiveDate::from_ymd_opt(key.year, key.month, day.min(last_day)).unwrap()
+                };
+                (
+                    closing_date(key.previous()) + Duration::days(1),
+                    closing_date(key),
+                )
+            }
+            PeriodRule::Weeks {
+                pattern,
+                fiscal_year_start,
+            } => {
+                let year_start = fiscal_year_start_of(fiscal_year_start, key.year);
+                let weeks_before =
+                    |month: u32| -> u32 { (0..month - 1).map(|i| pattern[i as usize % 3]).sum() };
+                let first = year_start + Duration::weeks(weeks_before(key.month) as i64);
+                // 最後の会計月は、53週の会計年度では1週長くなるので翌会計年度の初日の前日までとする
+                let next_start = match key.month {
+                    12 => fiscal_year_start_of(fiscal_year_start, key.year + 1),
+                    month => year_start + Duration::weeks(weeks_before(month + 1) as i64),
+                };
+                (first, next_start - Duration::days(1))
+            }
+        }
+    }
+}
+
+/// 指定した年の会計年度の初日
+/// 基準の初日と同じ曜日のうち、その年の同じ月日（2月29日は2月28日）に最も近い日とする
+fn fiscal_year_start_of(fiscal_year_start: NaiveDate, year: i32) -> NaiveDate {
+    let anniversary = fiscal_year_start
+        .with_year(year)
+        .or_else(|| NaiveDate::from_ymd_opt(year, 2, 28))
+        .unwrap();
+    let days = fiscal_year_start
+        .weekday()
+        .days_since(anniversary.weekday()) as i64;
+    // 前後3日以内に収まるよう、4日以上先なら1週前の同じ曜日とする
+    let days = if days > 3 { days - 7 } else { days };
+    anniversary + Duration::days(days)
+}
+
+fn last_day_of_month(year: i32, month: u32) -> u32 {
+    let next_month = match month {
+        12 => NaiveDate::from_ymd_opt(year + 1, 1, 1),
+        _ => NaiveDate::from_ymd_opt(year, month + 1, 1),
+    };
+    (next_month.unwrap() - Duration::days(1)).day()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ymd(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    fn resolve(
+        definition: &str,
+        selection: &str,
+        today: NaiveDate,
+    ) -> (String, NaiveDate, NaiveDate) {
+        let definitions = vec![definition.parse::<PeriodDefinition>().unwrap()];
+        BillingPeriod::find(&selection.parse().unwrap(), &definitions)
+            .unwrap()
+            .resolve(today)
+    }
+
+    #[test]
+    fn 締め日で区切った期間を解決する() {
+        let today = ymd(2023, 4, 25);
+
+        assert_eq!(
+            resolve("client-a=closes:20", "client-a:current", today),
+            (
+                "client-a:2023-05".to_string(),
+                ymd(2023, 4, 21),
+                ymd(2023, 5, 20)
+            )
+        );
+        assert_eq!(
+            resolve("client-a=closes:20", "client-a:previous", today),
+            (
+                "client-a:2023-04".to_string(),
+                ymd(2023, 3, 21),
+                ymd(2023, 4, 20)
+            )
+        );
+        // 締め日が月末より後の場合は月末で締める
+        assert_eq!(
+            resolve("client-a=closes:31", "client-a:2023-02", today),
+            (
+                "client-a:2023-02".to_string(),
+                ymd(2023, 2, 1),
+                ymd(2023, 2, 28)
+            )
+        );
+    }
+
+    #[test]
+    fn 週数で区切った会計月を解決する() {
+        let definition = "client-b=4-4-5:2023/04/03";
+
+        // 第3会計月は5週間
+        assert_eq!(
+            resolve(definition, "client-b:2023-03", ymd(2023, 4, 3)),
+            (
+                "client-b:2023-03".to_string(),
+                ymd(2023, 5, 29),
+                ymd(2023, 7, 2)
+            )
+        );
+        assert_eq!(
+            resolve(definition, "client-b:current", ymd(2023, 6, 1)),
+            (
+                "client-b:2023-03".to_string(),
+                ymd(2023, 5, 29),
+                ymd(2023, 7, 2)
+            )
+        );
+        // 会計年度の最初の会計月の前は、前の会計年度の最後の会計月とする
+        assert_eq!(
+            resolve(definition, "client-b:previous", ymd(2023, 4, 10)),
+            (
+                "client-b:2022-12".to_string(),
+                ymd(2023, 2, 27),
+                ymd(2023, 4, 2)
+            )
+        );
+    }
+
+    #[test]
+    fn 会計年度の初日がずれる年は53週として最後の会計月を延ばす() {
+        let definition = "client-b=4-4-5:2023/04/03";
+
+        // 2025年度は2025/03/31（月）から、2026年度は2026/04/06（月）から始まる
+        assert_eq!(
+            resolve(definition, "client-b:2025-12", ymd(2026, 4, 3)),
+            (
+                "client-b:2025-12".to_string(),
+                ymd(2026, 2, 23),
+                ymd(2026, 4, 5)
+            )
+        );
+        assert_eq!(
+            resolve(definition, "client-b:current", ymd(2026, 4, 5)),
+            (
+                "client-b:2025-12".to_string(),
+                ymd(2026, 2, 23),
+                ymd(2026, 4, 5)
+            )
+        );
+        assert_eq!(
+            resolve(definition, "client-b:current", ymd(2026, 4, 6)),
+            (
+                "client-b:2026-01".to_string(),
+                ymd(2026, 4, 6),
+                ymd(2026, 5, 3)
+            )
+        );
+        // 前の会計年度も同じ規則で遡る
+        assert_eq!(
+            resolve(definition, "client-b:2022-01", ymd(2023, 4, 3)),
+            (
+                "client-b:2022-01".to_string(),
+                ymd(2022, 4, 4),
+                ymd(2022, 5, 1)
+            )
+        );
+    }
+
+    #[test]
+    fn 定義されていない名前や不正な指定はエラーとする() {
+        let definitions = vec!["client-a=closes:20".parse::<PeriodDefinition>().unwrap()];
+
+        assert!(BillingPeriod::find(&"client-b:current".parse().unwrap(), &definitions).is_err());
+        assert!("client-a:2023-13".parse::<PeriodSelection>().is_err());
+        assert!("client-a=closes:32".parse::<PeriodDefinition>().is_err());
+        assert!("client-a=4-4-4:2023/04/03"
+            .parse::<PeriodDefinition>()
+            .is_err());
+    }
+}
@@ -0,0 +1,174 @@
+use super::Messages;
+use chrono::{Datelike, Duration, NaiveDate, Weekday};
+
+/// 対象日をまとめて集計する単位
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, clap::ValueEnum)]
+pub(crate) enum Granularity {
+    /// 日毎
+    Daily,
+    /// 週毎
+    Weekly,
+    /// 月毎
+    Monthly,
+    /// 四半期毎
+    Quarterly,
+}
+
+/// 週の始まりの曜日
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, clap::ValueEnum)]
+pub(crate) enum WeekStart {
+    Mon,
+    Tue,
+    Wed,
+    Thu,
+    Fri,
+    Sat,
+    Sun,
+}
+
+impl WeekStart {
+    pub(crate) fn weekday(&self) -> Weekday {
+        match self {
+            WeekStart::Mon => Weekday::Mon,
+            WeekStart::Tue => Weekday::Tue,
+            WeekStart::Wed => Weekday::Wed,
+            WeekStart::Thu => Weekday::Thu,
+            WeekStart::Fri => Weekday::Fri,
+            WeekStart::Sat => Weekday::Sat,
+            WeekStart::Sun => Weekday::Sun,
+        }
+    }
+}
+
+/// 対象日を週・月・四半期の区切りにまとめる方法
+/// 対象日は1日の区切りの時刻を考慮して判定済みなので、区切りもそれに従う
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub(crate) struct Bucketing {
+    granularity: Granularity,
+    week_start: WeekStart,
+}
+
+impl Bucketing {
+    pub(crate) fn new(granularity: Granularity, week_start: WeekStart) -> Self {
+        Self {
+            granularity,
+            week_start,
+        }
+    }
+
+    pub(crate) fn granularity(&self) -> Granularity {
+        self.granularity
+    }
+
+    pub(crate) fn week_start(&self) -> WeekStart {
+        self.week_start
+    }
+
+    pub(crate) fn is_daily(&self) -> bool {
+        self.granularity == Granularity::Daily
+    }
+
+    /// 対象日が属する区切りの初日
+    pub(crate) fn first_date_of(&self, date: NaiveDate) -> NaiveDate {
+        match self.granularity {
+            Granularity::Daily => date,
+            Granularity::Weekly => {
+                let days = date.weekday().days_since(self.week_start.weekday());
+                date - Duration::days(days as i64)
+            }
+            Granularity::Monthly => date.with_day(1).unwrap(),
+            Granularity::Quarterly => {
+                let month = (date.month0() / 3) * 3 + 1;
+                NaiveDate::from_ymd_opt(date.year(), month, 1).unwrap()
+            }
+        }
+    }
+
+    /// 区切りの見出し
+    /// 週はISO週番号で表す。週の始まりが月曜以外の場合は、週に含まれる月曜のISO週番号とする
+    pub(crate) fn label(&self, date: NaiveDate) -> String {
+        let first = self.first_date_of(date);
+        match self.granularity {
+            Granularity::Daily => first.format("%Y-%m-%d").to_string(),
+            Granularity::Weekly => {
+                let days = Weekday::Mon.days_since(first.weekday());
+                let week = (first + Duration::days(days as i64)).iso_week();
+                format!("{}-W{:02}", week.year(), week.week())
+            }
+            Granularity::Monthly => first.format("%Y-%m").to_string(),
+            Granularity::Quarterly => format!("{}-Q{}", first.year(), first.month0() / 3 + 1),
+        }
+    }
+
+    /// 言語毎の列見出し
+    pub(crate) fn column_label(&self, messages: &Messages) -> &'static str {
+        match self.granularity {
+            Granularity::Daily => messages.target_date,
+            Granularity::Weekly => messages.week,
+            Granularity::Monthly => messages.month,
+            Granularity::Quarterly => messages.quarter,
+        }
+    }
+}
+
+impl Default for Bucketing {
+    /// 日毎、週の始まりは月曜
+    fn default() -> Self {
+        Self::new(Granularity::Daily, WeekStart::Mon)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ymd(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    #[test]
+    fn 週の始まりの曜日に従って週の初日を求める() {
+        let monday = Bucketing::new(Granularity::Weekly, WeekStart::Mon);
+        let sunday = Bucketing::new(Granularity::Weekly, WeekStart::Sun);
+
+        // 2023/04/05 は水曜日
+        assert_eq!(monday.first_date_of(ymd(2023, 4, 5)), ymd(2023, 4, 3));
+        assert_eq!(monday.first_date_of(ymd(2023, 4, 3)), ymd(2023, 4, 3));
+        assert_eq!(sunday.first_date_of(ymd(2023, 4, 5)), ymd(2023, 4, 2));
+        assert_eq!(sunday.first_date_of(ymd(2023, 4, 8)), ymd(2023, 4, 2));
+    }
+
+    #[test]
+    fn 週はisoの週番号を見出しとする() {
+        let monday = Bucketing::new(Granularity::Weekly, WeekStart::Mon);
+        let sunday = Bucketing::new(Granularity::Weekly, WeekStart::Sun);
+
+        assert_eq!(monday.label(ymd(2023, 4, 5)), "2023-W14");
+        // 年を跨ぐ週は、ISO週番号の年で表す
+        assert_eq!(monday.label(ymd(2021, 1, 1)), "2020-W53");
+        // 日曜始まりの週は、週に含まれる月曜のISO週番号とする
+        assert_eq!(sunday.label(ymd(2023, 4, 2)), "2023-W14");
+        assert_eq!(sunday.label(ymd(2023, 4, 8)), "2023-W14");
+    }
+
+    #[test]
+    fn 月と四半期の初日と見出し() {
+        let monthly = Bucketing::new(Granularity::Monthly, WeekStart::Mon);
+        let quarterly = Bucketing::new(Granularity::Quarterly, WeekStart::Mon);
+
+        assert_eq!(monthly.first_date_of(ymd(2023, 4, 30)), ymd(2023, 4, 1));
+        assert_eq!(monthly.label(ymd(2023, 4, 30)), "2023-04");
+        assert_eq!(quarterly.first_date_of(ymd(2023, 6, 30)), ymd(2023, 4, 1));
+        assert_eq!(quarterly.first_date_of(ymd(2023, 12, 1)), ymd(2023, 10, 1));
+        assert_eq!(quarterly.label(ymd(2023, 2, 14)), "2023-Q1");
+    }
+
+    #[test]
+    fn 日毎は対象日をそのまま用いる() {
+        let daily = Bucketing::default();
+
+        assert!(daily.is_daily());
+        assert_eq!(daily.first_date_of(ymd(2023, 4, 5)), ymd(2023, 4, 5));
+        assert_eq!(daily.label(ymd(2023, 4, 5)), "2023-04-05");
+    }
+}
@@ -0,0 +1,158 @@
+use std::collections::BTreeSet;
+
+use anyhow::{anyhow, bail, Result};
+use chrono::{Duration, NaiveDate};
+
+/// 会社独自の休日（夏季休暇・年末年始休暇等）
+#[derive(Debug, Clone, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub(crate) struct CompanyHolidays(BTreeSet<NaiveDate>);
+
+impl CompanyHolidays {
+    pub(crate) fn contains(&self, date: &NaiveDate) -> bool {
+        self.0.contains(date)
+    }
+
+    /// 1行に1日ずつ、 `YYYY/MM/DD` あるいは `YYYY-MM-DD` 形式で休日を記述したテキストを読み込む
+    /// 日付の後ろには空白で区切って休日の名前を書ける。空行と `#` で始まる行は無視する
+    pub(crate) fn parse_text(text: &str) -> Result<Self> {
+        text.lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .map(|line| {
+                let date = line.split_whitespace().next().unwrap_or_default();
+                parse_date(date, &["%Y/%m/%d", "%Y-%m-%d"])
+            })
+            .collect::<Result<_>>()
+            .map(Self)
+    }
+
+    /// iCalendar形式の予定を読み込み、予定の期間に含まれる日を休日とする
+    /// 終日の予定の DTEND は含まず、時刻を持つ予定は開始日から終了日までを休日とする
+    pub(crate) fn parse_ics(text: &str) -> Result<Self> {
+        let mut dates = BTreeSet::new();
+        let mut event: Option<(Option<IcsDate>, Option<IcsDate>)> = None;
+        for line in unfold(text) {
+            let (name, value) = match line.split_once(':') {
+                Some((name, value)) => (name.to_uppercase(), value.trim()),
+                None => continue,
+            };
+            // パラメータ（ ;VALUE=DATE 等）は値の書式から判断するので読み捨てる
+            let property = name.split(';').next().unwrap_or_default();
+            match (property, &mut event) {
+                ("BEGIN", _) if value.eq_ignore_ascii_case("VEVENT") => event = Some((None, None)),
+                ("DTSTART", Some((start, _))) => *start = Some(IcsDate::parse(value)?),
+                ("DTEND", Some((_, end))) => *end = Some(IcsDate::parse(value)?),
+                ("END", Some((start, end))) if value.eq_ignore_ascii_case("VEVENT") => {
+                    let start = start.ok_or_else(|| anyhow!("VEVENT must have DTSTART"))?;
+                    dates.extend(start.dates_until(*end));
+                    event = None;
+                }
+                _ => {}
+            }
+        }
+        Ok(Self(dates))
+    }
+}
+
+/// iCalendarの日付、あるいは日時の日付部分
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum IcsDate {
+    /// 終日の予定
+    Date(NaiveDate),
+    /// 時刻を持つ予定
+    DateTime(NaiveDate),
+}
+
+impl IcsDate {
+    fn parse(value: &str) -> Result<Self> {
+        match value.split_once('T') {
+            Some((date, _)) => Ok(IcsDate::DateTime(parse_date(date, &["%Y%m%d"])?)),
+            None => Ok(IcsDate::Date(parse_date(value, &["%Y%m%d"])?)),
+        }
+    }
+
+    /// 開始日から終了日までの日付。終了日が無い場合は開始日のみとする
+    fn dates_until(self, end: Option<IcsDate>) -> Vec<NaiveDate> {
+        let (start, last) = match (self, end) {
+            (IcsDate::Date(start), Some(IcsDate::Date(end))) => (start, end - Duration::days(1)),
+            (IcsDate::Date(start), _) => (start, start),
+            (IcsDate::DateTime(start), Some(IcsDate::DateTime(end) | IcsDate::Date(end))) => {
+                (start, end)
+            }
+            (IcsDate::DateTime(start), None) => (start, start),
+        };
+        start
+            .iter_days()
+            .take_while(|date| date <= &last.max(start))
+            .collect()
+    }
+}
+
+/// 折り返された行（空白・タブで始まる行）を前の行に連結する
+fn unfold(text: &str) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    for line in text.lines() {
+        match (line.strip_prefix([' ', '\t']), lines.last_mut()) {
+            (Some(continued), Some(last)) => last.push_str(continued),
+            _ => lines.push(line.to_string()),
+        }
+    }
+    lines
+}
+
+fn parse_date(s: &str, formats: &[&str]) -> Result<NaiveDate> {
+    for format in formats {
+        if let Ok(date) = NaiveDate::parse_from_str(s, format) {
+            return Ok(date);
+        }
+    }
+    bail!(
+        "Holiday must be formatted as {}: {}",
+        formats.join(" or "),
+        s
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ymd(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    #[test]
+    fn テキストから休日を読み込む() {
+        let text = "# 夏季休暇\n2023/08/14 夏季休暇\n2023-08-15\n\n";
+        let holidays = CompanyHolidays::parse_text(text).unwrap();
+
+        assert!(holidays.contains(&ymd(2023, 8, 14)));
+        assert!(holidays.contains(&ymd(2023, 8, 15)));
+        assert!(!holidays.contains(&ymd(2023, 8, 16)));
+        assert!(CompanyHolidays::parse_text("2023/13/01").is_err());
+    }
+
+    #[test]
+    fn icsの予定の期間を休日とする() {
+        let ics = "BEGIN:VCALENDAR\r\n\
+            BEGIN:VEVENT\r\n\
+            SUMMARY:年末年始休暇\r\n\
+            DTSTART;VALUE=DATE:20231229\r\n\
+            DTEND;VALUE=DATE:20240101\r\n\
+            END:VEVENT\r\n\
+            BEGIN:VEVENT\r\n\
+            DTSTART:20230901T090000\r\n\
+            DTEND:20230901T180000\r\n\
+            END:VEVENT\r\n\
+            END:VCALENDAR\r\n";
+        let holidays = CompanyHolidays::parse_ics(ics).unwrap();
+
+        // 終日の予定の DTEND は含まない
+        assert!(holidays.contains(&ymd(2023, 12, 29)));
+        assert!(holidays.contains(&ymd(2023, 12, 31)));
+        assert!(!holidays.contains(&ymd(2024, 1, 1)));
+        // 時刻を持つ予定は開始日を休日とする
+        assert!(holidays.contains(&ymd(2023, 9, 1)));
+        assert!(!holidays.contains(&ymd(2023, 9, 2)));
+    }
+}
@@ -0,0 +1,62 @@
+mod company_holiday;
+mod national_holiday;
+
+pub(crate) use company_holiday::CompanyHolidays;
+
+use super::DateRange;
+use chrono::{Datelike, NaiveDate, Weekday};
+
+/// 稼働日の判定に用いる暦
+/// 土日・日本の国民の祝日・会社独自の休日を休日とし、それ以外を稼働日とする
+#[derive(Debug, Clone, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub(crate) struct WorkingCalendar {
+    company_holidays: CompanyHolidays,
+}
+
+impl WorkingCalendar {
+    pub(crate) fn new(company_holidays: CompanyHolidays) -> Self {
+        Self { company_holidays }
+    }
+
+    pub(crate) fn is_working_day(&self, date: NaiveDate) -> bool {
+        !matches!(date.weekday(), Weekday::Sat | Weekday::Sun)
+            && !national_holiday::is_national_holiday(date)
+            && !self.company_holidays.contains(&date)
+    }
+
+    /// 対象期間内の稼働日
+    pub(crate) fn working_days(&self, date_range: &DateRange) -> Vec<NaiveDate> {
+        date_range
+            .target_dates()
+            .into_iter()
+            .filter(|date| self.is_working_day(*date))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::model::DayBoundary;
+
+    #[test]
+    fn 土日と祝日と会社の休日を除いた日を稼働日とする() {
+        let company_holidays = CompanyHolidays::parse_text("2023/05/02").unwrap();
+        let calendar = WorkingCalendar::new(company_holidays);
+        let date_range = DateRange::new(
+            Some("2023/04/28"),
+            Some("2023/05/08"),
+            DayBoundary::default(),
+        )
+        .unwrap();
+
+        let working_days = calendar
+            .working_days(&date_range)
+            .iter()
+            .map(|date| date.format("%m/%d").to_string())
+            .collect::<Vec<_>>();
+
+        // 4/29(土)は昭和の日、4/30は日曜日、5/3〜5/5は祝日、5/6・5/7は土日
+        assert_eq!(working_days, vec!["04/28", "05/01", "05/08"]);
+    }
+}
@@ -0,0 +1,132 @@
+use chrono::{Datelike, Duration, NaiveDate, Weekday};
+
+/// 日本の国民の祝日（振替休日・国民の休日を含む）か否か
+/// 2020年以降の祝日法に基づいて判定する。春分日・秋分日は天文計算による近似式で求める
+pub(super) fn is_national_holiday(date: NaiveDate) -> bool {
+    is_statutory_holiday(date) || is_substitute_holiday(date) || is_citizens_holiday(date)
+}
+
+/// 祝日法で日付が定められた祝日
+fn is_statutory_holiday(date: NaiveDate) -> bool {
+    let year = date.year();
+    let (month, day) = (date.month(), date.day());
+    match (month, day) {
+        // 元日
+        (1, 1) => true,
+        // 成人の日（1月の第2月曜日）
+        (1, _) => date == nth_monday(year, 1, 2),
+        // 建国記念の日・天皇誕生日
+        (2, 11) | (2, 23) => true,
+        // 春分の日
+        (3, _) => day == vernal_equinox_day(year),
+        // 昭和の日・憲法記念日・みどりの日・こどもの日
+        (4, 29) | (5, 3) | (5, 4) | (5, 5) => true,
+        // 海の日・スポーツの日・山の日
+        // 東京オリンピック・パラリンピックの開催に伴い、2020年と2021年は特例で移動している
+        (7, _) | (8, _) | (10, _) => match year {
+            2020 => matches!((month, day), (7, 23) | (7, 24) | (8, 10)),
+            2021 => matches!((month, day), (7, 22) | (7, 23) | (8, 8)),
+            _ => {
+                date == nth_monday(year, 7, 3)
+                    || date == nth_monday(year, 10, 2)
+                    || (month, day) == (8, 11)
+            }
+        },
+        // 敬老の日（9月の第3月曜日）・秋分の日
+        (9, _) => date == nth_monday(year, 9, 3) || day == autumnal_equinox_day(year),
+        // 文化の日・勤労感謝の日
+        (11, 3) | (11, 23) => true,
+        _ => false,
+    }
+}
+
+/// 振替休日。祝日が日曜日に当たる場合、その後の最も近い祝日でない日を休日とする
+fn is_substitute_holiday(date: NaiveDate) -> bool {
+    if is_statutory_holiday(date) {
+        return false;
+    }
+    let mut previous = date - Duration::days(1);
+    while is_statutory_holiday(previous) {
+        if previous.weekday() == Weekday::Sun {
+            return true;
+        }
+        previous -= Duration::days(1);
+    }
+    false
+}
+
+/// 国民の休日。前日と翌日が祝日である、祝日でない日を休日とする
+fn is_citizens_holiday(date: NaiveDate) -> bool {
+    !is_statutory_holiday(date)
+        && is_statutory_holiday(date - Duration::days(1))
+        && is_statutory_holiday(date + Duration::days(1))
+}
+
+/// 指定した月の第N月曜日
+fn nth_monday(year: i32, month: u32, n: u8) -> NaiveDate {
+    NaiveDate::from_weekday_of_month_opt(year, month, Weekday::Mon, n).unwrap()
+}
+
+/// 3月の春分日（1980年〜2099年の近似式）
+fn vernal_equinox_day(year: i32) -> u32 {
+    equinox_day(20.8431, year)
+}
+
+/// 9月の秋分日（1980年〜2099年の近似式）
+fn autumnal_equinox_day(year: i32) -> u32 {
+    equinox_day(23.2488, year)
+}
+
+fn equinox_day(base: f64, year: i32) -> u32 {
+    let elapsed = year - 1980;
+    (base + 0.242194 * elapsed as f64).floor() as u32 - elapsed.div_euclid(4) as u32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn holidays_of(year: i32) -> Vec<String> {
+        NaiveDate::from_ymd_opt(year, 1, 1)
+            .unwrap()
+            .iter_days()
+            .take_while(|date| date.year() == year)
+            .filter(|date| is_national_holiday(*date))
+            .map(|date| date.format("%m/%d").to_string())
+            .collect()
+    }
+
+    #[test]
+    fn 振替休日を含めて2023年の祝日を判定できる() {
+        assert_eq!(
+            holidays_of(2023),
+            vec![
+                "01/01", "01/02", "01/09", "02/11", "02/23", "03/21", "04/29", "05/03", "05/04",
+                "05/05", "07/17", "08/11", "09/18", "09/23", "10/09", "11/03", "11/23"
+            ]
+        );
+    }
+
+    #[test]
+    fn 祝日に挟まれた日と日曜日の祝日の翌日を休日とする() {
+        // 2026年は敬老の日(9/21)と秋分の日(9/23)に挟まれた9/22が休日となる
+        assert!(is_national_holiday(
+            NaiveDate::from_ymd_opt(2026, 9, 22).unwrap()
+        ));
+        // 2024年はこどもの日(5/5)が日曜日のため、5/6が振替休日となる
+        assert!(is_national_holiday(
+            NaiveDate::from_ymd_opt(2024, 5, 6).unwrap()
+        ));
+    }
+
+    #[test]
+    fn オリンピックの特例で移動した祝日を判定できる() {
+        assert_eq!(
+            holidays_of(2021)
+                .into_iter()
+                .filter(|date| date.starts_with("07") || date.starts_with("08"))
+                .collect::<Vec<_>>(),
+            vec!["07/22", "07/23", "08/08", "08/09"]
+        );
+    }
+}
@@ -0,0 +1,248 @@
+use anyhow::{anyhow, Result};
+use regex::Regex;
+use serde_derive::Deserialize;
+
+use super::TaskRecord;
+
+/// 振り分けに合致しなかったレコードのチャージコードの既定値
+const DEFAULT_FALLBACK: &str = "unmapped";
+
+/// タスクの所属やタスク名等からチャージコードを決める規則
+/// 規則を上から順に評価し、最初に合致した規則のチャージコードを用いる
+/// 規則が1つも無い場合は、これまで通りリスト名をチャージコードとする
+#[derive(Debug, Clone, Default)]
+pub(crate) struct ChargeRules {
+    rules: Vec<ChargeRule>,
+    /// どの規則にも合致しなかったレコードのチャージコード
+    fallback: Option<String>,
+    /// チャージコードを持つカスタムフィールドのIDか名前
+    /// 値を持つタスクは、規則より優先してその値をチャージコードとする
+    custom_field: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+struct ChargeRule {
+    /// スペース・フォルダ・リストは、名前とIDのどちらかに一致すれば合致とする
+    space: Option<String>,
+    folder: Option<String>,
+    list: Option<String>,
+    /// タスク名の正規表現
+    task: Option<Regex>,
+    tag: Option<String>,
+    status: Option<String>,
+    charge: String,
+    /// 出力時にチャージコードの代わりに表示する名前
+    name: Option<String>,
+}
+
+/// 規則ファイルの構造
+/// {"fallback": "other", "rules": [{"folder": "client-a", "task": "^保守", "charge": "A-001", "name": "A社 保守"}]}
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ChargeRulesFile {
+    #[serde(default)]
+    fallback: Option<String>,
+    rules: Vec<ChargeRuleEntry>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ChargeRuleEntry {
+    space: Option<String>,
+    folder: Option<String>,
+    list: Option<String>,
+    task: Option<String>,
+    tag: Option<String>,
+    status: Option<String>,
+    charge: String,
+    name: Option<String>,
+}
+
+impl ChargeRules {
+    /// JSON形式の規則ファイルを読み込む
+    pub(crate) fn parse_json(text: &str) -> Result<Self> {
+        let file: ChargeRulesFile = serde_json::from_str(text)?;
+        let rules = file
+            .rules
+            .into_iter()
+            .map(|entry| {
+                let task = entry
+                    .task
+                    .map(|pattern| {
+                        Regex::new(&pattern)
+                            .map_err(|e| anyhow!("Invalid task name pattern '{}': {}", pattern, e))
+                    })
+                    .transpose()?;
+                Ok(ChargeRule {
+                    space: entry.space,
+                    folder: entry.folder,
+                    list: entry.list,
+                    task,
+                    tag: entry.tag,
+                    status: entry.status,
+                    charge: entry.charge,
+                    name: entry.name,
+                })
+            })
+            .collect::<Result<_>>()?;
+        Ok(Self {
+            rules,
+            fallback: file.fallback,
+            custom_field: None,
+        })
+    }
+
+    pub(crate) fn with_custom_field(self, custom_field: Option<String>) -> Self {
+        Self {
+            custom_field,
+            ..self
+        }
+    }
+
+    pub(crate) fn custom_field(&self) -> Option<&str> {
+        self.custom_field.as_deref()
+    }
+
+    /// レコードに合致する規則のチャージコードと表示名（表示名が無ければチャージコード）
+    /// 規則があるのにどれにも合致しない場合はNoneを返す
+    pub(crate) fn charge_of(&self, record: &TaskRecord) -> Option<(String, String)> {
+        if self.rules.is_empty() {
+            let list_name = record.location.list_name.clone();
+            return Some((list_name.clone(), list_name));
+        }
+        self.rules
+            .iter()
+            .find(|rule| rule.matches(record))
+            .map(|rule| {
+                let name = rule.name.clone().unwrap_or_else(|| rule.charge.clone());
+                (rule.charge.clone(), name)
+            })
+    }
+
+    pub(crate) fn fallback(&self) -> &str {
+        self.fallback.as_deref().unwrap_or(DEFAULT_FALLBACK)
+    }
+}
+
+impl ChargeRule {
+    /// 指定された条件を全て満たす場合に合致とする
+    fn matches(&self, record: &TaskRecord) -> bool {
+        let location = &record.location;
+        let is = |expected: &Option<String>, name: &str, id: &str| match expected {
+            Some(expected) => expected == name || expected == id,
+            None => true,
+        };
+        is(&self.space, &location.space_name, &location.space_id)
+            && is(&self.folder, &location.folder_name, &location.folder_id)
+            && is(&self.list, &location.list_name, &location.list_id)
+            && self
+                .task
+                .as_ref()
+                .is_none_or(|pattern| pattern.is_match(&record.task_name))
+            && self
+                .tag
+                .as_ref()
+                .is_none_or(|tag| record.tags.iter().any(|t| t.eq_ignore_ascii_case(tag)))
+            && self
+                .status
+                .as_ref()
+                .is_none_or(|status| record.task_status.eq_ignore_ascii_case(status))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::model::{
+        aggregated_by::{AggregatedRecord, AggregatedRecords, ChargeAndTotalPeriodRecords},
+        fixtures, DateRange, DayBoundary, RoundingPolicy, RoundingScope, TaskDuration,
+        TaskLocation, TaskRecords,
+    };
+    use std::collections::HashMap;
+
+    fn gen_task_record(task_name: &str, list_name: &str, tags: &[&str]) -> TaskRecord {
+        TaskRecord {
+            task_name: task_name.to_string(),
+            location: TaskLocation::new("s1", "client", "f1", "client-a", "l1", list_name),
+            tags: tags.iter().map(|tag| tag.to_string()).collect(),
+            ..fixtures::gen_task_record("a", list_name, 3, 60)
+        }
+    }
+
+    #[test]
+    fn 最初に合致した規則のチャージコードを用いる() {
+        let rules = ChargeRules::parse_json(
+            r#"{
+                "fallback": "other",
+                "rules": [
+                    {"folder": "client-a", "task": "^保守", "charge": "A-002", "name": "A社 保守"},
+                    {"folder": "f1", "charge": "A-001"},
+                    {"tag": "Internal", "status": "in progress", "charge": "X-001"}
+                ]
+            }"#,
+        )
+        .unwrap();
+        let charge_of = |record: TaskRecord| rules.charge_of(&record);
+
+        assert_eq!(
+            charge_of(gen_task_record("保守作業", "dev", &[])),
+            Some(("A-002".to_string(), "A社 保守".to_string()))
+        );
+        // フォルダはIDでも指定できる
+        assert_eq!(
+            charge_of(gen_task_record("設計", "dev", &[])),
+            Some(("A-001".to_string(), "A-001".to_string()))
+        );
+        assert_eq!(rules.fallback(), "other");
+    }
+
+    #[test]
+    fn 規則が無ければリスト名をチャージコードとする() {
+        let record = gen_task_record("設計", "dev", &["internal"]);
+        let rules = ChargeRules::parse_json(
+            r#"{"rules": [{"tag": "internal", "status": "done", "charge": "X"}]}"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            ChargeRules::default().charge_of(&record),
+            Some(("dev".to_string(), "dev".to_string()))
+        );
+        assert_eq!(rules.charge_of(&record), None);
+        assert_eq!(rules.fallback(), "unmapped");
+        assert!(ChargeRules::parse_json(r#"{"rules": [{"task": "(", "charge": "X"}]}"#).is_err());
+    }
+
+    #[test]
+    fn 表示名を持つ規則もチャージコードで集計して丸める() {
+        let rules = ChargeRules::parse_json(
+            r#"{"rules": [{"list": "dev", "charge": "A-001", "name": "A社 開発"}]}"#,
+        )
+        .unwrap();
+        let records = TaskRecords::new(vec![
+            gen_task_record("設計", "dev", &[]),
+            gen_task_record("実装", "dev", &[]),
+        ]);
+        let (records, unmapped) = records.map_charges(&rules, &HashMap::new());
+        let policy = RoundingPolicy::new(
+            None,
+            vec!["A-001=up:90".parse().unwrap()],
+            RoundingScope::Row,
+        )
+        .unwrap();
+        let aggregated = ChargeAndTotalPeriodRecords::new(
+            DateRange::new(Some("2023/04/03"), None, DayBoundary::default()).unwrap(),
+            records,
+        )
+        .round(&policy);
+
+        let rows = aggregated.records();
+        assert!(unmapped.is_empty());
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].charge_name(), Some("A社 開発"));
+        assert_eq!(
+            rows[0].rounded_duration(),
+            &TaskDuration::from(chrono::Duration::minutes(180))
+        );
+    }
+}
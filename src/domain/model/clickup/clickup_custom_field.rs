@@ -0,0 +1,23 @@
+/// タスクのカスタムフィールド
+/// ドロップダウン・ラベルの値は、選択肢のIDではなく表示名に解決したものを持つ
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct ClickupCustomField {
+    pub(crate) id: String,
+    pub(crate) name: String,
+    pub(crate) value: Option<String>,
+}
+
+impl ClickupCustomField {
+    pub fn new(id: impl Into<String>, name: impl Into<String>, value: Option<String>) -> Self {
+        Self {
+            id: id.into(),
+            name: name.into(),
+            value,
+        }
+    }
+
+    /// IDか名前が一致するかどうか
+    pub(crate) fn is(&self, field: &str) -> bool {
+        self.id == field || self.name == field
+    }
+}
@@ -1,4 +1,5 @@
-use crate::domain::model::{DateRange, Jst, TaskDuration, TaskRecord};
+use super::ClickupCustomField;
+use crate::domain::model::{DateRange, TaskDuration, TaskLocation, TaskRecord};
 
 use chrono::{DateTime, FixedOffset};
 
@@ -8,29 +9,36 @@ pub struct ClickupTask {
     pub(crate) task_name: String,
     pub(crate) task_url: String,
     pub(crate) task_status: String,
-    pub(crate) parent_list_name: String,
+    pub(crate) location: TaskLocation,
+    pub(crate) tags: Vec<String>,
+    pub(crate) custom_fields: Vec<ClickupCustomField>,
     pub(crate) duration: TaskDuration,
     pub(crate) updated_at: DateTime<FixedOffset>,
 }
 
 impl ClickupTask {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         task_id: impl Into<String>,
         task_name: impl Into<String>,
         task_url: impl Into<String>,
         task_status: impl Into<String>,
-        list_name: impl Into<String>,
-        duration: Option<&str>,
-        updated_at: &str,
+        location: TaskLocation,
+        tags: Vec<String>,
+        custom_fields: Vec<ClickupCustomField>,
+        duration: TaskDuration,
+        updated_at: DateTime<FixedOffset>,
     ) -> Self {
         Self {
             task_id: task_id.into(),
             task_name: task_name.into(),
             task_url: task_url.into(),
             task_status: task_status.into(),
-            parent_list_name: list_name.into(),
-            duration: TaskDuration::from(duration),
-            updated_at: Jst::timestamp_millis(updated_at.parse::<i64>().unwrap()),
+            location,
+            tags,
+            custom_fields,
+            duration,
+            updated_at,
         }
     }
 }
@@ -41,17 +49,31 @@ impl PartialEq for ClickupTask {
     }
 }
 
-impl From<ClickupTask> for TaskRecord {
-    fn from(clickup_task: ClickupTask) -> Self {
-        Self {
+impl ClickupTask {
+    /// IDか名前で指定したカスタムフィールドの値。フィールドが無いか、値が空の場合はNoneを返す
+    pub(crate) fn custom_field_value(&self, field: &str) -> Option<&str> {
+        self.custom_fields
+            .iter()
+            .find(|custom_field| custom_field.is(field))
+            .and_then(|custom_field| custom_field.value.as_deref())
+            .filter(|value| !value.is_empty())
+    }
+
+    pub(crate) fn into_task_record(self, date_range: &DateRange) -> TaskRecord {
+        let clickup_task = self;
+        TaskRecord {
             task_id: clickup_task.task_id,
             task_name: clickup_task.task_name,
             task_url: clickup_task.task_url,
             task_status: clickup_task.task_status,
-            charge_name: clickup_task.parent_list_name,
+            charge_code: clickup_task.location.list_name.clone(),
+            charge_name: clickup_task.location.list_name.clone(),
+            location: clickup_task.location,
+            tags: clickup_task.tags,
+            rounded_duration: clickup_task.duration.clone(),
             duration: clickup_task.duration,
-            target_date: DateRange::convert_datetime_to_date(clickup_task.updated_at),
-            updated_at: clickup_task.updated_at,
+            target_date: date_range.convert_datetime_to_date(clickup_task.updated_at),
+            updated_at: date_range.zone().localize(&clickup_task.updated_at),
         }
     }
 }
@@ -1,7 +1,7 @@
 extern crate chrono;
 
 use super::ClickupTask;
-use crate::domain::model::{AsVec, TaskRecords};
+use crate::domain::model::{AsVec, DateRange, TaskRecords};
 
 #[derive(Debug, Clone, Eq, PartialEq, PartialOrd, Ord)]
 pub struct ClickupTasks(Vec<ClickupTask>);
@@ -19,20 +19,18 @@ impl AsVec for ClickupTasks {
     }
 }
 
-impl From<ClickupTasks> for TaskRecords {
-    fn from(clickup_tasks: ClickupTasks) -> Self {
-        Self::new(
-            clickup_tasks
-                .into_inner()
-                .into_iter()
-                .map(|task| task.into())
-                .collect(),
-        )
-    }
-}
-
 impl ClickupTasks {
     pub fn new(tasks: Vec<ClickupTask>) -> Self {
         Self(tasks)
     }
+
+    /// 対象期間の1日の区切りに従って、対象日付を持つタスク情報に変換する
+    pub(crate) fn into_task_records(self, date_range: &DateRange) -> TaskRecords {
+        TaskRecords::new(
+            self.0
+                .into_iter()
+                .map(|task| task.into_task_record(date_range))
+                .collect(),
+        )
+    }
 }
@@ -1,5 +1,5 @@
 use super::ClickupTimeEntry;
-use crate::domain::model::{AsVec, TaskRecords};
+use crate::domain::model::{AsVec, DateRange, TaskRecords};
 
 #[derive(Debug, Clone, Eq, PartialEq, PartialOrd, Ord)]
 pub struct ClickupTimeEntries(Vec<ClickupTimeEntry>);
@@ -17,20 +17,28 @@ impl AsVec for ClickupTimeEntries {
     }
 }
 
-impl From<ClickupTimeEntries> for TaskRecords {
-    fn from(clickup_time_entries: ClickupTimeEntries) -> Self {
-        Self::new(
-            clickup_time_entries
-                .into_inner()
+impl ClickupTimeEntries {
+    pub fn new(time_entries: Vec<ClickupTimeEntry>) -> Self {
+        Self(time_entries)
+    }
+
+    /// 対象期間の1日の区切りに従って、対象日付を持つタスク情報に変換する
+    pub(crate) fn into_task_records(self, date_range: &DateRange) -> TaskRecords {
+        TaskRecords::new(
+            self.0
                 .into_iter()
-                .map(|entry| entry.into())
+                .map(|entry| entry.into_task_record(date_range))
                 .collect(),
         )
     }
-}
 
-impl ClickupTimeEntries {
-    pub fn new(time_entries: Vec<ClickupTimeEntry>) -> Self {
-        Self(time_entries)
+    /// 1日の区切りでタイムエントリーを分割し、対象期間内の部分のみをタスク情報に変換する
+    pub(crate) fn into_split_task_records(self, date_range: &DateRange) -> TaskRecords {
+        TaskRecords::new(
+            self.0
+                .into_iter()
+                .flat_map(|entry| entry.into_split_task_records(date_range))
+                .collect(),
+        )
     }
 }
@@ -1,6 +1,6 @@
-use crate::domain::model::{DateRange, Jst, TaskDuration, TaskRecord};
+use crate::domain::model::{DateRange, TaskDuration, TaskLocation, TaskRecord};
 
-use chrono::{DateTime, Duration, FixedOffset};
+use chrono::{DateTime, Duration, FixedOffset, NaiveDate};
 
 #[derive(Debug, Clone, Eq, PartialOrd, Ord)]
 pub struct ClickupTimeEntry {
@@ -12,55 +12,193 @@ pub struct ClickupTimeEntry {
     pub task_name: String,
     pub task_url: String,
     pub task_status: String,
-    pub parent_list_name: String,
+    pub location: TaskLocation,
+    pub tags: Vec<String>,
 }
 
 impl ClickupTimeEntry {
     #[allow(clippy::too_many_arguments)]
     pub fn new(
         id: impl Into<String>,
-        duration: &str,
-        start: &str,
-        end: &str,
+        duration: Duration,
+        start: DateTime<FixedOffset>,
+        end: DateTime<FixedOffset>,
         task_id: impl Into<String>,
         task_name: impl Into<String>,
         task_url: impl Into<String>,
         task_status: impl Into<String>,
-        list_name: impl Into<String>,
+        location: TaskLocation,
+        tags: Vec<String>,
     ) -> Self {
         ClickupTimeEntry {
             id: id.into(),
-            duration: Duration::milliseconds(duration.parse::<i64>().unwrap()),
-            start: Jst::timestamp_millis(start.parse::<i64>().unwrap()),
-            end: Jst::timestamp_millis(end.parse::<i64>().unwrap()),
+            duration,
+            start,
+            end,
             task_id: task_id.into(),
             task_name: task_name.into(),
             task_url: task_url.into(),
             task_status: task_status.into(),
-            parent_list_name: list_name.into(),
+            location,
+            tags,
         }
     }
 }
 
+/// チャージコードを振り分けたタイムエントリー
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChargedTimeEntry {
+    pub entry: ClickupTimeEntry,
+    /// 規則あるいはカスタムフィールドで振り分けたチャージ名
+    pub charge_name: String,
+}
+
 impl PartialEq for ClickupTimeEntry {
     fn eq(&self, other: &Self) -> bool {
         self.task_id == other.task_id
     }
 }
 
-impl From<ClickupTimeEntry> for TaskRecord {
-    fn from(clickup_time_entry: ClickupTimeEntry) -> Self {
-        Self {
+impl ClickupTimeEntry {
+    pub(crate) fn into_task_record(self, date_range: &DateRange) -> TaskRecord {
+        // 開始時点の日時で対象日付を判定する
+        let target_date = date_range.convert_datetime_to_date(self.start);
+        let duration = self.duration;
+        self.task_record(target_date, duration, date_range)
+    }
+
+    /// 1日の区切りでタイムエントリーを分割し、対象日毎のタスク情報に変換する
+    /// 対象期間外の部分は切り落とし、所要時間は対象日に含まれる時間の割合で按分する
+    pub(crate) fn into_split_task_records(self, date_range: &DateRange) -> Vec<TaskRecord> {
+        let elapsed = (self.end - self.start).num_milliseconds();
+        if elapsed <= 0 {
+            // 計測中などで終了日時が定まっていない場合は分割しない
+            return vec![self.into_task_record(date_range)];
+        }
+
+        // 按分の端数は累積値で調整し、分割後の合計が元の所要時間と一致するようにする
+        let total = self.duration.num_milliseconds() as i128;
+        let mut offset = date_range
+            .start()
+            .max(self.start)
+            .signed_duration_since(self.start)
+            .num_milliseconds() as i128;
+        date_range
+            .split_by_date(self.start, self.end)
+            .into_iter()
+            .map(|(target_date, span)| {
+                let from = total * offset / elapsed as i128;
+                offset += span.num_milliseconds() as i128;
+                let to = total * offset / elapsed as i128;
+                let duration = Duration::milliseconds((to - from) as i64);
+                self.clone().task_record(target_date, duration, date_range)
+            })
+            .collect()
+    }
+
+    fn task_record(
+        self,
+        target_date: NaiveDate,
+        duration: Duration,
+        date_range: &DateRange,
+    ) -> TaskRecord {
+        let clickup_time_entry = self;
+        TaskRecord {
             task_id: clickup_time_entry.task_id,
             task_name: clickup_time_entry.task_name,
             task_url: clickup_time_entry.task_url,
             task_status: clickup_time_entry.task_status,
-            charge_name: clickup_time_entry.parent_list_name,
-            duration: TaskDuration::from(clickup_time_entry.duration),
-            // 開始時点の日時で対象日付を判定する
-            target_date: DateRange::convert_datetime_to_date(clickup_time_entry.start),
+            charge_code: clickup_time_entry.location.list_name.clone(),
+            charge_name: clickup_time_entry.location.list_name.clone(),
+            location: clickup_time_entry.location,
+            tags: clickup_time_entry.tags,
+            duration: TaskDuration::from(duration),
+            rounded_duration: TaskDuration::from(duration),
+            target_date,
             // 更新日時は終了時点のものを採用する
-            updated_at: clickup_time_entry.end,
+            updated_at: date_range.zone().localize(&clickup_time_entry.end),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::model::{DayBoundary, Zone};
+
+    fn entry(duration: i64, start: &str, end: &str) -> ClickupTimeEntry {
+        let datetime = |s: &str| {
+            Zone::default().datetime_from_local(
+                &chrono::NaiveDateTime::parse_from_str(s, "%Y/%m/%d %H:%M").unwrap(),
+            )
+        };
+        ClickupTimeEntry::new(
+            "entry",
+            Duration::milliseconds(duration),
+            datetime(start),
+            datetime(end),
+            "task",
+            "タスク",
+            "https://app.clickup.com/t/task",
+            "in progress",
+            TaskLocation::new("space", "スペース", "folder", "フォルダ", "list", "charge"),
+            Vec::new(),
+        )
+    }
+
+    fn ymd(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    #[test]
+    fn 区切りを跨ぐタイムエントリーは対象日毎に按分する() {
+        let date_range = DateRange::new(
+            Some("2023/04/03"),
+            Some("2023/04/04"),
+            DayBoundary::default(),
+        )
+        .unwrap();
+        // 午前3時から午前7時までのうち、計測していたのは3時間
+        let records = entry(3 * 60 * 60 * 1000, "2023/04/04 03:00", "2023/04/04 07:00")
+            .into_split_task_records(&date_range);
+
+        let split: Vec<_> = records
+            .iter()
+            .map(|record| (record.target_date, record.duration.num_seconds()))
+            .collect();
+        assert_eq!(
+            split,
+            vec![(ymd(2023, 4, 3), 90 * 60), (ymd(2023, 4, 4), 90 * 60)]
+        );
+    }
+
+    #[test]
+    fn 按分の端数は合計が一致するように調整する() {
+        let date_range = DateRange::new(
+            Some("2023/04/03"),
+            Some("2023/04/05"),
+            DayBoundary::default(),
+        )
+        .unwrap();
+        let records = entry(1000, "2023/04/03 20:00", "2023/04/05 14:00")
+            .into_split_task_records(&date_range);
+
+        assert_eq!(records.len(), 3);
+        let total: i64 = records
+            .iter()
+            .map(|record| record.duration.as_duration().num_milliseconds())
+            .sum();
+        assert_eq!(total, 1000);
+    }
+
+    #[test]
+    fn 対象期間外の部分は切り落とす() {
+        let date_range = DateRange::new(Some("2023/04/03"), None, DayBoundary::default()).unwrap();
+        let records = entry(4 * 60 * 60 * 1000, "2023/04/04 03:00", "2023/04/04 07:00")
+            .into_split_task_records(&date_range);
+
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].target_date, ymd(2023, 4, 3));
+        assert_eq!(records[0].duration.num_seconds(), 2 * 60 * 60);
+    }
+}
@@ -1,9 +1,11 @@
+mod clickup_custom_field;
 mod clickup_task;
 mod clickup_tasks;
 mod clickup_time_entries;
 mod clickup_time_entry;
 
+pub use clickup_custom_field::ClickupCustomField;
 pub use clickup_task::ClickupTask;
 pub use clickup_tasks::ClickupTasks;
 pub use clickup_time_entries::ClickupTimeEntries;
-pub use clickup_time_entry::ClickupTimeEntry;
+pub use clickup_time_entry::{ChargedTimeEntry, ClickupTimeEntry};
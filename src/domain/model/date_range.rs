@@ -1,41 +1,111 @@
 use std::str::FromStr;
+use std::sync::LazyLock;
 
-use super::Jst;
+use super::{BillingPeriod, WeekStart, Zone};
+use crate::error::AppError;
 use anyhow::{bail, Error, Ok, Result};
-use chrono::{DateTime, Duration, FixedOffset, NaiveDate, NaiveTime, Timelike};
+use chrono::{DateTime, Datelike, Duration, FixedOffset, NaiveDate, NaiveTime, Weekday};
+use regex::Regex;
+
+// 日付の表現のパターン。呼び出し毎にコンパイルしないよう、初回の利用時に一度だけ生成する
+static DATE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^(?P<y>\d{4})[/-](?P<m>\d{1,2})[/-](?P<d>\d{1,2})$").unwrap());
+static DAYS_AGO: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^-(?P<n>\d+)d$").unwrap());
+static ISO_WEEK: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^(?P<y>\d{4})-[Ww](?P<w>\d{1,2})$").unwrap());
+static YEAR_MONTH: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^(?P<y>\d{4})[/-](?P<m>\d{1,2})$").unwrap());
 
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
 pub struct DateRange {
     start_dt: StartDateTime,
     end_dt: EndDateTime,
+    day_boundary: DayBoundary,
+    /// 請求期間を指定した場合の見出し
+    period_label: Option<String>,
 }
 
 impl DateRange {
-    pub(crate) fn new<T: Into<String>>(start_date: Option<T>, end_date: Option<T>) -> Self {
-        // 日付文字列のパース
+    /// テストでは週の始まりを月曜として日付の表現を解決する
+    #[cfg(test)]
+    pub(crate) fn new<T: Into<String>>(
+        start_date: Option<T>,
+        end_date: Option<T>,
+        day_boundary: DayBoundary,
+    ) -> Result<Self> {
+        Self::parse(start_date, end_date, day_boundary, WeekStart::Mon)
+    }
+
+    /// 日付の表現を解決する。週を表す表現は `week_start` の曜日から始まる1週間とする
+    fn parse<T: Into<String>>(
+        start_date: Option<T>,
+        end_date: Option<T>,
+        day_boundary: DayBoundary,
+        week_start: WeekStart,
+    ) -> Result<Self> {
+        // 日付表現のパース
+        // 相対的な表現は、区切りの時刻を考慮した「今日」を基準に解決する
+        let today = TargetDate::new(day_boundary);
         let start = match start_date {
-            Some(date) => TargetDate::from_str(&date.into()).unwrap(),
-            None => TargetDate::new(),
+            Some(expr) => {
+                TargetPeriod::parse(&expr.into(), &today, week_start).map_err(usage_error)?
+            }
+            None => TargetPeriod::single(today.clone()),
         };
         let end = match end_date {
-            Some(date) => TargetDate::from_str(&date.into()).unwrap(),
+            Some(expr) => {
+                TargetPeriod::parse(&expr.into(), &today, week_start).map_err(usage_error)?
+            }
             None => start.clone(),
         };
+        // 始端は始端側の表現の初日、終端は終端側の表現の末日とする
+        let (start, end) = (start.first, end.last);
 
         // 始端日が終端日よりも前であるか検証する
-        Self::validate_start_under_end(&start, &end).unwrap();
+        Self::validate_start_under_end(&start, &end).map_err(usage_error)?;
 
         // DateTimeの生成
-        let start_dt = StartDateTime::from(start.0);
-        let end_dt = EndDateTime::from(end.0);
+        let start_dt = StartDateTime::new(start.0, day_boundary);
+        let end_dt = EndDateTime::new(end.0, day_boundary);
 
         // インスタンスを返す
-        Self { start_dt, end_dt }
+        Ok(Self {
+            start_dt,
+            end_dt,
+            day_boundary,
+            period_label: None,
+        })
+    }
+
+    /// 請求期間を指定した場合は請求期間を、そうでなければ日付の表現を解決する
+    pub(crate) fn resolve<T: Into<String>>(
+        start_date: Option<T>,
+        end_date: Option<T>,
+        period: Option<&BillingPeriod>,
+        day_boundary: DayBoundary,
+        week_start: WeekStart,
+    ) -> Result<Self> {
+        match period {
+            Some(period) => Ok(Self::of_period(period, day_boundary)),
+            None => Self::parse(start_date, end_date, day_boundary, week_start),
+        }
+    }
+
+    /// 請求期間の定義に従って、区切りの時刻を考慮した「今日」を基準に期間を解決する
+    pub(crate) fn of_period(period: &BillingPeriod, day_boundary: DayBoundary) -> Self {
+        let today = TargetDate::new(day_boundary);
+        let (label, first, last) = period.resolve(today.0);
+        Self {
+            start_dt: StartDateTime::new(first, day_boundary),
+            end_dt: EndDateTime::new(last, day_boundary),
+            day_boundary,
+            period_label: Some(label),
+        }
     }
     fn validate_start_under_end(start: &TargetDate, end: &TargetDate) -> Result<()> {
         if end < start {
             bail!(
-                "Start date must be before end date. \nInput start: {}, \nInput end  : {}",
+                "Start date must be before end date. Input start: {}, Input end: {}",
                 start.0,
                 end.0
             )
@@ -49,8 +119,8 @@ impl DateRange {
     pub(crate) fn end(&self) -> DateTime<FixedOffset> {
         self.end_dt.0
     }
-    pub(crate) fn start_date_str(&self) -> String {
-        self.start_dt.0.format("%Y/%m/%d").to_string()
+    pub(crate) fn start_date(&self) -> NaiveDate {
+        self.convert_datetime_to_date(self.start_dt.0)
     }
     pub(crate) fn start_datetime_str(&self) -> String {
         self.start_dt.0.format("%Y/%m/%dT%H:%M:%S").to_string()
@@ -64,10 +134,9 @@ impl DateRange {
     pub(crate) fn end_date_str(&self) -> String {
         self.end_dt.0.format("%Y/%m/%d").to_string()
     }
-    pub(crate) fn end_target_date_str(&self) -> String {
-        (self.end_dt.0.date_naive() + Duration::days(-1))
-            .format("%Y/%m/%d")
-            .to_string()
+    /// 終端日時は翌日の区切りの1秒前なので、区切りを考慮した日付を対象日とする
+    pub(crate) fn end_target_date(&self) -> NaiveDate {
+        self.convert_datetime_to_date(self.end_dt.0)
     }
     pub(crate) fn end_datetime_str(&self) -> String {
         self.end_dt.0.format("%Y/%m/%dT%H:%M:%S").to_string()
@@ -78,18 +147,133 @@ impl DateRange {
         self.end_dt.0.timestamp_millis()
     }
 
+    /// 請求期間を指定した場合の見出し（ "client-a:2023-04" 等）
+    pub(crate) fn period_label(&self) -> Option<&str> {
+        self.period_label.as_deref()
+    }
     /// start_date と end_date が同じ1日を表しているか判定
     pub(crate) fn is_same_date(&self) -> bool {
-        self.start_dt.0.date_naive() == self.end_dt.0.date_naive() + Duration::days(-1)
+        self.start_date() == self.end_target_date()
+    }
+
+    /// 始端日から終端日までの対象日を全て返す
+    pub(crate) fn target_dates(&self) -> Vec<NaiveDate> {
+        self.start_date()
+            .iter_days()
+            .take_while(|date| date <= &self.end_target_date())
+            .collect()
+    }
+
+    /// 対象日の始まりの日時
+    pub(crate) fn start_of(&self, date: NaiveDate) -> DateTime<FixedOffset> {
+        self.day_boundary.start_of(date)
     }
 
     /// 対象のDateTimeをNaiveDateに変換する
-    /// ただし、1日の始まりは午前5時とする
-    pub(crate) fn convert_datetime_to_date(dt: DateTime<FixedOffset>) -> NaiveDate {
-        if dt.hour() >= 5 {
-            dt.date_naive()
+    /// ただし、1日の始まりは区切りの時刻とする
+    pub(crate) fn convert_datetime_to_date(&self, dt: DateTime<FixedOffset>) -> NaiveDate {
+        self.day_boundary.date_of(&dt)
+    }
+
+    /// 集計に用いるタイムゾーン
+    pub(crate) fn zone(&self) -> Zone {
+        self.day_boundary.zone()
+    }
+
+    /// 開始日時から終了日時までの時間を、1日の区切りで対象日毎に分割する
+    /// 対象期間外の部分は切り落とし、対象日と対象日に含まれる時間の組を返す
+    pub(crate) fn split_by_date(
+        &self,
+        start: DateTime<FixedOffset>,
+        end: DateTime<FixedOffset>,
+    ) -> Vec<(NaiveDate, Duration)> {
+        // 終端日時は翌日の区切りの1秒前なので、区切りの時刻までを対象期間とする
+        let until = end.min(self.end_dt.0 + Duration::seconds(1));
+        let mut from = start.max(self.start_dt.0);
+        let mut spans = Vec::new();
+        while from < until {
+            let date = self.convert_datetime_to_date(from);
+            let to = self
+                .day_boundary
+                .start_of(date + Duration::days(1))
+                .min(until);
+            spans.push((date, to - from));
+            from = to;
+        }
+        spans
+    }
+}
+
+/// 日付の指定の誤りは、コマンドライン引数の誤りとして扱う
+fn usage_error(error: Error) -> AppError {
+    AppError::Usage(error.to_string())
+}
+
+/// 1日の区切りとなる時刻
+/// 区切りの時刻はタイムゾーンの現地時刻とし、区切りより前の時刻は前日の作業として扱う
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub(crate) struct DayBoundary {
+    time: NaiveTime,
+    zone: Zone,
+}
+
+impl DayBoundary {
+    /// 区切りの時刻を、指定したタイムゾーンの現地時刻とする
+    pub(crate) fn in_zone(self, zone: Zone) -> Self {
+        Self { zone, ..self }
+    }
+
+    pub(crate) fn zone(&self) -> Zone {
+        self.zone
+    }
+
+    /// 対象日の始まりの日時
+    fn start_of(&self, date: NaiveDate) -> DateTime<FixedOffset> {
+        self.zone.datetime_from_local(&date.and_time(self.time))
+    }
+
+    /// 対象日の終わりの日時。翌日の始まりの1秒前とする
+    /// 夏時間の切り替え日は、1日の長さが24時間にならない
+    fn end_of(&self, date: NaiveDate) -> DateTime<FixedOffset> {
+        self.zone
+            .localize(&(self.start_of(date + Duration::days(1)) - Duration::seconds(1)))
+    }
+
+    /// 日時が属する対象日
+    fn date_of(&self, dt: &DateTime<FixedOffset>) -> NaiveDate {
+        let local_date = self.zone.localize(dt).date_naive();
+        if *dt >= self.start_of(local_date) {
+            local_date
         } else {
-            dt.date_naive() - Duration::days(-1)
+            local_date - Duration::days(1)
+        }
+    }
+}
+
+impl Default for DayBoundary {
+    /// 日本標準時の午前5時
+    fn default() -> Self {
+        Self {
+            time: NaiveTime::from_hms_opt(5, 0, 0).unwrap(),
+            zone: Zone::default(),
+        }
+    }
+}
+
+impl FromStr for DayBoundary {
+    type Err = Error;
+
+    /// タイムゾーンは日本標準時とする。変更する場合は `in_zone` で指定する
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let time = NaiveTime::parse_from_str(s, "%H:%M:%S")
+            .or_else(|_| NaiveTime::parse_from_str(s, "%H:%M"))
+            .ok();
+        match time {
+            Some(time) => Ok(Self {
+                time,
+                zone: Zone::default(),
+            }),
+            None => bail!("Invalid format. It must be 'HH:MM'. Input is '{}'", s),
         }
     }
 }
@@ -98,14 +282,9 @@ impl DateRange {
 struct TargetDate(NaiveDate);
 
 impl TargetDate {
-    fn new() -> Self {
-        // 午前5時以降なら今日、以前なら昨日として出力する
-        let border = NaiveTime::from_hms_opt(5, 0, 0).unwrap();
-        if border < Jst::now().time() {
-            Self(Jst::today())
-        } else {
-            Self(Jst::today() + Duration::days(-1))
-        }
+    fn new(day_boundary: DayBoundary) -> Self {
+        // 区切りの時刻以降なら今日、以前なら昨日として出力する
+        Self(day_boundary.date_of(&day_boundary.zone().now()))
     }
 }
 
@@ -113,29 +292,150 @@ impl FromStr for TargetDate {
     type Err = Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let date_jst = Jst::date_from_str(s)?;
-        Ok(Self(date_jst))
+        let Some(caps) = DATE.captures(s) else {
+            bail!("Invalid format. It must be 'YYYY/MM/DD'. Input is '{}'", s)
+        };
+        let year = caps["y"].parse::<i32>().unwrap();
+        let month = caps["m"].parse::<u32>().unwrap();
+        let day = caps["d"].parse::<u32>().unwrap();
+        match NaiveDate::from_ymd_opt(year, month, day) {
+            Some(date) => Ok(Self(date)),
+            None => bail!("No such date. Input is '{}'", s),
+        }
+    }
+}
+
+/// 日付の表現が表す期間
+/// 単一の日付の場合は、初日と末日が同じ日になる
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct TargetPeriod {
+    first: TargetDate,
+    last: TargetDate,
+}
+
+impl TargetPeriod {
+    fn single(date: TargetDate) -> Self {
+        Self {
+            first: date.clone(),
+            last: date,
+        }
+    }
+
+    fn between(first: NaiveDate, last: NaiveDate) -> Self {
+        Self {
+            first: TargetDate(first),
+            last: TargetDate(last),
+        }
+    }
+
+    /// 日付の表現をパースする。相対的な表現は `today` を基準に解決する
+    ///
+    /// - `today` , `yesterday` , `-3d` : 今日・昨日・N日前
+    /// - `this-week` , `last-week` : 今週・先週（ `week_start` の曜日始まり）
+    /// - `this-month` , `last-month` : 今月・先月
+    /// - `2023-W14` : ISO週番号の週の月曜を含む1週間
+    /// - `2023/04` : 1か月間
+    /// - `2023/04/01..2023/04/15` : 両端の表現を含む期間
+    /// - `2023/04/01` : 1日
+    fn parse(s: &str, today: &TargetDate, week_start: WeekStart) -> Result<Self> {
+        let s = s.trim();
+        if let Some((first, last)) = s.split_once("..") {
+            let first = Self::parse_expression(first.trim(), today, week_start)?;
+            let last = Self::parse_expression(last.trim(), today, week_start)?;
+            if last.last < first.first {
+                bail!(
+                    "Start date must be before end date. Input start: {}, Input end: {}",
+                    first.first.0,
+                    last.last.0
+                )
+            }
+            return Ok(Self {
+                first: first.first,
+                last: last.last,
+            });
+        }
+        Self::parse_expression(s, today, week_start)
+    }
+
+    fn parse_expression(s: &str, today: &TargetDate, week_start: WeekStart) -> Result<Self> {
+        let today = today.0;
+        match s.to_lowercase().as_str() {
+            "today" => return Ok(Self::between(today, today)),
+            "yesterday" => {
+                let yesterday = today - Duration::days(1);
+                return Ok(Self::between(yesterday, yesterday));
+            }
+            "this-week" => return Ok(Self::week_of(today, week_start)),
+            "last-week" => return Ok(Self::week_of(today - Duration::weeks(1), week_start)),
+            "this-month" => return Self::month_of(today.year(), today.month(), s),
+            "last-month" => {
+                let last_month = today.with_day(1).unwrap() - Duration::days(1);
+                return Self::month_of(last_month.year(), last_month.month(), s);
+            }
+            _ => {}
+        }
+
+        if let Some(caps) = DAYS_AGO.captures(s) {
+            let Some(date) = caps["n"]
+                .parse::<i64>()
+                .ok()
+                .and_then(|n| today.checked_sub_signed(Duration::days(n)))
+            else {
+                bail!("No such date. Input is '{}'", s)
+            };
+            return Ok(Self::between(date, date));
+        }
+        if let Some(caps) = ISO_WEEK.captures(s) {
+            let year = caps["y"].parse::<i32>().unwrap();
+            let week = caps["w"].parse::<u32>().unwrap();
+            return match NaiveDate::from_isoywd_opt(year, week, Weekday::Mon) {
+                Some(monday) => Ok(Self::week_of(monday, week_start)),
+                None => bail!("No such week. Input is '{}'", s),
+            };
+        }
+        if let Some(caps) = YEAR_MONTH.captures(s) {
+            let year = caps["y"].parse::<i32>().unwrap();
+            let month = caps["m"].parse::<u32>().unwrap();
+            return Self::month_of(year, month, s);
+        }
+        TargetDate::from_str(s).map(Self::single)
+    }
+
+    /// 対象日を含む `week_start` の曜日始まりの1週間
+    fn week_of(date: NaiveDate, week_start: WeekStart) -> Self {
+        let first = date - Duration::days(date.weekday().days_since(week_start.weekday()) as i64);
+        Self::between(first, first + Duration::days(6))
+    }
+
+    /// 対象月の1日から末日まで
+    fn month_of(year: i32, month: u32, s: &str) -> Result<Self> {
+        let Some(first) = NaiveDate::from_ymd_opt(year, month, 1) else {
+            bail!("No such month. Input is '{}'", s)
+        };
+        let next_month = match month {
+            12 => NaiveDate::from_ymd_opt(year + 1, 1, 1),
+            _ => NaiveDate::from_ymd_opt(year, month + 1, 1),
+        };
+        let last = next_month.unwrap() - Duration::days(1);
+        Ok(Self::between(first, last))
     }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
 struct StartDateTime(DateTime<FixedOffset>);
 
-impl From<NaiveDate> for StartDateTime {
-    fn from(from: NaiveDate) -> Self {
-        let local = from.and_hms_opt(5, 0, 0).unwrap();
-        Self(Jst::offset_datetime_from_native_datetime(&local))
+impl StartDateTime {
+    fn new(date: NaiveDate, day_boundary: DayBoundary) -> Self {
+        Self(day_boundary.start_of(date))
     }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
 struct EndDateTime(DateTime<FixedOffset>);
 
-impl From<NaiveDate> for EndDateTime {
-    fn from(from: NaiveDate) -> Self {
-        let local = from.and_hms_opt(4, 59, 59).unwrap();
-        let local_tomorrow = local + Duration::days(1);
-        Self(Jst::offset_datetime_from_native_datetime(&local_tomorrow))
+impl EndDateTime {
+    fn new(date: NaiveDate, day_boundary: DayBoundary) -> Self {
+        Self(day_boundary.end_of(date))
     }
 }
 
@@ -143,31 +443,50 @@ impl From<NaiveDate> for EndDateTime {
 mod tests {
 
     use super::*;
-    use chrono::{Duration, Local, NaiveTime};
+    use chrono::{Duration, Local, NaiveTime, TimeZone};
+
+    fn ymd(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    fn today() -> NaiveDate {
+        Zone::default().now().date_naive()
+    }
+
+    fn jst(y: i32, m: u32, d: u32, h: u32, mi: u32, sec: u32) -> DateTime<FixedOffset> {
+        FixedOffset::east_opt(9 * 60 * 60)
+            .unwrap()
+            .with_ymd_and_hms(y, m, d, h, mi, sec)
+            .unwrap()
+    }
 
     mod test_of_target_date_time_by_single_date {
         use super::*;
 
         #[test]
         fn create_with_some_start_date() {
-            let dt = DateRange::new(Some("2012/12/31"), None);
-            assert_eq!(dt.start().date_naive(), Jst::ymd(2012, 12, 31));
+            let dt = DateRange::new(Some("2012/12/31"), None, DayBoundary::default()).unwrap();
+            assert_eq!(dt.start().date_naive(), ymd(2012, 12, 31));
         }
 
         #[test]
         fn create_with_no_start_date() {
-            let dt = DateRange::new::<&str>(None, None);
+            let dt = DateRange::new::<&str>(None, None, DayBoundary::default()).unwrap();
 
             // 時間帯によって対象日が変化するが、
             // それはTargetDateの責務なので、ここでは簡単に済ませる。
-            assert_eq!(dt.start().date_naive(), TargetDate::new().0)
+            assert_eq!(
+                dt.start().date_naive(),
+                TargetDate::new(DayBoundary::default()).0
+            )
         }
 
         #[test]
         fn compare_target_date_time() {
-            let past_dt = DateRange::new(Some("2012/12/31"), None);
-            let current_dt = DateRange::new::<&str>(None, None);
-            let future_dt = DateRange::new(Some("2222/02/22"), None);
+            let past_dt = DateRange::new(Some("2012/12/31"), None, DayBoundary::default()).unwrap();
+            let current_dt = DateRange::new::<&str>(None, None, DayBoundary::default()).unwrap();
+            let future_dt =
+                DateRange::new(Some("2222/02/22"), None, DayBoundary::default()).unwrap();
 
             assert!(past_dt == past_dt);
             assert!(current_dt == current_dt);
@@ -180,45 +499,62 @@ mod tests {
 
         #[test]
         fn start_less_than_end() {
-            let dt = DateRange::new::<&str>(None, None);
+            let dt = DateRange::new::<&str>(None, None, DayBoundary::default()).unwrap();
             assert!(dt.start() < dt.end());
         }
 
         #[test]
         #[should_panic = "assertion failed: dt.end() < dt.start()"]
         fn end_less_than_start() {
-            let dt = DateRange::new::<&str>(None, None);
+            let dt = DateRange::new::<&str>(None, None, DayBoundary::default()).unwrap();
             assert!(dt.end() < dt.start());
         }
     }
 
     mod test_of_target_date_time_by_period {
-        use chrono::TimeZone;
-
         use super::*;
 
         #[test]
         fn create_with_some_dates() {
-            let dt = DateRange::new(Some("2010/12/31"), Some("2022/07/13"));
+            let dt = DateRange::new(
+                Some("2010/12/31"),
+                Some("2022/07/13"),
+                DayBoundary::default(),
+            )
+            .unwrap();
 
+            assert_eq!(dt.start(), jst(2010, 12, 31, 5, 0, 0));
+            assert_eq!(dt.end(), jst(2022, 7, 14, 4, 59, 59));
+        }
+
+        #[test]
+        fn target_dates_contains_every_date() {
+            let dt = DateRange::new(
+                Some("2022/12/30"),
+                Some("2023/01/02"),
+                DayBoundary::default(),
+            )
+            .unwrap();
             assert_eq!(
-                dt.start(),
-                Jst::offset()
-                    .with_ymd_and_hms(2010, 12, 31, 5, 0, 0)
-                    .unwrap()
-            );
-            assert_eq!(
-                dt.end(),
-                Jst::offset()
-                    .with_ymd_and_hms(2022, 7, 14, 4, 59, 59)
-                    .unwrap()
+                dt.target_dates(),
+                vec![
+                    ymd(2022, 12, 30),
+                    ymd(2022, 12, 31),
+                    ymd(2023, 1, 1),
+                    ymd(2023, 1, 2),
+                ]
             );
         }
 
         #[test]
         #[should_panic = "Start date must be before end date."]
         fn end_less_than_start() {
-            let _ = DateRange::new(Some("2010/12/31"), Some("2010/12/30"));
+            let _ = DateRange::new(
+                Some("2010/12/31"),
+                Some("2010/12/30"),
+                DayBoundary::default(),
+            )
+            .unwrap();
         }
     }
 
@@ -257,7 +593,7 @@ mod tests {
         }
 
         #[test]
-        #[should_panic = "No such local time"]
+        #[should_panic = "No such date"]
         fn date_str_has_incorrect_numbers() {
             let dt = TargetDate::from_str("2022/13/32").unwrap();
             dbg!(&dt);
@@ -265,7 +601,7 @@ mod tests {
 
         #[test]
         fn check_date() {
-            let target_date = TargetDate::new();
+            let target_date = TargetDate::new(DayBoundary::default());
             let today = Local::now().date_naive();
             let yesterday = today + Duration::days(-1);
             let border = NaiveTime::from_hms_opt(5, 0, 0).unwrap();
@@ -278,19 +614,131 @@ mod tests {
         }
     }
 
+    mod test_of_target_period {
+        use super::*;
+
+        /// 2023/04/12（水）を今日とする
+        fn parse(s: &str) -> (NaiveDate, NaiveDate) {
+            let period =
+                TargetPeriod::parse(s, &TargetDate(ymd(2023, 4, 12)), WeekStart::Mon).unwrap();
+            (period.first.0, period.last.0)
+        }
+
+        #[test]
+        fn relative_days() {
+            assert_eq!(parse("today"), (ymd(2023, 4, 12), ymd(2023, 4, 12)));
+            assert_eq!(parse("yesterday"), (ymd(2023, 4, 11), ymd(2023, 4, 11)));
+            assert_eq!(parse("-3d"), (ymd(2023, 4, 9), ymd(2023, 4, 9)));
+            assert_eq!(parse("-12d"), (ymd(2023, 3, 31), ymd(2023, 3, 31)));
+        }
+
+        #[test]
+        fn relative_weeks_start_on_monday() {
+            assert_eq!(parse("this-week"), (ymd(2023, 4, 10), ymd(2023, 4, 16)));
+            assert_eq!(parse("last-week"), (ymd(2023, 4, 3), ymd(2023, 4, 9)));
+        }
+
+        #[test]
+        fn weeks_follow_week_start() {
+            let sunday = |s: &str| {
+                let period =
+                    TargetPeriod::parse(s, &TargetDate(ymd(2023, 4, 12)), WeekStart::Sun).unwrap();
+                (period.first.0, period.last.0)
+            };
+            assert_eq!(sunday("this-week"), (ymd(2023, 4, 9), ymd(2023, 4, 15)));
+            assert_eq!(sunday("last-week"), (ymd(2023, 4, 2), ymd(2023, 4, 8)));
+            // 週番号は、週に含まれる月曜のISO週番号とする
+            assert_eq!(sunday("2023-W14"), (ymd(2023, 4, 2), ymd(2023, 4, 8)));
+        }
+
+        #[test]
+        fn relative_months() {
+            assert_eq!(parse("this-month"), (ymd(2023, 4, 1), ymd(2023, 4, 30)));
+            assert_eq!(parse("last-month"), (ymd(2023, 3, 1), ymd(2023, 3, 31)));
+
+            let new_year =
+                TargetPeriod::parse("last-month", &TargetDate(ymd(2023, 1, 15)), WeekStart::Mon)
+                    .unwrap();
+            assert_eq!(new_year.first.0, ymd(2022, 12, 1));
+            assert_eq!(new_year.last.0, ymd(2022, 12, 31));
+        }
+
+        #[test]
+        fn iso_week() {
+            assert_eq!(parse("2023-W14"), (ymd(2023, 4, 3), ymd(2023, 4, 9)));
+            // ISO週の第1週は前年の12月から始まることがある
+            assert_eq!(parse("2020-W01"), (ymd(2019, 12, 30), ymd(2020, 1, 5)));
+            assert!(
+                TargetPeriod::parse("2023-W54", &TargetDate(ymd(2023, 4, 12)), WeekStart::Mon)
+                    .is_err()
+            );
+        }
+
+        #[test]
+        fn whole_month() {
+            assert_eq!(parse("2023/04"), (ymd(2023, 4, 1), ymd(2023, 4, 30)));
+            assert_eq!(parse("2024-02"), (ymd(2024, 2, 1), ymd(2024, 2, 29)));
+            assert_eq!(parse("2023/12"), (ymd(2023, 12, 1), ymd(2023, 12, 31)));
+            assert!(
+                TargetPeriod::parse("2023/13", &TargetDate(ymd(2023, 4, 12)), WeekStart::Mon)
+                    .is_err()
+            );
+        }
+
+        #[test]
+        fn range_of_expressions() {
+            assert_eq!(
+                parse("2023/04/01..2023/04/15"),
+                (ymd(2023, 4, 1), ymd(2023, 4, 15))
+            );
+            assert_eq!(
+                parse("2023/03..2023-W14"),
+                (ymd(2023, 3, 1), ymd(2023, 4, 9))
+            );
+            assert_eq!(
+                parse("last-week..today"),
+                (ymd(2023, 4, 3), ymd(2023, 4, 12))
+            );
+            assert!(TargetPeriod::parse(
+                "2023/04/15..2023/04/01",
+                &TargetDate(ymd(2023, 4, 12)),
+                WeekStart::Mon
+            )
+            .is_err());
+        }
+
+        #[test]
+        #[should_panic = "Invalid format. It must be 'YYYY/MM/DD'. Input is 'next-week'"]
+        fn unknown_expression() {
+            parse("next-week");
+        }
+
+        #[test]
+        fn date_range_uses_first_and_last_of_expressions() {
+            let month = DateRange::new(Some("2023/04"), None, DayBoundary::default()).unwrap();
+            assert_eq!(month.start(), jst(2023, 4, 1, 5, 0, 0));
+            assert_eq!(month.end(), jst(2023, 5, 1, 4, 59, 59));
+
+            let weeks =
+                DateRange::new(Some("2023-W14"), Some("2023-W15"), DayBoundary::default()).unwrap();
+            assert_eq!(weeks.start_date(), ymd(2023, 4, 3));
+            assert_eq!(weeks.end_target_date(), ymd(2023, 4, 16));
+        }
+    }
+
     mod test_of_target_start_date_time {
         use super::*;
 
         #[test]
         fn check_start_date() {
-            let today = Jst::today();
-            let start = StartDateTime::from(today);
+            let today = today();
+            let start = StartDateTime::new(today, DayBoundary::default());
             assert_eq!(start.0.date_naive(), today);
         }
 
         #[test]
         fn check_start_hhmiss() {
-            let start = StartDateTime::from(Jst::today());
+            let start = StartDateTime::new(today(), DayBoundary::default());
             assert_eq!(start.0.time(), NaiveTime::from_hms_opt(5, 0, 0).unwrap());
         }
     }
@@ -300,15 +748,235 @@ mod tests {
 
         #[test]
         fn check_end_date() {
-            let today = Jst::today();
-            let end = EndDateTime::from(today);
+            let today = today();
+            let end = EndDateTime::new(today, DayBoundary::default());
             assert_eq!(end.0.date_naive(), today + Duration::days(1));
         }
 
         #[test]
         fn check_end_hhmiss() {
-            let end = EndDateTime::from(Jst::today());
+            let end = EndDateTime::new(today(), DayBoundary::default());
             assert_eq!(end.0.time(), NaiveTime::from_hms_opt(4, 59, 59).unwrap());
         }
     }
+
+    mod test_of_split_by_date {
+        use super::*;
+
+        #[test]
+        fn split_at_day_boundary() {
+            let dt = DateRange::new(
+                Some("2023/04/03"),
+                Some("2023/04/04"),
+                DayBoundary::default(),
+            )
+            .unwrap();
+
+            // 午前3時から午前7時までの作業は、区切りの前後で別の日の作業とする
+            assert_eq!(
+                dt.split_by_date(jst(2023, 4, 4, 3, 0, 0), jst(2023, 4, 4, 7, 0, 0)),
+                vec![
+                    (ymd(2023, 4, 3), Duration::hours(2)),
+                    (ymd(2023, 4, 4), Duration::hours(2)),
+                ]
+            );
+            assert_eq!(
+                dt.split_by_date(jst(2023, 4, 3, 9, 0, 0), jst(2023, 4, 3, 10, 30, 0)),
+                vec![(ymd(2023, 4, 3), Duration::minutes(90))]
+            );
+        }
+
+        #[test]
+        fn clip_outside_of_date_range() {
+            let dt = DateRange::new(Some("2023/04/03"), None, DayBoundary::default()).unwrap();
+
+            assert_eq!(
+                dt.split_by_date(jst(2023, 4, 3, 4, 0, 0), jst(2023, 4, 3, 6, 0, 0)),
+                vec![(ymd(2023, 4, 3), Duration::hours(1))]
+            );
+            assert_eq!(
+                dt.split_by_date(jst(2023, 4, 4, 4, 0, 0), jst(2023, 4, 4, 6, 0, 0)),
+                vec![(ymd(2023, 4, 3), Duration::hours(1))]
+            );
+            assert_eq!(
+                dt.split_by_date(jst(2023, 4, 2, 9, 0, 0), jst(2023, 4, 2, 10, 0, 0)),
+                vec![]
+            );
+        }
+
+        #[test]
+        fn split_in_configured_zone() {
+            let berlin = DayBoundary::default().in_zone(Zone::from_str("Europe/Berlin").unwrap());
+            let dt = DateRange::new(Some("2023/03/25"), Some("2023/03/26"), berlin).unwrap();
+            let start = DateTime::parse_from_rfc3339("2023-03-26T03:00:00+01:00").unwrap();
+            let end = DateTime::parse_from_rfc3339("2023-03-26T07:00:00+02:00").unwrap();
+
+            // 夏時間の切り替えを挟むので、現地時刻の差より1時間短い
+            assert_eq!(
+                dt.split_by_date(start, end),
+                vec![
+                    (ymd(2023, 3, 25), Duration::hours(1)),
+                    (ymd(2023, 3, 26), Duration::hours(2)),
+                ]
+            );
+        }
+    }
+
+    mod test_of_day_boundary {
+        use super::*;
+
+        #[test]
+        fn parse_boundary() {
+            assert_eq!(
+                DayBoundary::from_str("08:00").unwrap(),
+                DayBoundary {
+                    time: NaiveTime::from_hms_opt(8, 0, 0).unwrap(),
+                    zone: Zone::default(),
+                }
+            );
+            assert_eq!(
+                DayBoundary::from_str("00:00:00").unwrap(),
+                DayBoundary {
+                    time: NaiveTime::from_hms_opt(0, 0, 0).unwrap(),
+                    zone: Zone::default(),
+                }
+            );
+            assert!(DayBoundary::from_str("8am").is_err());
+            assert!(DayBoundary::from_str("24:00").is_err());
+        }
+
+        #[test]
+        fn date_range_with_eight_oclock_boundary() {
+            let boundary = DayBoundary::from_str("08:00").unwrap();
+            let dt = DateRange::new(Some("2023/04/03"), Some("2023/04/04"), boundary).unwrap();
+
+            assert_eq!(dt.start(), jst(2023, 4, 3, 8, 0, 0));
+            assert_eq!(dt.end(), jst(2023, 4, 5, 7, 59, 59));
+            assert_eq!(dt.start_date(), ymd(2023, 4, 3));
+            assert_eq!(dt.end_target_date(), ymd(2023, 4, 4));
+            assert_eq!(dt.target_dates(), vec![ymd(2023, 4, 3), ymd(2023, 4, 4)]);
+        }
+
+        #[test]
+        fn date_range_with_midnight_boundary() {
+            let boundary = DayBoundary::from_str("00:00").unwrap();
+            let dt = DateRange::new(Some("2023/04/03"), None, boundary).unwrap();
+
+            assert_eq!(dt.start(), jst(2023, 4, 3, 0, 0, 0));
+            assert_eq!(dt.end(), jst(2023, 4, 3, 23, 59, 59));
+            assert_eq!(dt.end_target_date(), ymd(2023, 4, 3));
+            assert!(dt.is_same_date());
+        }
+
+        #[test]
+        fn datetime_before_boundary_belongs_to_previous_date() {
+            let dt = DateRange::new(Some("2023/04/03"), None, DayBoundary::default()).unwrap();
+
+            assert_eq!(
+                dt.convert_datetime_to_date(jst(2023, 4, 4, 4, 59, 59)),
+                ymd(2023, 4, 3)
+            );
+            assert_eq!(
+                dt.convert_datetime_to_date(jst(2023, 4, 4, 5, 0, 0)),
+                ymd(2023, 4, 4)
+            );
+        }
+
+        #[test]
+        fn datetime_is_converted_with_configured_boundary() {
+            let night_shift = DateRange::new(
+                Some("2023/04/03"),
+                None,
+                DayBoundary::from_str("08:00").unwrap(),
+            )
+            .unwrap();
+            let midnight = DateRange::new(
+                Some("2023/04/03"),
+                None,
+                DayBoundary::from_str("00:00").unwrap(),
+            )
+            .unwrap();
+
+            // 夜勤明けの午前7時は前日の作業とする
+            assert_eq!(
+                night_shift.convert_datetime_to_date(jst(2023, 4, 4, 7, 0, 0)),
+                ymd(2023, 4, 3)
+            );
+            assert_eq!(
+                midnight.convert_datetime_to_date(jst(2023, 4, 4, 0, 0, 0)),
+                ymd(2023, 4, 4)
+            );
+            assert_eq!(
+                midnight.convert_datetime_to_date(jst(2023, 4, 3, 23, 59, 59)),
+                ymd(2023, 4, 3)
+            );
+        }
+
+        #[test]
+        fn day_before_spring_forward_is_23_hours() {
+            let berlin = DayBoundary::default().in_zone(Zone::from_str("Europe/Berlin").unwrap());
+            let dt = DateRange::new(Some("2023/03/25"), None, berlin).unwrap();
+
+            assert_eq!(dt.start().to_rfc3339(), "2023-03-25T05:00:00+01:00");
+            assert_eq!(dt.end().to_rfc3339(), "2023-03-26T04:59:59+02:00");
+            assert_eq!(
+                dt.end() - dt.start(),
+                Duration::hours(23) - Duration::seconds(1)
+            );
+            assert!(dt.is_same_date());
+        }
+
+        #[test]
+        fn boundary_in_spring_forward_gap_starts_after_transition() {
+            let berlin = DayBoundary::from_str("02:30")
+                .unwrap()
+                .in_zone(Zone::from_str("Europe/Berlin").unwrap());
+            let dt = DateRange::new(Some("2023/03/25"), Some("2023/03/26"), berlin).unwrap();
+            let before_transition =
+                DateTime::parse_from_rfc3339("2023-03-26T01:59:59+01:00").unwrap();
+
+            assert_eq!(dt.start().to_rfc3339(), "2023-03-25T02:30:00+01:00");
+            assert_eq!(dt.end().to_rfc3339(), "2023-03-27T02:29:59+02:00");
+            assert_eq!(
+                dt.convert_datetime_to_date(before_transition),
+                ymd(2023, 3, 25)
+            );
+            assert_eq!(
+                dt.convert_datetime_to_date(before_transition + Duration::seconds(1)),
+                ymd(2023, 3, 26)
+            );
+        }
+
+        #[test]
+        fn boundary_in_fall_back_overlap_starts_at_first_occurrence() {
+            let new_york = DayBoundary::from_str("01:30")
+                .unwrap()
+                .in_zone(Zone::from_str("America/New_York").unwrap());
+            let dt = DateRange::new(Some("2023/11/05"), None, new_york).unwrap();
+            // 2回目の午前1時10分は、1回目の午前1時30分より後になる
+            let second_occurrence =
+                DateTime::parse_from_rfc3339("2023-11-05T01:10:00-05:00").unwrap();
+
+            assert_eq!(dt.start().to_rfc3339(), "2023-11-05T01:30:00-04:00");
+            assert_eq!(dt.end().to_rfc3339(), "2023-11-06T01:29:59-05:00");
+            assert_eq!(
+                dt.convert_datetime_to_date(second_occurrence),
+                ymd(2023, 11, 5)
+            );
+        }
+
+        #[test]
+        fn datetime_is_converted_in_configured_zone() {
+            let los_angeles =
+                DayBoundary::default().in_zone(Zone::from_str("America/Los_Angeles").unwrap());
+            let dt = DateRange::new(Some("2023/04/03"), None, los_angeles).unwrap();
+
+            // 日本時間では4月4日の午後8時だが、ロサンゼルスでは4月4日の午前4時なので前日の作業とする
+            assert_eq!(
+                dt.convert_datetime_to_date(jst(2023, 4, 4, 20, 0, 0)),
+                ymd(2023, 4, 3)
+            );
+            assert_eq!(dt.start().to_rfc3339(), "2023-04-03T05:00:00-07:00");
+        }
+    }
 }
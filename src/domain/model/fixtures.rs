@@ -0,0 +1,37 @@
+//! テストで共通して利用する集計元データ
+
+use super::{TaskLocation, TaskRecord, Zone};
+use chrono::{Duration, NaiveDate};
+
+/// 2023/04/DD の12時に更新したタスクの記録
+/// リストはチャージコードと同じ名前とし、他の項目を変える場合は構造体更新記法で上書きする
+pub(crate) fn gen_task_record(
+    task_id: &str,
+    charge_name: &str,
+    day: u32,
+    minutes: i64,
+) -> TaskRecord {
+    let target_date = NaiveDate::from_ymd_opt(2023, 4, day).unwrap();
+    TaskRecord {
+        task_id: task_id.to_string(),
+        task_name: format!("task-{}", task_id),
+        task_url: format!("https://app.clickup.com/t/{}", task_id),
+        task_status: "in progress".to_string(),
+        charge_code: charge_name.to_string(),
+        charge_name: charge_name.to_string(),
+        location: TaskLocation::new(
+            "space-1",
+            "space",
+            "folder-1",
+            "folder",
+            charge_name,
+            charge_name,
+        ),
+        tags: Vec::new(),
+        duration: Duration::minutes(minutes).into(),
+        rounded_duration: Duration::minutes(minutes).into(),
+        target_date,
+        updated_at: Zone::default()
+            .datetime_from_local(&target_date.and_hms_opt(12, 0, 0).unwrap()),
+    }
+}
@@ -0,0 +1,40 @@
+use super::Messages;
+
+pub(super) const MESSAGES: Messages = Messages {
+    html_lang: "en",
+    date_format: "%Y-%m-%d",
+    datetime_format: "%Y-%m-%d %H:%M:%S",
+    excel_date_format: "yyyy-mm-dd",
+    excel_datetime_format: "yyyy-mm-dd hh:mm:ss",
+
+    date_title: "Target date",
+    period_title: "Target period",
+    title_delimiter: ": ",
+    period_delimiter: " to ",
+
+    grand_total: "Grand total",
+    subtotal: "Subtotal",
+
+    target_date: "Date",
+    updated_at: "Last updated",
+    total_duration: "Duration",
+    rounded_duration: "Rounded duration",
+    charge_name: "Charge code",
+    space_name: "Space",
+    folder_name: "Folder",
+    list_name: "List",
+    task_status: "Status",
+    task_name: "Task",
+    week: "Week",
+    month: "Month",
+    quarter: "Quarter",
+
+    task_and_total_period: "By task (whole period)",
+    task_and_daily: "By task (daily)",
+    charge_and_total_period: "By charge (whole period)",
+    charge_and_daily: "By charge (daily)",
+
+    charts: "Charts by charge",
+    daily_chart: "Working hours by date and charge",
+    share_chart: "Share of working hours by charge",
+};
@@ -0,0 +1,40 @@
+use super::Messages;
+
+pub(super) const MESSAGES: Messages = Messages {
+    html_lang: "ja",
+    date_format: "%Y/%m/%d",
+    datetime_format: "%Y/%m/%d %H:%M:%S",
+    excel_date_format: "yyyy/mm/dd",
+    excel_datetime_format: "yyyy/mm/dd hh:mm:ss",
+
+    date_title: "集計対象日付",
+    period_title: "集計対象期間",
+    title_delimiter: "：",
+    period_delimiter: " ～ ",
+
+    grand_total: "総合計",
+    subtotal: "小計",
+
+    target_date: "対象日",
+    updated_at: "最終更新日時",
+    total_duration: "所要時間",
+    rounded_duration: "丸めた所要時間",
+    charge_name: "チャージコード",
+    space_name: "スペース",
+    folder_name: "フォルダ",
+    list_name: "リスト",
+    task_status: "ステータス",
+    task_name: "タスク名",
+    week: "週",
+    month: "月",
+    quarter: "四半期",
+
+    task_and_total_period: "タスク毎（対象期間）",
+    task_and_daily: "タスク毎（日毎）",
+    charge_and_total_period: "チャージ毎（対象期間）",
+    charge_and_daily: "チャージ毎（日毎）",
+
+    charts: "チャージ毎のグラフ",
+    daily_chart: "日毎・チャージ毎の作業時間",
+    share_chart: "チャージ毎の作業時間の割合",
+};
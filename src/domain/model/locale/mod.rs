@@ -0,0 +1,75 @@
+mod en;
+mod ja;
+
+use serde_derive::Serialize;
+
+/// 出力時の言語
+/// 言語を追加する場合は、メッセージカタログのモジュールを追加し、ここに列挙子を加える
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, clap::ValueEnum)]
+#[serde(rename_all = "kebab-case")]
+pub(crate) enum Locale {
+    /// 日本語
+    Ja,
+    /// 英語
+    En,
+}
+
+impl Locale {
+    pub(crate) fn messages(&self) -> &'static Messages {
+        match self {
+            Locale::Ja => &ja::MESSAGES,
+            Locale::En => &en::MESSAGES,
+        }
+    }
+}
+
+/// 言語毎のメッセージカタログ
+/// 日付・日時の書式は chrono の書式指定子、Excelの書式は Excel の表示形式で記述する
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) struct Messages {
+    /// HTMLの lang 属性
+    pub(crate) html_lang: &'static str,
+    pub(crate) date_format: &'static str,
+    pub(crate) datetime_format: &'static str,
+    pub(crate) excel_date_format: &'static str,
+    pub(crate) excel_datetime_format: &'static str,
+
+    /// 対象期間が1日のみの場合のタイトル
+    pub(crate) date_title: &'static str,
+    /// 対象期間が複数日に渡る場合のタイトル
+    pub(crate) period_title: &'static str,
+    /// タイトルと日付の区切り
+    pub(crate) title_delimiter: &'static str,
+    /// 始端日と終端日の区切り
+    pub(crate) period_delimiter: &'static str,
+
+    pub(crate) grand_total: &'static str,
+    pub(crate) subtotal: &'static str,
+
+    /// 列見出し
+    pub(crate) target_date: &'static str,
+    pub(crate) updated_at: &'static str,
+    pub(crate) total_duration: &'static str,
+    pub(crate) rounded_duration: &'static str,
+    pub(crate) charge_name: &'static str,
+    pub(crate) space_name: &'static str,
+    pub(crate) folder_name: &'static str,
+    pub(crate) list_name: &'static str,
+    pub(crate) task_status: &'static str,
+    pub(crate) task_name: &'static str,
+    /// 週・月・四半期毎に集計する場合の列見出し
+    pub(crate) week: &'static str,
+    pub(crate) month: &'static str,
+    pub(crate) quarter: &'static str,
+
+    /// 集計単位毎の見出し
+    pub(crate) task_and_total_period: &'static str,
+    pub(crate) task_and_daily: &'static str,
+    pub(crate) charge_and_total_period: &'static str,
+    pub(crate) charge_and_daily: &'static str,
+
+    /// グラフの見出しとタイトル
+    pub(crate) charts: &'static str,
+    pub(crate) daily_chart: &'static str,
+    pub(crate) share_chart: &'static str,
+}
@@ -1,15 +1,33 @@
 pub(crate) mod aggregated_by;
 mod as_vec;
+mod billing_period;
+mod bucket;
+mod calendar;
+mod charge_rule;
 pub(crate) mod clickup;
 mod date_range;
-mod jst;
+#[cfg(test)]
+pub(crate) mod fixtures;
+mod locale;
+mod notation;
+mod rounding;
 mod task_duration;
+mod task_location;
 mod task_record;
 mod task_records;
+mod zone;
 
 pub(crate) use as_vec::AsVec;
-pub(crate) use date_range::DateRange;
-pub(crate) use jst::Jst;
-pub(crate) use task_duration::TaskDuration;
+pub(crate) use billing_period::{BillingPeriod, PeriodDefinition, PeriodSelection};
+pub(crate) use bucket::{Bucketing, Granularity, WeekStart};
+pub(crate) use calendar::{CompanyHolidays, WorkingCalendar};
+pub(crate) use charge_rule::ChargeRules;
+pub(crate) use date_range::{DateRange, DayBoundary};
+pub(crate) use locale::{Locale, Messages};
+pub(crate) use notation::Notation;
+pub(crate) use rounding::{ChargeRounding, Rounding, RoundingPolicy, RoundingScope};
+pub(crate) use task_duration::{DurationFormat, DurationUnit, TaskDuration};
+pub(crate) use task_location::TaskLocation;
 pub(crate) use task_record::TaskRecord;
 pub(crate) use task_records::TaskRecords;
+pub(crate) use zone::Zone;
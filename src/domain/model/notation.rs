@@ -0,0 +1,117 @@
+use super::{DateRange, DurationFormat, Locale, Messages, TaskDuration};
+use chrono::{DateTime, FixedOffset, NaiveDate};
+
+/// 出力時の表記。言語と所要時間の出力形式をまとめて扱う
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct Notation {
+    locale: Locale,
+    duration_format: DurationFormat,
+}
+
+impl Notation {
+    pub(crate) fn new(locale: Locale, duration_format: DurationFormat) -> Self {
+        Self {
+            locale,
+            duration_format,
+        }
+    }
+
+    pub(crate) fn messages(&self) -> &'static Messages {
+        self.locale.messages()
+    }
+    pub(crate) fn duration_format(&self) -> &DurationFormat {
+        &self.duration_format
+    }
+
+    pub(crate) fn date(&self, date: &NaiveDate) -> String {
+        date.format(self.messages().date_format).to_string()
+    }
+    pub(crate) fn datetime(&self, datetime: &DateTime<FixedOffset>) -> String {
+        datetime.format(self.messages().datetime_format).to_string()
+    }
+    pub(crate) fn duration(&self, duration: &TaskDuration) -> String {
+        self.duration_format.format(duration)
+    }
+
+    /// 出力時のタイトル
+    /// 1日のみなら集計対象日付を、複数日に渡るなら集計対象期間を表し、末尾にタイムゾーンを添える
+    pub(crate) fn title(&self, date_range: &DateRange) -> String {
+        let messages = self.messages();
+        let title = if let Some(label) = date_range.period_label() {
+            // 請求期間を指定した場合は、日付の代わりに期間の見出しを表示する
+            format!(
+                "{}{}{}",
+                messages.period_title, messages.title_delimiter, label
+            )
+        } else if date_range.is_same_date() {
+            format!(
+                "{}{}{}",
+                messages.date_title,
+                messages.title_delimiter,
+                self.date(&date_range.start_date())
+            )
+        } else {
+            format!(
+                "{}{}{}{}{}",
+                messages.period_title,
+                messages.title_delimiter,
+                self.date(&date_range.start_date()),
+                messages.period_delimiter,
+                self.date(&date_range.end_target_date())
+            )
+        };
+        format!("{} ({})", title, date_range.zone())
+    }
+}
+
+impl Default for Notation {
+    /// 日本語、所要時間はhh:mi:ss表記
+    fn default() -> Self {
+        Self::new(Locale::Ja, DurationFormat::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::model::{BillingPeriod, DayBoundary};
+
+    #[test]
+    fn タイトルは言語毎の表記になりタイムゾーンを添える() {
+        let same_date = DateRange::new(Some("2023/04/03"), None, DayBoundary::default()).unwrap();
+        let period = DateRange::new(
+            Some("2023/04/03"),
+            Some("2023/04/05"),
+            DayBoundary::default().in_zone("Europe/Berlin".parse().unwrap()),
+        )
+        .unwrap();
+        let en = Notation::new(Locale::En, DurationFormat::default());
+
+        assert_eq!(
+            Notation::default().title(&same_date),
+            "集計対象日付：2023/04/03 (Asia/Tokyo)"
+        );
+        assert_eq!(
+            Notation::default().title(&period),
+            "集計対象期間：2023/04/03 ～ 2023/04/05 (Europe/Berlin)"
+        );
+        assert_eq!(en.title(&same_date), "Target date: 2023-04-03 (Asia/Tokyo)");
+        assert_eq!(
+            en.title(&period),
+            "Target period: 2023-04-03 to 2023-04-05 (Europe/Berlin)"
+        );
+    }
+
+    #[test]
+    fn 請求期間を指定した場合は期間の見出しをタイトルとする() {
+        let definitions = vec!["client-a=closes:20".parse().unwrap()];
+        let period =
+            BillingPeriod::find(&"client-a:2023-04".parse().unwrap(), &definitions).unwrap();
+        let date_range = DateRange::of_period(&period, DayBoundary::default());
+
+        assert_eq!(
+            Notation::default().title(&date_range),
+            "集計対象期間：client-a:2023-04 (Asia/Tokyo)"
+        );
+    }
+}
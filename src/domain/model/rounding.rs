@@ -0,0 +1,220 @@
+use std::{collections::HashMap, str::FromStr};
+
+use super::TaskDuration;
+use anyhow::{anyhow, bail, Error, Result};
+use chrono::Duration;
+
+/// 端数の丸め方
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(crate) enum RoundingMode {
+    /// 切り上げ
+    Up,
+    /// 切り捨て
+    Down,
+    /// 四捨五入（ちょうど半分の場合は切り上げ）
+    Nearest,
+}
+
+/// 所要時間をN分単位に丸める設定
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(crate) struct Rounding {
+    mode: RoundingMode,
+    unit_minutes: i64,
+}
+
+impl Rounding {
+    pub(crate) fn new(mode: RoundingMode, unit_minutes: i64) -> Result<Self> {
+        if unit_minutes <= 0 {
+            bail!("Rounding unit must be positive minutes: {}", unit_minutes)
+        }
+        Ok(Self { mode, unit_minutes })
+    }
+
+    pub(crate) fn apply(&self, duration: &TaskDuration) -> TaskDuration {
+        let unit = self.unit_minutes * 60;
+        let seconds = duration.num_seconds();
+        let units = match self.mode {
+            RoundingMode::Up => seconds.div_euclid(unit) + (seconds.rem_euclid(unit) > 0) as i64,
+            RoundingMode::Down => seconds.div_euclid(unit),
+            RoundingMode::Nearest => (seconds + unit / 2).div_euclid(unit),
+        };
+        Duration::seconds(units * unit).into()
+    }
+}
+
+/// "up:15" のように、丸め方と単位（分）をコロン区切りで指定する
+impl FromStr for Rounding {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (mode, unit) = s.split_once(':').ok_or_else(|| {
+            anyhow!(
+                "Rounding must be formatted as <up|down|nearest>:<minutes>: {}",
+                s
+            )
+        })?;
+        let mode = match mode.to_lowercase().as_str() {
+            "up" => RoundingMode::Up,
+            "down" => RoundingMode::Down,
+            "nearest" => RoundingMode::Nearest,
+            _ => bail!("Rounding mode must be up, down or nearest: {}", mode),
+        };
+        let unit = unit
+            .parse::<i64>()
+            .map_err(|e| anyhow!("Rounding unit must be minutes: {} ({})", unit, e))?;
+        Self::new(mode, unit)
+    }
+}
+
+/// チャージコード毎の丸め設定
+/// "charge-a=up:15" のように、チャージコードと丸め設定をイコール区切りで指定する
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct ChargeRounding {
+    charge_name: String,
+    rounding: Rounding,
+}
+
+impl FromStr for ChargeRounding {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        // チャージコードにイコールが含まれる場合に備え、最後のイコールで区切る
+        let (charge_name, rounding) = s.rsplit_once('=').ok_or_else(|| {
+            anyhow!(
+                "Charge rounding must be formatted as <charge>=<up|down|nearest>:<minutes>: {}",
+                s
+            )
+        })?;
+        Ok(Self {
+            charge_name: charge_name.to_string(),
+            rounding: rounding.parse()?,
+        })
+    }
+}
+
+/// 丸めを適用する単位
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, clap::ValueEnum)]
+pub(crate) enum RoundingScope {
+    /// タイムエントリー毎に丸めてから集計する
+    Entry,
+    /// 集計結果の行毎に丸める
+    Row,
+}
+
+/// 所要時間の丸め方針
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct RoundingPolicy {
+    default: Option<Rounding>,
+    by_charge: HashMap<String, Rounding>,
+    scope: RoundingScope,
+}
+
+impl RoundingPolicy {
+    /// 丸め設定が1つも無い場合はNoneを返す
+    pub(crate) fn new(
+        default: Option<Rounding>,
+        by_charge: Vec<ChargeRounding>,
+        scope: RoundingScope,
+    ) -> Option<Self> {
+        if default.is_none() && by_charge.is_empty() {
+            return None;
+        }
+        Some(Self {
+            default,
+            by_charge: by_charge
+                .into_iter()
+                .map(|charge| (charge.charge_name, charge.rounding))
+                .collect(),
+            scope,
+        })
+    }
+
+    pub(crate) fn scope(&self) -> RoundingScope {
+        self.scope
+    }
+
+    /// チャージコード毎の設定を優先し、無ければ全体の設定で丸める
+    /// どちらの設定も無いチャージコードは丸めない
+    pub(crate) fn apply(&self, charge_name: &str, duration: &TaskDuration) -> TaskDuration {
+        match self.by_charge.get(charge_name).or(self.default.as_ref()) {
+            Some(rounding) => rounding.apply(duration),
+            None => duration.clone(),
+        }
+    }
+
+    /// 集計結果の1行分の丸めた所要時間
+    /// タイムエントリー毎に丸める場合は、丸めた所要時間の合計をそのまま用いる
+    pub(crate) fn apply_to_row(
+        &self,
+        charge_name: &str,
+        total_duration: &TaskDuration,
+        rounded_duration: &TaskDuration,
+    ) -> TaskDuration {
+        match self.scope {
+            RoundingScope::Entry => rounded_duration.clone(),
+            RoundingScope::Row => self.apply(charge_name, total_duration),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn minutes(minutes: i64) -> TaskDuration {
+        Duration::minutes(minutes).into()
+    }
+
+    #[test]
+    fn 指定した単位で切り上げ_切り捨て_四捨五入できる() {
+        let up = Rounding::new(RoundingMode::Up, 15).unwrap();
+        let down = Rounding::new(RoundingMode::Down, 15).unwrap();
+        let nearest = Rounding::new(RoundingMode::Nearest, 15).unwrap();
+
+        assert_eq!(up.apply(&minutes(16)), minutes(30));
+        assert_eq!(up.apply(&minutes(15)), minutes(15));
+        assert_eq!(down.apply(&minutes(29)), minutes(15));
+        assert_eq!(nearest.apply(&minutes(22)), minutes(15));
+        assert_eq!(
+            nearest.apply(&Duration::seconds(22 * 60 + 30).into()),
+            minutes(30)
+        );
+        assert_eq!(up.apply(&minutes(0)), minutes(0));
+    }
+
+    #[test]
+    fn 丸め設定を文字列から読み込める() {
+        assert_eq!(
+            "up:6".parse::<Rounding>().unwrap(),
+            Rounding::new(RoundingMode::Up, 6).unwrap()
+        );
+        assert!("up".parse::<Rounding>().is_err());
+        assert!("ceil:15".parse::<Rounding>().is_err());
+        assert!("up:0".parse::<Rounding>().is_err());
+
+        let charge = "client=a=nearest:30".parse::<ChargeRounding>().unwrap();
+        assert_eq!(charge.charge_name, "client=a");
+        assert_eq!(
+            charge.rounding,
+            Rounding::new(RoundingMode::Nearest, 30).unwrap()
+        );
+    }
+
+    #[test]
+    fn チャージコード毎の設定が優先される() {
+        let policy = RoundingPolicy::new(
+            Some("up:30".parse().unwrap()),
+            vec!["charge-1=down:15".parse().unwrap()],
+            RoundingScope::Row,
+        )
+        .unwrap();
+
+        assert_eq!(policy.apply("charge-1", &minutes(20)), minutes(15));
+        assert_eq!(policy.apply("charge-2", &minutes(20)), minutes(30));
+    }
+
+    #[test]
+    fn 丸め設定が無ければ丸め方針も無い() {
+        assert_eq!(RoundingPolicy::new(None, vec![], RoundingScope::Row), None);
+    }
+}
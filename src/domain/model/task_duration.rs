@@ -1,4 +1,5 @@
 use chrono::Duration;
+use serde_derive::Serialize;
 
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub(crate) struct TaskDuration(Duration);
@@ -10,6 +11,9 @@ impl TaskDuration {
     pub fn as_duration(&self) -> Duration {
         self.0
     }
+    pub fn num_seconds(&self) -> i64 {
+        self.0.num_seconds()
+    }
     pub fn add(&self, other: TaskDuration) -> Self {
         Self(self.as_duration() + other.as_duration())
     }
@@ -21,16 +25,6 @@ impl From<Duration> for TaskDuration {
     }
 }
 
-impl From<Option<&str>> for TaskDuration {
-    fn from(millisec_str: Option<&str>) -> Self {
-        let millisec = match millisec_str {
-            Some(d) => d.parse::<i64>().unwrap(),
-            None => 0,
-        };
-        Self(Duration::milliseconds(millisec))
-    }
-}
-
 impl std::fmt::Display for TaskDuration {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         if self.as_duration().is_zero() {
@@ -45,13 +39,89 @@ impl std::fmt::Display for TaskDuration {
     }
 }
 
+/// 所要時間を出力する単位
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, clap::ValueEnum)]
+#[serde(rename_all = "kebab-case")]
+pub(crate) enum DurationUnit {
+    /// hh:mi:ss表記
+    Hms,
+    /// 時間（小数）
+    Hours,
+    /// 分（小数）
+    Minutes,
+    /// 人日。1日あたりの時間数で換算する
+    ManDays,
+    /// 人月。1日あたりの時間数と1ヶ月あたりの日数で換算する
+    ManMonths,
+}
+
+/// 所要時間の出力形式
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct DurationFormat {
+    unit: DurationUnit,
+    /// 小数で出力する場合の小数点以下の桁数
+    precision: usize,
+    hours_per_day: f64,
+    days_per_month: f64,
+}
+
+impl DurationFormat {
+    pub(crate) fn new(
+        unit: DurationUnit,
+        precision: usize,
+        hours_per_day: f64,
+        days_per_month: f64,
+    ) -> Self {
+        Self {
+            unit,
+            precision,
+            hours_per_day,
+            days_per_month,
+        }
+    }
+
+    pub(crate) fn unit(&self) -> DurationUnit {
+        self.unit
+    }
+    pub(crate) fn precision(&self) -> usize {
+        self.precision
+    }
+
+    /// 出力する単位に換算した値。hh:mi:ss表記の場合はNoneを返す
+    pub(crate) fn decimal(&self, duration: &TaskDuration) -> Option<f64> {
+        let hours = duration.num_seconds() as f64 / (60.0 * 60.0);
+        match self.unit {
+            DurationUnit::Hms => None,
+            DurationUnit::Hours => Some(hours),
+            DurationUnit::Minutes => Some(duration.num_seconds() as f64 / 60.0),
+            DurationUnit::ManDays => Some(hours / self.hours_per_day),
+            DurationUnit::ManMonths => Some(hours / self.hours_per_day / self.days_per_month),
+        }
+    }
+
+    /// 出力する単位・桁数の文字列にする
+    pub(crate) fn format(&self, duration: &TaskDuration) -> String {
+        match self.decimal(duration) {
+            Some(value) => format!("{:.*}", self.precision, value),
+            None => duration.to_string(),
+        }
+    }
+}
+
+impl Default for DurationFormat {
+    /// hh:mi:ss表記。1日は8時間、1ヶ月は20日とする
+    fn default() -> Self {
+        Self::new(DurationUnit::Hms, 2, 8.0, 20.0)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
     fn gen_task_duration(hour: i32, minute: i32, sec: i32) -> TaskDuration {
         let millisec = ((hour * 60 * 60) + (minute * 60) + sec) * 1000;
-        TaskDuration::from(Some(millisec.to_string().as_str()))
+        TaskDuration::from(Duration::milliseconds(millisec as i64))
     }
 
     #[test]
@@ -75,6 +145,25 @@ mod tests {
         assert_eq!(d.to_string(), "03:15:45".to_string());
     }
 
+    #[test]
+    fn 時間_分は指定した桁数の小数で出力される() {
+        let d = gen_task_duration(7, 15, 0);
+        let hours = DurationFormat::new(DurationUnit::Hours, 2, 8.0, 20.0);
+        let minutes = DurationFormat::new(DurationUnit::Minutes, 0, 8.0, 20.0);
+        assert_eq!(hours.format(&d), "7.25");
+        assert_eq!(minutes.format(&d), "435");
+        assert_eq!(DurationFormat::default().format(&d), "07:15:00");
+    }
+
+    #[test]
+    fn 人日_人月は1日あたりの時間数で換算される() {
+        let d = gen_task_duration(60, 0, 0);
+        let man_days = DurationFormat::new(DurationUnit::ManDays, 2, 7.5, 20.0);
+        let man_months = DurationFormat::new(DurationUnit::ManMonths, 3, 7.5, 20.0);
+        assert_eq!(man_days.format(&d), "8.00");
+        assert_eq!(man_months.format(&d), "0.400");
+    }
+
     #[test]
     fn 時間数が24を超えても繰り上がらない() {
         let d = gen_task_duration(99, 30, 45);
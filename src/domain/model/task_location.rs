@@ -0,0 +1,31 @@
+/// タスクの所属するスペース・フォルダ・リスト
+/// フォルダに属さないリストの場合、フォルダのIDと名前は空文字列とする
+#[derive(Debug, Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub(crate) struct TaskLocation {
+    pub(crate) space_id: String,
+    pub(crate) space_name: String,
+    pub(crate) folder_id: String,
+    pub(crate) folder_name: String,
+    pub(crate) list_id: String,
+    pub(crate) list_name: String,
+}
+
+impl TaskLocation {
+    pub(crate) fn new(
+        space_id: impl Into<String>,
+        space_name: impl Into<String>,
+        folder_id: impl Into<String>,
+        folder_name: impl Into<String>,
+        list_id: impl Into<String>,
+        list_name: impl Into<String>,
+    ) -> Self {
+        Self {
+            space_id: space_id.into(),
+            space_name: space_name.into(),
+            folder_id: folder_id.into(),
+            folder_name: folder_name.into(),
+            list_id: list_id.into(),
+            list_name: list_name.into(),
+        }
+    }
+}
@@ -1,6 +1,6 @@
 use chrono::{DateTime, FixedOffset, NaiveDate};
 
-use super::TaskDuration;
+use super::{TaskDuration, TaskLocation};
 
 #[derive(Debug, Clone, Eq, PartialEq, PartialOrd, Ord)]
 pub(crate) struct TaskRecord {
@@ -8,8 +8,17 @@ pub(crate) struct TaskRecord {
     pub(crate) task_name: String,
     pub(crate) task_url: String,
     pub(crate) task_status: String,
+    /// 集計とチャージコード毎の丸めに用いるチャージコード
+    pub(crate) charge_code: String,
+    /// 出力時に表示するチャージ名（表示名が無ければチャージコードと同じ）
     pub(crate) charge_name: String,
+    /// タスクの所属するスペース・フォルダ・リスト
+    pub(crate) location: TaskLocation,
+    /// タスクのタグ
+    pub(crate) tags: Vec<String>,
     pub(crate) duration: TaskDuration,
+    /// タイムエントリー毎に丸めた所要時間（丸めない場合は所要時間と同じ）
+    pub(crate) rounded_duration: TaskDuration,
     pub(crate) target_date: NaiveDate,
     pub(crate) updated_at: DateTime<FixedOffset>,
 }
@@ -1,4 +1,6 @@
-use super::{AsVec, TaskRecord};
+use std::collections::{BTreeSet, HashMap};
+
+use super::{AsVec, ChargeRules, RoundingPolicy, TaskRecord};
 
 #[derive(Debug, Clone, Eq, PartialEq, PartialOrd, Ord)]
 pub(crate) struct TaskRecords(Vec<TaskRecord>);
@@ -21,6 +23,47 @@ impl TaskRecords {
         Self(task_records)
     }
 
+    /// カスタムフィールドの値（タスクIDをキーとする）を持つタスクはその値を、
+    /// 持たないタスクは規則に従ってチャージコードを振り分ける
+    /// どの規則にも合致しなかったレコードは代替のチャージコードとし、その所属とタスク名を返す
+    pub(crate) fn map_charges(
+        mut self,
+        rules: &ChargeRules,
+        custom_charges: &HashMap<String, String>,
+    ) -> (Self, Vec<String>) {
+        let mut unmapped = BTreeSet::new();
+        for record in self.0.iter_mut() {
+            if let Some(charge_code) = custom_charges.get(&record.task_id) {
+                record.charge_code = charge_code.clone();
+                record.charge_name = charge_code.clone();
+                continue;
+            }
+            (record.charge_code, record.charge_name) = match rules.charge_of(record) {
+                Some(charge) => charge,
+                None => {
+                    unmapped.insert(format!(
+                        "{}/{}/{}: {}",
+                        record.location.space_name,
+                        record.location.folder_name,
+                        record.location.list_name,
+                        record.task_name
+                    ));
+                    let fallback = rules.fallback().to_string();
+                    (fallback.clone(), fallback)
+                }
+            };
+        }
+        (self, unmapped.into_iter().collect())
+    }
+
+    /// タイムエントリー毎に所要時間を丸める
+    pub(crate) fn round_each(mut self, policy: &RoundingPolicy) -> Self {
+        for record in self.0.iter_mut() {
+            record.rounded_duration = policy.apply(&record.charge_code, &record.duration);
+        }
+        self
+    }
+
     pub(crate) fn concat(&self, other: &Self) -> Self {
         let mut records = self.0.clone();
         records.extend_from_slice(&other.0);
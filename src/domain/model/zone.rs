@@ -0,0 +1,229 @@
+use std::str::FromStr;
+
+use anyhow::{anyhow, Error, Result};
+use chrono::{DateTime, Duration, FixedOffset, LocalResult, NaiveDateTime, TimeZone, Utc};
+use chrono_tz::Tz;
+
+/// 集計に用いるタイムゾーン
+/// 対象日付の判定と日時の表記は、このタイムゾーンの現地時刻で行う
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(crate) struct Zone(Tz);
+
+impl Zone {
+    /// IANAタイムゾーンデータベースの名前（ `Asia/Tokyo` 等）
+    pub(crate) fn name(&self) -> &'static str {
+        self.0.name()
+    }
+
+    pub(crate) fn now(&self) -> DateTime<FixedOffset> {
+        self.localize(&Utc::now().fixed_offset())
+    }
+
+    /// 日時を、このタイムゾーンの現地時刻で表す
+    pub(crate) fn localize(&self, dt: &DateTime<FixedOffset>) -> DateTime<FixedOffset> {
+        dt.with_timezone(&self.0).fixed_offset()
+    }
+
+    /// 現地時刻を日時に変換する
+    /// 夏時間への切り替えで存在しない時刻は切り替え直後の時刻に、
+    /// 夏時間の終了で重複する時刻は早い方の時刻にする
+    pub(crate) fn datetime_from_local(&self, local: &NaiveDateTime) -> DateTime<FixedOffset> {
+        (0..=MAX_GAP_MINUTES)
+            .find_map(|minutes| {
+                match self
+                    .0
+                    .from_local_datetime(&(*local + Duration::minutes(minutes)))
+                {
+                    LocalResult::Single(dt) => Some(dt),
+                    LocalResult::Ambiguous(earliest, _) => Some(earliest),
+                    LocalResult::None => None,
+                }
+            })
+            .map(|dt| dt.fixed_offset())
+            .unwrap_or_else(|| panic!("Fail to convert local datetime: {}", local))
+    }
+}
+
+/// 夏時間等の切り替えで時刻が飛ぶ幅の上限
+const MAX_GAP_MINUTES: i64 = 24 * 60;
+
+impl Default for Zone {
+    /// 日本標準時
+    fn default() -> Self {
+        Self(Tz::Asia__Tokyo)
+    }
+}
+
+impl FromStr for Zone {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.parse::<Tz>().map(Self).map_err(|_| {
+            anyhow!(
+                "Unknown time zone. It must be an IANA name like 'Asia/Tokyo'. Input is '{}'",
+                s
+            )
+        })
+    }
+}
+
+impl std::fmt::Display for Zone {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+/// 並び順はタイムゾーンの名前順とする
+impl PartialOrd for Zone {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Zone {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.name().cmp(other.name())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+
+    fn local(y: i32, m: u32, d: u32, h: u32, mi: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(y, m, d)
+            .unwrap()
+            .and_hms_opt(h, mi, 0)
+            .unwrap()
+    }
+
+    #[test]
+    fn iana名でタイムゾーンを指定できる() {
+        assert_eq!(
+            Zone::from_str("Europe/Berlin").unwrap().name(),
+            "Europe/Berlin"
+        );
+        assert_eq!(Zone::default().name(), "Asia/Tokyo");
+        assert!(Zone::from_str("JST+9").is_err());
+    }
+
+    #[test]
+    fn 夏時間の期間は夏時間のオフセットになる() {
+        let berlin = Zone::from_str("Europe/Berlin").unwrap();
+
+        assert_eq!(
+            berlin
+                .datetime_from_local(&local(2023, 1, 10, 5, 0))
+                .to_rfc3339(),
+            "2023-01-10T05:00:00+01:00"
+        );
+        assert_eq!(
+            berlin
+                .datetime_from_local(&local(2023, 7, 10, 5, 0))
+                .to_rfc3339(),
+            "2023-07-10T05:00:00+02:00"
+        );
+    }
+
+    #[test]
+    fn 夏時間への切り替えで存在しない時刻は切り替え直後の時刻になる() {
+        let berlin = Zone::from_str("Europe/Berlin").unwrap();
+
+        // 2023/03/26 02:00 に 03:00 へ進む
+        assert_eq!(
+            berlin
+                .datetime_from_local(&local(2023, 3, 26, 2, 30))
+                .to_rfc3339(),
+            "2023-03-26T03:00:00+02:00"
+        );
+    }
+
+    #[test]
+    fn 夏時間の終了で重複する時刻は早い方の時刻になる() {
+        let new_york = Zone::from_str("America/New_York").unwrap();
+
+        // 2023/11/05 02:00 に 01:00 へ戻る
+        assert_eq!(
+            new_york
+                .datetime_from_local(&local(2023, 11, 5, 1, 30))
+                .to_rfc3339(),
+            "2023-11-05T01:30:00-04:00"
+        );
+    }
+
+    #[test]
+    fn 日時を現地時刻で表す() {
+        let utc = Utc
+            .with_ymd_and_hms(2022, 12, 3, 15, 0, 0)
+            .unwrap()
+            .fixed_offset();
+
+        assert_eq!(
+            Zone::default().localize(&utc).to_rfc3339(),
+            "2022-12-04T00:00:00+09:00"
+        );
+        assert_eq!(
+            Zone::from_str("America/Los_Angeles")
+                .unwrap()
+                .localize(&utc)
+                .to_rfc3339(),
+            "2022-12-03T07:00:00-08:00"
+        );
+    }
+
+    mod learning {
+        use super::*;
+        use chrono::{Datelike, Timelike};
+
+        #[test]
+        fn naive_dateは各offsetにおける年月日を指す() {
+            let hour = 60 * 60;
+            // UTCなら 2022年12月3日 15時になるはず。
+            let jst = FixedOffset::east_opt(9 * hour)
+                .unwrap()
+                .with_ymd_and_hms(2022, 12, 4, 0, 0, 0)
+                .unwrap();
+            assert_eq!(jst.to_rfc3339(), "2022-12-04T00:00:00+09:00");
+            assert_eq!(jst.date_naive().to_string(), "2022-12-04");
+            assert_eq!(jst.date_naive().year(), 2022);
+            assert_eq!(jst.date_naive().month(), 12);
+            assert_eq!(jst.date_naive().day(), 4);
+        }
+
+        #[test]
+        fn 同時刻ならfixed_offsetにおいてutcとjstで日時が異なる() {
+            let utc = Utc.with_ymd_and_hms(2022, 12, 3, 15, 0, 0).unwrap();
+            assert_eq!(utc.year(), 2022);
+            assert_eq!(utc.month(), 12);
+            assert_eq!(utc.day(), 3);
+            assert_eq!(utc.hour(), 15);
+
+            let hour = 60 * 60;
+            let offset = FixedOffset::east_opt(9 * hour).unwrap();
+            let jst_from_utc = offset.from_utc_datetime(&utc.naive_utc());
+            assert_eq!(jst_from_utc.year(), 2022);
+            assert_eq!(jst_from_utc.month(), 12);
+            assert_eq!(jst_from_utc.day(), 4);
+            assert_eq!(jst_from_utc.hour(), 0);
+        }
+        #[test]
+        fn 同時刻ならnaive_dateにおいてもutcとjstで年月日が異なる() {
+            let utc = Utc.with_ymd_and_hms(2022, 12, 3, 15, 0, 0).unwrap();
+
+            let hour = 60 * 60;
+            let offset = FixedOffset::east_opt(9 * hour).unwrap();
+            let jst = offset.from_utc_datetime(&utc.naive_utc());
+
+            // utcの場合、2022年12月3日 15:00:00
+            assert_eq!(utc.date_naive().year(), 2022);
+            assert_eq!(utc.date_naive().month(), 12);
+            assert_eq!(utc.date_naive().day(), 3);
+
+            // jstの場合、2022年12月4日 00:00:00
+            assert_eq!(jst.date_naive().year(), 2022);
+            assert_eq!(jst.date_naive().month(), 12);
+            assert_eq!(jst.date_naive().day(), 4);
+        }
+    }
+}
@@ -5,11 +5,13 @@ use crate::domain::model::clickup::{ClickupTasks, ClickupTimeEntries};
 use crate::domain::model::DateRange;
 use anyhow::Result;
 use async_trait::async_trait;
+use std::collections::BTreeSet;
 
 #[async_trait]
 pub trait ClickupTaskRepository {
     async fn find_tasks_by_date_range(&self, date_range: &DateRange) -> Result<ClickupTasks>;
     async fn tasks_pagination(&self, date_range: &DateRange) -> Result<ClickupTasks>;
+    async fn find_tasks_by_list_ids(&self, list_ids: &BTreeSet<String>) -> Result<ClickupTasks>;
 }
 #[async_trait]
 pub trait ClickupTimeEntryRepository {
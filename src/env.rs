@@ -1,37 +1,58 @@
+use crate::error::AppError;
+use std::path::Path;
+
 /// dotenvファイルを読み込む
-/// 読み込み順の関係上、tracingではなくprintln!()を使っている
-pub(super) fn set_dotenv(package: &str) {
+/// 読み込み順の関係上、tracingではなくeprintln!()を使っている
+/// （標準出力は集計結果の出力に用いる）
+pub(super) fn set_dotenv(package: &str) -> Result<(), AppError> {
     // ルートで実行されない場合に備え、
     // カレントあるいは親ディレクトリからdotenvを探す
-    let current_dir = match std::env::current_dir() {
-        Ok(current_dir) => current_dir,
-        Err(e) => panic!("Fail to get current directory\n{:#?}", e),
+    let current_dir = std::env::current_dir()
+        .map_err(|e| AppError::Config(format!("Fail to get current directory: {}", e)))?;
+    let dir_name = |dir: &Path| -> Result<Option<String>, AppError> {
+        dir.file_name()
+            .map(|name| {
+                name.to_str().map(str::to_string).ok_or_else(|| {
+                    AppError::Config(format!("Invalid directory name: {}", dir.display()))
+                })
+            })
+            .transpose()
     };
-    let parent_dir = current_dir.parent().unwrap();
-    let current_dir_name = current_dir.file_name().unwrap().to_str().unwrap();
-    let parent_dir_name = parent_dir.file_name().unwrap().to_str().unwrap();
+    let current_dir_name = dir_name(&current_dir)?;
+    // ルートディレクトリやその直下で実行された場合は、親ディレクトリやその名前が無い
+    let parent_dir = current_dir.parent();
+    let parent_dir_name = parent_dir.map(dir_name).transpose()?.flatten();
 
     // 指定されたパッケージ名とディレクトリ名を比較し、
     // 合致したディレクトリの配下にdotenvが存在するとみなす
-    let dirpath = match package {
-        package if package == current_dir_name => current_dir,
-        package if package == parent_dir_name => parent_dir.into(),
+    let dirpath = match (parent_dir, package) {
+        (_, package) if current_dir_name.as_deref() == Some(package) => current_dir.clone(),
+        (Some(parent_dir), package) if parent_dir_name.as_deref() == Some(package) => {
+            parent_dir.into()
+        }
         _ => {
-            println!(
+            eprintln!(
                 "Fail to load dotenv file, because not match Package '{}' and Directory '{}'",
                 package,
                 current_dir.display()
             );
-            return;
+            return Ok(());
         }
     };
     let dotenv_path = dirpath.join("dotenv").join(".env");
 
     // 読み込み対象のpathを表示する
-    println!("Load dotenv from: {:#?}", dotenv_path);
+    eprintln!("Load dotenv from: {:#?}", dotenv_path);
     dotenv::from_path(dotenv_path).ok();
+    Ok(())
 }
 
 pub(super) fn get_env_var(name: &str) -> Result<String, String> {
     std::env::var(name).map_err(|e| format!("{}: {}", name, e))
 }
+
+/// 必須の環境変数を取得する。未設定の場合は設定の誤りとする
+pub(super) fn require_env_var(name: &str) -> Result<String, AppError> {
+    std::env::var(name)
+        .map_err(|_| AppError::Config(format!("{} is not set. Set it in dotenv/.env", name)))
+}
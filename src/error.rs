@@ -0,0 +1,87 @@
+use std::process::ExitCode;
+
+/// アプリケーションのエラー
+/// 呼び出し元のスクリプトが対処を選べるよう、分類毎に異なる終了コードを返す
+#[derive(Debug, thiserror::Error)]
+pub(crate) enum AppError {
+    /// コマンドライン引数の誤り
+    #[error("Invalid argument: {0}")]
+    Usage(String),
+    /// dotenv/.env や環境変数の設定の誤り
+    #[error("Invalid configuration: {0}")]
+    Config(String),
+    /// 認証の失敗
+    #[error("Authentication failed: {0}")]
+    Auth(String),
+    /// APIの利用制限
+    #[error("API rate limit exceeded: {0}")]
+    RateLimit(String),
+    /// 通信先やクリップボード等、利用するサービスを使えない
+    #[error("Service unavailable: {0}")]
+    Unavailable(String),
+    /// APIのエラー応答
+    #[error("API error: {0}")]
+    Api(String),
+}
+
+impl AppError {
+    /// 終了コード。値は sysexits.h に倣う
+    pub(crate) fn exit_code(&self) -> u8 {
+        match self {
+            AppError::Usage(_) => 64,
+            AppError::Config(_) => 78,
+            AppError::Auth(_) => 77,
+            AppError::RateLimit(_) => 75,
+            AppError::Unavailable(_) => 69,
+            AppError::Api(_) => 76,
+        }
+    }
+}
+
+/// エラーの原因を辿って分類を判定し、終了コードを返す
+/// 分類できないエラーは 1 とする
+pub(crate) fn exit_code(error: &anyhow::Error) -> ExitCode {
+    error
+        .chain()
+        .find_map(|cause| cause.downcast_ref::<AppError>())
+        .map(|app_error| ExitCode::from(app_error.exit_code()))
+        .unwrap_or(ExitCode::FAILURE)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::Context;
+
+    #[test]
+    fn 分類毎に異なる終了コードを返す() {
+        let errors = [
+            AppError::Usage(String::new()),
+            AppError::Config(String::new()),
+            AppError::Auth(String::new()),
+            AppError::RateLimit(String::new()),
+            AppError::Unavailable(String::new()),
+            AppError::Api(String::new()),
+        ];
+        let mut codes = errors.iter().map(AppError::exit_code).collect::<Vec<_>>();
+        codes.sort();
+        codes.dedup();
+
+        assert_eq!(codes.len(), errors.len());
+        assert!(!codes.contains(&0) && !codes.contains(&1));
+    }
+
+    #[test]
+    fn 文脈を添えたエラーも分類を判定する() {
+        let error = Err::<(), _>(AppError::Unavailable("timed out".to_string()))
+            .context("Fail to fetch time entries")
+            .unwrap_err();
+
+        assert_eq!(exit_code(&error), ExitCode::from(69));
+        assert_eq!(
+            format!("{:#}", error),
+            "Fail to fetch time entries: Service unavailable: timed out"
+        );
+        assert_eq!(exit_code(&anyhow::anyhow!("unknown")), ExitCode::FAILURE);
+    }
+}
@@ -3,26 +3,41 @@ use crate::domain::{
     model::DateRange,
     repository::{ClickupTaskRepository, ClickupTimeEntryRepository},
 };
+use crate::error::AppError;
 use crate::infra::web::{
     clickup::{ClickupClient, ParseClickupResponse},
+    params::ApiParams,
     BasicClient, BasicResponse,
 };
-use anyhow::{bail, Result};
+use anyhow::Result;
 use async_trait::async_trait;
+use std::collections::{BTreeSet, HashMap, HashSet};
 use std::marker::PhantomData;
+use std::sync::Arc;
+use tokio::sync::{Mutex, OnceCell};
 
 #[derive(Debug, Clone)]
 pub(crate) struct ClickupRepositoryImpl<T> {
     client: ClickupClient,
+    /// 集計中に変わらない参照情報は、リポジトリの生存期間中キャッシュして再取得しない
+    lookup: Arc<ClickupLookupCache>,
     _marker: PhantomData<fn() -> T>,
 }
 
+/// スペース名と、スペース毎の非表示のフォルダのIDのキャッシュ
+#[derive(Debug, Default)]
+struct ClickupLookupCache {
+    space_names: OnceCell<HashMap<String, String>>,
+    hidden_folder_ids: Mutex<HashMap<String, HashSet<String>>>,
+}
+
 impl<T> ClickupRepositoryImpl<T> {
-    pub(crate) fn new(client: BasicClient) -> Self {
-        Self {
-            client: ClickupClient::new(client),
+    pub(crate) fn new(client: BasicClient) -> Result<Self, AppError> {
+        Ok(Self {
+            client: ClickupClient::new(client)?,
+            lookup: Arc::default(),
             _marker: PhantomData,
-        }
+        })
     }
 }
 
@@ -51,20 +66,43 @@ impl ClickupTaskRepository for ClickupRepositoryImpl<ClickupTask> {
     }
 
     async fn tasks_pagination(&self, date_range: &DateRange) -> Result<ClickupTasks> {
+        self.paginate_team_tasks(|page| {
+            self.client.params().filterd_team_tasks(
+                page,
+                true,
+                true,
+                clickup_date_start_milles(date_range),
+                date_range.end().timestamp_millis(),
+            )
+        })
+        .await
+    }
+
+    #[tracing::instrument(name = "find_tasks_in_lists", level = "debug", skip_all)]
+    async fn find_tasks_by_list_ids(&self, list_ids: &BTreeSet<String>) -> Result<ClickupTasks> {
+        if list_ids.is_empty() {
+            return Ok(ClickupTasks::new(Vec::new()));
+        }
+        self.paginate_team_tasks(|page| self.client.params().team_tasks_in_lists(page, list_ids))
+            .await
+    }
+}
+
+impl ClickupRepositoryImpl<ClickupTask> {
+    /// チーム内のタスクを最終ページまで取得する
+    /// ページ毎のパラメータは、ページ番号を受け取って組み立てる
+    async fn paginate_team_tasks<F>(&self, params_of: F) -> Result<ClickupTasks>
+    where
+        F: Fn(u32) -> ApiParams + Send + Sync,
+    {
         let per_page = 100;
         let mut is_not_last_page = true;
         let mut page = 0;
         let mut clickup_tasks = Vec::new();
 
         while is_not_last_page {
-            let params = self.client.params().filterd_team_tasks(
-                page,
-                true,
-                true,
-                clickup_date_start_milles(date_range),
-                date_range.end().timestamp_millis(),
-            );
-            let resp = self.client.api().filtered_team_tasks(Some(params)).await;
+            let params = params_of(page);
+            let resp = self.client.api().filtered_team_tasks(Some(params)).await?;
 
             tracing::trace!(
                 "\nCurrent page is: {}. \nAPI limit Remaining: {}.\nAPI limit will reset, after {} seconds.",
@@ -82,15 +120,38 @@ impl ClickupTaskRepository for ClickupRepositoryImpl<ClickupTask> {
                     // 現在のページのタスクをDTO配列に加える
                     clickup_tasks.append(current_tasks.tasks.as_mut());
                 }
-                Err(e) => bail!("{:?}", e),
+                Err(e) => return Err(resp.app_error(e).into()),
             }
         }
 
+        // タスクのレスポンスに含まれないスペース名を、スペースの一覧から引く
+        let space_names = match clickup_tasks.is_empty() {
+            true => HashMap::new(),
+            false => self.space_names().await?.clone(),
+        };
+
         // レスポンス構造体(DTO)をEntityに変換する
-        let tasks: Vec<ClickupTask> = clickup_tasks.into_iter().map(|t| t.into()).collect();
+        let tasks: Vec<ClickupTask> = clickup_tasks
+            .into_iter()
+            .map(|t| t.into_clickup_task(&space_names))
+            .collect::<Result<_, _>>()?;
         tracing::trace!("Tasks Hit: {}", tasks.len());
         Ok(ClickupTasks::new(tasks))
     }
+
+    /// スペースIDとスペース名の対応。初回のみスペースの一覧を取得する
+    async fn space_names(&self) -> Result<&HashMap<String, String>> {
+        self.lookup
+            .space_names
+            .get_or_try_init(|| async {
+                let resp = self.client.api().spaces(None).await?;
+                match resp.try_to_clickup_spaces() {
+                    Ok(spaces) => Ok(spaces.names()),
+                    Err(e) => Err(resp.app_error(e).into()),
+                }
+            })
+            .await
+    }
 }
 
 #[async_trait]
@@ -109,6 +170,7 @@ impl ClickupTimeEntryRepository for ClickupRepositoryImpl<ClickupTimeEntry> {
         date_range: &DateRange,
     ) -> Result<ClickupTimeEntries> {
         let params = self.client.params().time_entries_within_a_date_range(
+            true,
             true,
             clickup_date_start_milles(date_range),
             date_range.end().timestamp_millis(),
@@ -117,7 +179,7 @@ impl ClickupTimeEntryRepository for ClickupRepositoryImpl<ClickupTimeEntry> {
             .client
             .api()
             .time_entries_within_a_date_range(Some(params))
-            .await;
+            .await?;
 
         tracing::trace!(
             "\nAPI limit Remaining: {}.\nAPI limit will reset, after {} seconds.",
@@ -128,9 +190,39 @@ impl ClickupTimeEntryRepository for ClickupRepositoryImpl<ClickupTimeEntry> {
         match resp.try_to_clickup_time_entries() {
             Ok(time_entries) => {
                 tracing::trace!("TimeEntries Hit: {}", time_entries.data.len());
-                Ok(time_entries.into())
+                let hidden_folder_ids = self.hidden_folder_ids(time_entries.space_ids()).await?;
+                Ok(time_entries.into_clickup_time_entries(&hidden_folder_ids)?)
+            }
+            Err(e) => return Err(resp.app_error(e).into()),
+        }
+    }
+}
+
+impl ClickupRepositoryImpl<ClickupTimeEntry> {
+    /// フォルダに属さないリストが属する、非表示のフォルダのIDをスペース毎に引く
+    /// 取得済みのスペースは再取得しない
+    async fn hidden_folder_ids(&self, space_ids: HashSet<String>) -> Result<HashSet<String>> {
+        let mut cache = self.lookup.hidden_folder_ids.lock().await;
+        let mut hidden_folder_ids = HashSet::new();
+        for space_id in space_ids {
+            if let Some(ids) = cache.get(&space_id) {
+                hidden_folder_ids.extend(ids.iter().cloned());
+                continue;
+            }
+            let resp = self
+                .client
+                .api()
+                .folderless_lists(space_id.clone(), None)
+                .await?;
+            match resp.try_to_clickup_lists() {
+                Ok(lists) => {
+                    let ids = lists.hidden_folder_ids();
+                    hidden_folder_ids.extend(ids.iter().cloned());
+                    cache.insert(space_id, ids);
+                }
+                Err(e) => return Err(resp.app_error(e).into()),
             }
-            Err(e) => bail!("{:?}", e),
         }
+        Ok(hidden_folder_ids)
     }
 }
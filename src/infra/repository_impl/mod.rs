@@ -6,6 +6,7 @@ use crate::domain::{
     model::clickup::{ClickupTask, ClickupTimeEntry},
     repository::Repositories,
 };
+use crate::error::AppError;
 use crate::infra::web::BasicClient;
 
 #[derive(Debug, Clone)]
@@ -27,13 +28,13 @@ impl Repositories for RepositoryImpls {
 }
 
 impl RepositoryImpls {
-    pub(crate) fn new(client: BasicClient) -> Self {
+    pub(crate) fn new(client: BasicClient) -> Result<Self, AppError> {
         // 各リポジトリのインスタンスを生成する
-        let clickup_task_repository = ClickupRepositoryImpl::new(client.clone());
-        let clickup_time_entry_repository = ClickupRepositoryImpl::new(client);
-        Self {
+        let clickup_task_repository = ClickupRepositoryImpl::new(client.clone())?;
+        let clickup_time_entry_repository = ClickupRepositoryImpl::new(client)?;
+        Ok(Self {
             clickup_task_repository,
             clickup_time_entry_repository,
-        }
+        })
     }
 }
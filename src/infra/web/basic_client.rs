@@ -1,11 +1,10 @@
-use crate::env::set_dotenv;
+use crate::error::AppError;
 use crate::infra::web::params::{ApiParams, AuthType, ContentType, HttpMethods};
 use crate::infra::web::{BasicResponse, BasicResponseImpl};
 use async_trait::async_trait;
 use reqwest::header::{HeaderValue, AUTHORIZATION, CONTENT_TYPE};
 use reqwest::{Client, RequestBuilder, Response};
 use reqwest::{StatusCode, Url};
-use std::thread;
 use std::time::Duration;
 
 #[derive(Debug, Clone)]
@@ -13,8 +12,6 @@ pub(crate) struct BasicClient(pub Client);
 
 impl BasicClient {
     pub fn new() -> Self {
-        // dotenvを読み込む
-        set_dotenv("task-reporter");
         Self(Client::new())
     }
 }
@@ -36,28 +33,27 @@ pub(crate) trait BasicProperty {
 
 #[async_trait]
 pub(crate) trait BasicClientApi: BasicProperty {
-    async fn send_get(&self, path: &str, params: Option<ApiParams>) -> BasicResponseImpl {
+    async fn send_get(
+        &self,
+        path: &str,
+        params: Option<ApiParams>,
+    ) -> Result<BasicResponseImpl, AppError> {
         self.send_request(HttpMethods::Get, path, params).await
     }
-    async fn send_post(&self, path: &str, params: Option<ApiParams>) -> BasicResponseImpl {
+    async fn send_post(
+        &self,
+        path: &str,
+        params: Option<ApiParams>,
+    ) -> Result<BasicResponseImpl, AppError> {
         self.send_request(HttpMethods::Post, path, params).await
     }
-    async fn send_put(&self, path: &str, params: Option<ApiParams>) -> BasicResponseImpl {
-        self.send_request(HttpMethods::Put, path, params).await
-    }
-    async fn send_patch(&self, path: &str, params: Option<ApiParams>) -> BasicResponseImpl {
-        self.send_request(HttpMethods::Patch, path, params).await
-    }
-    async fn send_delete(&self, path: &str, params: Option<ApiParams>) -> BasicResponseImpl {
-        self.send_request(HttpMethods::Delete, path, params).await
-    }
     async fn send_request(
         &self,
         method: HttpMethods,
         path: &str,
         params: Option<ApiParams>,
-    ) -> BasicResponseImpl {
-        let url = self.build_request_url(path);
+    ) -> Result<BasicResponseImpl, AppError> {
+        let url = self.build_request_url(path)?;
         let builder = self.build_request_method(method, url);
         let builder = self.build_request_auth_type(builder);
         let builder = self.build_request_content_type(builder);
@@ -65,9 +61,12 @@ pub(crate) trait BasicClientApi: BasicProperty {
 
         // Too Many Requests(429)発生時、指定秒数待機した後に再試行するか否か
         let resp = if !self.retry_on_rate_limit_exceeded() {
-            builder.send().await.unwrap()
+            builder
+                .send()
+                .await
+                .map_err(|e| AppError::Unavailable(e.to_string()))?
         } else {
-            send_request_retry_on_too_many_requests(builder).await
+            send_request_retry_on_too_many_requests(builder).await?
         };
         BasicResponseImpl::new(resp).await
     }
@@ -75,8 +74,16 @@ pub(crate) trait BasicClientApi: BasicProperty {
     // 以下はリクエスト処理を分割したもの
 
     /// リクエストするURLを組み立てる
-    fn build_request_url(&self, path: &str) -> Url {
-        Url::parse(self.api_endpoint()).unwrap().join(path).unwrap()
+    fn build_request_url(&self, path: &str) -> Result<Url, AppError> {
+        Url::parse(self.api_endpoint())
+            .and_then(|url| url.join(path))
+            .map_err(|e| {
+                AppError::Config(format!(
+                    "Invalid API endpoint '{}': {}",
+                    self.api_endpoint(),
+                    e
+                ))
+            })
     }
     /// HTTPリクエストメソッド毎に処理する
     fn build_request_method(&self, method: HttpMethods, url: Url) -> RequestBuilder {
@@ -125,31 +132,49 @@ pub(crate) trait BasicClientApi: BasicProperty {
     }
 }
 
-//// 各サービス毎に更新までの残り秒数を取得し、その秒数＋5秒待機する。
+/// 利用制限による再試行の上限回数
+const MAX_RETRIES_ON_TOO_MANY_REQUESTS: usize = 3;
+
+/// 各サービス毎に更新までの残り秒数を取得し、その秒数＋5秒待機する。
 /// 残り秒数を取得できない場合は60秒+5秒待機する。  
-async fn send_request_retry_on_too_many_requests(builder: RequestBuilder) -> Response {
-    loop {
-        match builder.try_clone().unwrap().send().await {
+/// 上限回数まで再試行しても制限が解除されない場合は、利用制限のエラーとする
+/// 最後の試行で制限された場合は、再試行しないので待機せずにエラーとする
+async fn send_request_retry_on_too_many_requests(
+    builder: RequestBuilder,
+) -> Result<Response, AppError> {
+    for attempt in 1..=MAX_RETRIES_ON_TOO_MANY_REQUESTS {
+        // ボディがストリームの場合は複製できず、再試行できないのでエラーとする
+        let request = builder.try_clone().ok_or_else(|| {
+            AppError::Api("Request with a streaming body cannot be retried".to_string())
+        })?;
+        match request.send().await {
             Ok(resp) if resp.status() == StatusCode::TOO_MANY_REQUESTS => {
+                if attempt == MAX_RETRIES_ON_TOO_MANY_REQUESTS {
+                    break;
+                }
                 let wait_secs = BasicResponseImpl::new(resp)
-                    .await
+                    .await?
                     .x_ratelimit_reset_from_now();
                 // 5秒余裕を持って待機させる
-                wait_for(wait_secs + 5);
+                wait_for(wait_secs + 5).await;
             }
-            Ok(resp) => break resp,
-            Err(e) => panic!("{:#?}", e),
+            Ok(resp) => return Ok(resp),
+            Err(e) => return Err(AppError::Unavailable(e.to_string())),
         }
     }
+    Err(AppError::RateLimit(format!(
+        "Still limited after {} attempts. Try again later",
+        MAX_RETRIES_ON_TOO_MANY_REQUESTS
+    )))
 }
-/// 指定秒数の間、メインスレッドをブロックする
+/// 指定秒数の間、スレッドをブロックせずに待機する
 /// 10秒毎に "waiting..."のメッセージを表示する
 #[tracing::instrument()]
-fn wait_for(wait_secs: u64) {
+async fn wait_for(wait_secs: u64) {
     assert!(wait_secs > 0);
     for _ in 0..(wait_secs / 10) {
         tracing::info!("waiting...");
-        thread::sleep(Duration::from_secs(10));
+        tokio::time::sleep(Duration::from_secs(10)).await;
     }
-    thread::sleep(Duration::from_secs(wait_secs % 10));
+    tokio::time::sleep(Duration::from_secs(wait_secs % 10)).await;
 }
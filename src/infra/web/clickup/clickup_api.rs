@@ -1,3 +1,4 @@
+use crate::error::AppError;
 use crate::infra::web::basic_client::{BasicClientApi, BasicProperty};
 use crate::infra::web::clickup::ClickupResponse;
 use crate::infra::web::params::{ApiParams, AuthType, ContentType};
@@ -45,8 +46,11 @@ impl ClickupApi {
     }
 
     #[allow(unused)]
-    pub(crate) async fn teams(&self, params: Option<ApiParams>) -> ClickupResponse {
-        self.send_get("/api/v2/team", params).await.into()
+    pub(crate) async fn teams(
+        &self,
+        params: Option<ApiParams>,
+    ) -> Result<ClickupResponse, AppError> {
+        self.send_get("/api/v2/team", params).await.map(Into::into)
     }
 
     #[allow(unused)]
@@ -54,20 +58,22 @@ impl ClickupApi {
         &self,
         user_id: impl Into<String>,
         params: Option<ApiParams>,
-    ) -> ClickupResponse {
+    ) -> Result<ClickupResponse, AppError> {
         self.send_get(
             &format!("/api/v2/team/{}/user/{}", self.team_id(), user_id.into()),
             params,
         )
         .await
-        .into()
+        .map(Into::into)
     }
 
-    #[allow(unused)]
-    pub(crate) async fn spaces(&self, params: Option<ApiParams>) -> ClickupResponse {
+    pub(crate) async fn spaces(
+        &self,
+        params: Option<ApiParams>,
+    ) -> Result<ClickupResponse, AppError> {
         self.send_get(&format!("/api/v2/team/{}/space?", self.team_id()), params)
             .await
-            .into()
+            .map(Into::into)
     }
 
     #[allow(unused)]
@@ -75,24 +81,23 @@ impl ClickupApi {
         &self,
         space_id: impl Into<String>,
         params: Option<ApiParams>,
-    ) -> ClickupResponse {
+    ) -> Result<ClickupResponse, AppError> {
         self.send_get(
             &format!("/api/v2/space/{}/folder?", space_id.into()),
             params,
         )
         .await
-        .into()
+        .map(Into::into)
     }
 
-    #[allow(unused)]
     pub(crate) async fn folderless_lists(
         &self,
         space_id: impl Into<String>,
         params: Option<ApiParams>,
-    ) -> ClickupResponse {
+    ) -> Result<ClickupResponse, AppError> {
         self.send_get(&format!("/api/v2/space/{}/list?", space_id.into()), params)
             .await
-            .into()
+            .map(Into::into)
     }
 
     #[allow(unused)]
@@ -100,13 +105,13 @@ impl ClickupApi {
         &self,
         folder_id: impl Into<String>,
         params: Option<ApiParams>,
-    ) -> ClickupResponse {
+    ) -> Result<ClickupResponse, AppError> {
         self.send_get(
             &format!("/api/v2/folder/{}/list?", folder_id.into()),
             params,
         )
         .await
-        .into()
+        .map(Into::into)
     }
 
     #[allow(unused)]
@@ -114,10 +119,10 @@ impl ClickupApi {
         &self,
         list_id: impl Into<String>,
         params: Option<ApiParams>,
-    ) -> ClickupResponse {
+    ) -> Result<ClickupResponse, AppError> {
         self.send_get(&format!("/api/v2/list/{}", list_id.into()), params)
             .await
-            .into()
+            .map(Into::into)
     }
 
     #[allow(unused)]
@@ -125,10 +130,10 @@ impl ClickupApi {
         &self,
         list_id: impl Into<String>,
         params: Option<ApiParams>,
-    ) -> ClickupResponse {
+    ) -> Result<ClickupResponse, AppError> {
         self.send_get(&format!("/api/v2/list/{}/task?", list_id.into()), params)
             .await
-            .into()
+            .map(Into::into)
     }
 
     #[allow(unused)]
@@ -136,61 +141,73 @@ impl ClickupApi {
         &self,
         task_id: impl Into<String>,
         params: Option<ApiParams>,
-    ) -> ClickupResponse {
+    ) -> Result<ClickupResponse, AppError> {
         self.send_get(&format!("/api/v2/task/{}/", task_id.into()), params)
             .await
-            .into()
+            .map(Into::into)
     }
 
-    pub(crate) async fn filtered_team_tasks(&self, params: Option<ApiParams>) -> ClickupResponse {
+    pub(crate) async fn filtered_team_tasks(
+        &self,
+        params: Option<ApiParams>,
+    ) -> Result<ClickupResponse, AppError> {
         self.send_get(&format!("/api/v2/team/{}/task?", self.team_id()), params)
             .await
-            .into()
+            .map(Into::into)
     }
 
     pub(crate) async fn time_entries_within_a_date_range(
         &self,
         params: Option<ApiParams>,
-    ) -> ClickupResponse {
+    ) -> Result<ClickupResponse, AppError> {
         self.send_get(
             &format!("/api/v2/team/{}/time_entries?", self.team_id()),
             params,
         )
         .await
-        .into()
+        .map(Into::into)
     }
 
     #[allow(unused)]
-    pub(crate) async fn stop_a_time_entry(&self, params: Option<ApiParams>) -> ClickupResponse {
+    pub(crate) async fn stop_a_time_entry(
+        &self,
+        params: Option<ApiParams>,
+    ) -> Result<ClickupResponse, AppError> {
         self.send_post(
             &format!("/api/v2/team/{}/time_entries/stop", self.team_id()),
             params,
         )
         .await
-        .into()
+        .map(Into::into)
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::domain::model::DateRange;
+    use crate::domain::model::{DateRange, DayBoundary};
     use crate::infra::web::clickup::clickup_client::ClickupClient;
     use crate::infra::web::clickup::ParseClickupResponse;
     use crate::infra::web::BasicResponse;
 
+    /// アプリケーションの起動時と同様に、dotenvから接続先とトークンを読み込む
+    fn new_client() -> ClickupClient {
+        crate::env::set_dotenv("task-reporter").unwrap();
+        ClickupClient::new(BasicClient::new()).unwrap()
+    }
+
     #[tokio::test]
     async fn check_response_header() {
         // teams()のpathを確認する
-        let client = ClickupClient::new(BasicClient::new());
-        let resp = client.api().teams(None).await;
+        let client = new_client();
+        let resp = client.api().teams(None).await.unwrap();
         assert_eq!(resp.0.path(), "/api/v2/team");
     }
 
     #[tokio::test]
     async fn check_ratelimit_remaining() {
-        let client = ClickupClient::new(BasicClient::new());
-        let resp = client.api().teams(None).await;
+        let client = new_client();
+        let resp = client.api().teams(None).await.unwrap();
         let remaining = resp.0.x_ratelimit_remaining().parse().unwrap();
         dbg!(remaining);
         assert!(
@@ -201,8 +218,8 @@ mod tests {
 
     #[tokio::test]
     async fn check_ratelimit_reset() {
-        let client = ClickupClient::new(BasicClient::new());
-        let resp = client.api().teams(None).await;
+        let client = new_client();
+        let resp = client.api().teams(None).await.unwrap();
         let reset_secs = resp.0.x_ratelimit_reset_from_now();
         println!("API limitrate will reset, after {} seconds.", reset_secs);
         assert!(reset_secs < (60 * 15));
@@ -211,8 +228,8 @@ mod tests {
     #[tokio::test]
     async fn check_teams_0th_id_by_serde_value() {
         // teamsの内、0番目のチームのIDを確認する
-        let client = ClickupClient::new(BasicClient::new());
-        let resp = client.api().teams(None).await;
+        let client = new_client();
+        let resp = client.api().teams(None).await.unwrap();
         let teams = resp.try_to_serde_json_value().unwrap();
         let team_id = teams.get("teams").unwrap()[0]
             .get("id")
@@ -231,8 +248,8 @@ mod tests {
     #[tokio::test]
     async fn check_teams_0th_id() {
         // teamsの内、0番目のチームのIDを確認する
-        let client = ClickupClient::new(BasicClient::new());
-        let resp = client.api().teams(None).await;
+        let client = new_client();
+        let resp = client.api().teams(None).await.unwrap();
         let teams = resp.try_to_clickup_teams().unwrap();
         println!("{:?}", teams);
         assert_eq!(teams.teams[0].id, client.api().team_id());
@@ -242,15 +259,15 @@ mod tests {
     #[should_panic = "ビジネスプランが必要です。: ClickupError { ECODE: \"TEAM_110\", err: \"Team must be on enterprise plan\" }"]
     async fn check_user() {
         // ユーザIDを取得する
-        let client = ClickupClient::new(BasicClient::new());
-        let resp = client.api().teams(None).await;
+        let client = new_client();
+        let resp = client.api().teams(None).await.unwrap();
         let user_id = resp.try_to_clickup_teams().unwrap().teams[0].members[0]
             .user
             .id;
         dbg!(&user_id);
 
         // ユーザ情報を取得する
-        let resp = client.api().user(user_id.to_string(), None).await;
+        let resp = client.api().user(user_id.to_string(), None).await.unwrap();
         let user = resp
             .try_to_serde_json_value()
             .expect("ビジネスプランが必要です。");
@@ -260,8 +277,8 @@ mod tests {
     #[tokio::test]
     async fn check_spaces() {
         // 複数のスペースを取得する
-        let client = ClickupClient::new(BasicClient::new());
-        let resp = client.api().spaces(None).await;
+        let client = new_client();
+        let resp = client.api().spaces(None).await.unwrap();
         let spaces = resp.try_to_clickup_spaces().unwrap();
         dbg!(&spaces);
         assert_eq!(spaces.spaces[2].name, "Development")
@@ -270,8 +287,8 @@ mod tests {
     #[tokio::test]
     async fn check_folders() {
         // 複数のスペースIDを取得する
-        let client = ClickupClient::new(BasicClient::new());
-        let resp = client.api().spaces(None).await;
+        let client = new_client();
+        let resp = client.api().spaces(None).await.unwrap();
         let spaces = resp.try_to_clickup_spaces().unwrap();
         let space_ids = spaces
             .spaces
@@ -280,7 +297,7 @@ mod tests {
             .collect::<Vec<String>>();
 
         // 複数のフォルダを取得する
-        let resp = client.api().folders(&space_ids[2], None).await;
+        let resp = client.api().folders(&space_ids[2], None).await.unwrap();
         // let folders = resp.try_to_serde_json_value().unwrap();
         let folders = resp.try_to_clickup_folders().unwrap();
         dbg!(&folders);
@@ -290,8 +307,8 @@ mod tests {
     #[tokio::test]
     async fn check_folderless_lists() {
         // 複数のスペースIDを取得する
-        let client = ClickupClient::new(BasicClient::new());
-        let resp = client.api().spaces(None).await;
+        let client = new_client();
+        let resp = client.api().spaces(None).await.unwrap();
         let spaces = resp.try_to_clickup_spaces().unwrap();
         let space_ids = spaces
             .spaces
@@ -300,7 +317,11 @@ mod tests {
             .collect::<Vec<String>>();
 
         // 複数のフォルダに紐づかないリストIDを取得する
-        let resp = client.api().folderless_lists(&space_ids[2], None).await;
+        let resp = client
+            .api()
+            .folderless_lists(&space_ids[2], None)
+            .await
+            .unwrap();
         let folderless_lists = resp.try_to_clickup_lists().unwrap();
         dbg!(&folderless_lists);
         assert_eq!(folderless_lists.lists[0].name, "開発ネタ")
@@ -309,8 +330,8 @@ mod tests {
     #[tokio::test]
     async fn check_lists() {
         // 複数のスペースIDを取得する
-        let client = ClickupClient::new(BasicClient::new());
-        let resp = client.api().spaces(None).await;
+        let client = new_client();
+        let resp = client.api().spaces(None).await.unwrap();
         let spaces = resp.try_to_clickup_spaces().unwrap();
         let space_ids = spaces
             .spaces
@@ -319,7 +340,7 @@ mod tests {
             .collect::<Vec<String>>();
 
         // 複数のフォルダを取得する
-        let resp = client.api().folders(&space_ids[2], None).await;
+        let resp = client.api().folders(&space_ids[2], None).await.unwrap();
         // let folders = resp.try_to_serde_json_value().unwrap();
         let folder_ids = resp
             .try_to_clickup_folders()
@@ -330,7 +351,7 @@ mod tests {
             .collect::<Vec<String>>();
 
         // フォルダーに紐づくリストを取得する
-        let resp = client.api().lists(&folder_ids[0], None).await;
+        let resp = client.api().lists(&folder_ids[0], None).await.unwrap();
         let lists = resp.try_to_clickup_lists().unwrap();
         dbg!(&lists);
         assert_eq!(lists.lists[2].name, "Rust学習");
@@ -339,8 +360,8 @@ mod tests {
     #[tokio::test]
     async fn check_list() {
         // 複数のスペースIDを取得する
-        let client = ClickupClient::new(BasicClient::new());
-        let resp = client.api().spaces(None).await;
+        let client = new_client();
+        let resp = client.api().spaces(None).await.unwrap();
         let spaces = resp.try_to_clickup_spaces().unwrap();
         let space_ids = spaces
             .spaces
@@ -349,7 +370,11 @@ mod tests {
             .collect::<Vec<String>>();
 
         // 複数のフォルダに紐づかないリストIDを取得する
-        let resp = client.api().folderless_lists(&space_ids[2], None).await;
+        let resp = client
+            .api()
+            .folderless_lists(&space_ids[2], None)
+            .await
+            .unwrap();
         let folderless_list_ids = resp
             .try_to_clickup_lists()
             .unwrap()
@@ -359,7 +384,11 @@ mod tests {
             .collect::<Vec<String>>();
 
         // 指定したリストの情報を取得する
-        let resp = client.api().list(&folderless_list_ids[0], None).await;
+        let resp = client
+            .api()
+            .list(&folderless_list_ids[0], None)
+            .await
+            .unwrap();
         let list = resp.try_to_clickup_list().unwrap();
         dbg!(&list);
         assert_eq!(list.name, "開発ネタ")
@@ -367,8 +396,8 @@ mod tests {
     #[tokio::test]
     async fn check_tasks() {
         // 複数のスペースIDを取得する
-        let client = ClickupClient::new(BasicClient::new());
-        let resp = client.api().spaces(None).await;
+        let client = new_client();
+        let resp = client.api().spaces(None).await.unwrap();
         let spaces = resp.try_to_clickup_spaces().unwrap();
         let space_ids = spaces
             .spaces
@@ -377,7 +406,11 @@ mod tests {
             .collect::<Vec<String>>();
 
         // 複数のフォルダに紐づかないリストIDを取得する
-        let resp = client.api().folderless_lists(&space_ids[2], None).await;
+        let resp = client
+            .api()
+            .folderless_lists(&space_ids[2], None)
+            .await
+            .unwrap();
         let folderless_list_ids = resp
             .try_to_clickup_lists()
             .unwrap()
@@ -387,7 +420,11 @@ mod tests {
             .collect::<Vec<String>>();
 
         // 指定したリストに含まれるタスクの情報を取得する
-        let resp = client.api().tasks(&folderless_list_ids[0], None).await;
+        let resp = client
+            .api()
+            .tasks(&folderless_list_ids[0], None)
+            .await
+            .unwrap();
         let mut tasks = resp.try_to_clickup_tasks().unwrap();
         tasks.tasks.reverse();
         dbg!(&tasks);
@@ -399,8 +436,8 @@ mod tests {
     #[tokio::test]
     async fn check_task() {
         // 複数のスペースIDを取得する
-        let client = ClickupClient::new(BasicClient::new());
-        let resp = client.api().spaces(None).await;
+        let client = new_client();
+        let resp = client.api().spaces(None).await.unwrap();
         let spaces = resp.try_to_clickup_spaces().unwrap();
         let space_ids = spaces
             .spaces
@@ -409,7 +446,11 @@ mod tests {
             .collect::<Vec<String>>();
 
         // 複数のフォルダに紐づかないリストIDを取得する
-        let resp = client.api().folderless_lists(&space_ids[2], None).await;
+        let resp = client
+            .api()
+            .folderless_lists(&space_ids[2], None)
+            .await
+            .unwrap();
         let folderless_list_ids = resp
             .try_to_clickup_lists()
             .unwrap()
@@ -419,7 +460,11 @@ mod tests {
             .collect::<Vec<String>>();
 
         // 指定したリストに含まれるタスクのIDを取得する
-        let resp = client.api().tasks(&folderless_list_ids[0], None).await;
+        let resp = client
+            .api()
+            .tasks(&folderless_list_ids[0], None)
+            .await
+            .unwrap();
         let mut task_ids = resp
             .try_to_clickup_tasks()
             .unwrap()
@@ -430,7 +475,7 @@ mod tests {
         task_ids.reverse();
 
         // 指定したタスクの情報を取得する
-        let resp = client.api().task(&task_ids[0], None).await;
+        let resp = client.api().task(&task_ids[0], None).await.unwrap();
         let task = resp.try_to_clickup_task().unwrap();
         dbg!(&task);
         assert_eq!(
@@ -442,8 +487,8 @@ mod tests {
     #[tokio::test]
     async fn check_filtered_team_tasks() {
         // 事前準備
-        let dr = DateRange::new(Some("2023/04/03"), None);
-        let client = ClickupClient::new(BasicClient::new());
+        let dr = DateRange::new(Some("2023/04/03"), None, DayBoundary::default()).unwrap();
+        let client = new_client();
         let params = client.params().filterd_team_tasks(
             0,
             true,
@@ -453,7 +498,11 @@ mod tests {
         );
 
         // リクエスト
-        let resp = client.api().filtered_team_tasks(Some(params)).await;
+        let resp = client
+            .api()
+            .filtered_team_tasks(Some(params))
+            .await
+            .unwrap();
         let tasks = resp.try_to_clickup_tasks().unwrap();
         dbg!(&tasks);
         assert_eq!(tasks.tasks[0].name.as_str(), "請求書をメール送付する")
@@ -461,9 +510,10 @@ mod tests {
     #[tokio::test]
     async fn check_time_entries_within_a_date_range() {
         // 事前準備
-        let dr = DateRange::new(Some("2022/01/05"), None);
-        let client = ClickupClient::new(BasicClient::new());
+        let dr = DateRange::new(Some("2022/01/05"), None, DayBoundary::default()).unwrap();
+        let client = new_client();
         let params = client.params().time_entries_within_a_date_range(
+            true,
             true,
             dr.start_unixtime_millis(),
             dr.end_unixtime_millis(),
@@ -473,7 +523,8 @@ mod tests {
         let resp = client
             .api()
             .time_entries_within_a_date_range(Some(params))
-            .await;
+            .await
+            .unwrap();
         let time_entries = resp.try_to_clickup_time_entries().unwrap();
         dbg!(&time_entries);
         assert_eq!(
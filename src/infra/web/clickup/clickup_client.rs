@@ -1,4 +1,5 @@
-use crate::env::get_env_var;
+use crate::env::require_env_var;
+use crate::error::AppError;
 use crate::infra::web::clickup::{ClickupApi, ClickupParamsBuilder};
 use crate::infra::web::params::{AuthType, ContentType};
 use crate::infra::web::BasicClient;
@@ -10,18 +11,18 @@ pub(crate) struct ClickupClient {
 }
 
 impl ClickupClient {
-    pub(crate) fn new(client: BasicClient) -> Self {
+    pub(crate) fn new(client: BasicClient) -> Result<Self, AppError> {
         let api = ClickupApi::new(
             client,
             AuthType::General,
             ContentType::Json,
-            get_env_var("APP_CLICKUP_API_ENDPOINT").unwrap(),
-            get_env_var("APP_CLICKUP_ACCESS_TOKEN").unwrap(),
-            get_env_var("APP_CLICKUP_TEAM_IDENT").unwrap(),
+            require_env_var("APP_CLICKUP_API_ENDPOINT")?,
+            require_env_var("APP_CLICKUP_ACCESS_TOKEN")?,
+            require_env_var("APP_CLICKUP_TEAM_IDENT")?,
             true,
         );
         let params = ClickupParamsBuilder;
-        Self { api, params }
+        Ok(Self { api, params })
     }
 
     pub(crate) fn api(&self) -> &ClickupApi {
@@ -32,15 +32,43 @@ impl ClickupParamsBuilder {
         .to_params_of_query_string()
     }
 
+    /// 指定したリストに属するタスクを、完了済み・サブタスクも含めて取得する
+    /// リストIDは配列として "list_ids[]" を繰り返し指定する必要があるので、構造体を経ずに組み立てる
+    pub(crate) fn team_tasks_in_lists<'a>(
+        &self,
+        page: u32,
+        list_ids: impl IntoIterator<Item = &'a String>,
+    ) -> ApiParams {
+        let mut query = vec![
+            ("page".to_string(), Some(page.to_string())),
+            (
+                "subtasks".to_string(),
+                Some(self.convert_bool_to_lower_string(true)),
+            ),
+            (
+                "include_closed".to_string(),
+                Some(self.convert_bool_to_lower_string(true)),
+            ),
+        ];
+        query.extend(
+            list_ids
+                .into_iter()
+                .map(|list_id| ("list_ids[]".to_string(), Some(list_id.clone()))),
+        );
+        ApiParams::QueryString(query)
+    }
+
     /// 引数のbool値では、全部小文字の文字列を求めらている。
     pub(crate) fn time_entries_within_a_date_range(
         &self,
         include_location_names: bool,
+        include_task_tags: bool,
         start_date: i64,
         end_date: i64,
     ) -> ApiParams {
         ClickupGetParamsOfTimeEntriesWithinADateRange::new(
             self.convert_bool_to_lower_string(include_location_names),
+            self.convert_bool_to_lower_string(include_task_tags),
             start_date.to_string(),
             end_date.to_string(),
         )
@@ -66,6 +94,7 @@ pub(crate) struct ClickupGetParamsOfFilteredTeamTasks {
 pub(crate) struct ClickupGetParamsOfTimeEntriesWithinADateRange {
     // bool値だが、全部小文字の文字列を求められている "true" or "talse"
     include_location_names: String,
+    include_task_tags: String,
     // 下記2つは数値だが、文字列を求められている。
     start_date: String,
     end_date: String,
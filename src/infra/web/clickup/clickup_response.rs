@@ -1,8 +1,14 @@
+use std::collections::{HashMap, HashSet};
+
 use crate::domain::model::clickup::{
-    ClickupTask, ClickupTasks, ClickupTimeEntries, ClickupTimeEntry,
+    ClickupCustomField, ClickupTask, ClickupTasks, ClickupTimeEntries, ClickupTimeEntry,
 };
+use crate::domain::model::{TaskDuration, TaskLocation};
+use crate::error::AppError;
 use crate::infra::web::{BasicResponse, BasicResponseImpl};
 use anyhow::{bail, Result};
+use chrono::{DateTime, Duration, FixedOffset, TimeZone, Utc};
+use serde::de::DeserializeOwned;
 use serde_derive::{Deserialize, Serialize};
 use serde_json::Value;
 
@@ -16,13 +22,18 @@ impl From<BasicResponseImpl> for ClickupResponse {
 }
 
 pub(crate) trait ParseClickupResponse {
+    #[cfg(test)]
     fn try_to_serde_json_value(&self) -> Result<Value, ClickupError>;
+    #[cfg(test)]
     fn try_to_clickup_teams(&self) -> Result<ClickupTeamsResponseDto, ClickupError>;
     fn try_to_clickup_spaces(&self) -> Result<ClickupSpacesResponseDto, ClickupError>;
+    #[cfg(test)]
     fn try_to_clickup_folders(&self) -> Result<ClickupFoldersResponseDto, ClickupError>;
     fn try_to_clickup_lists(&self) -> Result<ClickupListsResponseDto, ClickupError>;
+    #[cfg(test)]
     fn try_to_clickup_list(&self) -> Result<ClickupListResponseDto, ClickupError>;
     fn try_to_clickup_tasks(&self) -> Result<ClickupTasksResponseDto, ClickupError>;
+    #[cfg(test)]
     fn try_to_clickup_task(&self) -> Result<ClickupTaskResponseDto, ClickupError>;
     fn try_to_clickup_time_entries(&self) -> Result<ClickupTimeEntriesResponseDto, ClickupError>;
 }
@@ -31,83 +42,87 @@ fn try_into_dto<'de, T>(text: &'de str) -> Result<T>
 where
     T: serde::Deserialize<'de>,
 {
-    let dto = match serde_json::from_str::<T>(text) {
-        Ok(dto) => dto,
-        Err(e) => {
-            let any_dto = serde_json::from_str::<Value>(text).unwrap();
-            bail!(
-                "Fail to deserialize. Error: {:#?}, Response text: {:#?}",
-                e,
-                any_dto
-            );
-        }
-    };
-    Ok(dto)
+    match serde_json::from_str::<T>(text) {
+        Ok(dto) => Ok(dto),
+        Err(e) => bail!("Fail to deserialize. Error: {}, Response text: {}", e, text),
+    }
 }
 
-impl ParseClickupResponse for ClickupResponse {
-    fn try_to_serde_json_value(&self) -> Result<Value, ClickupError> {
+/// ミリ秒単位の数値の文字列を解析する。解析できない値はAPIの不正な応答とする
+fn parse_millis(field: &str, value: &str) -> Result<i64, AppError> {
+    value
+        .parse::<i64>()
+        .map_err(|e| AppError::Api(format!("Invalid {} '{}' in response: {}", field, value, e)))
+}
+
+/// ミリ秒単位のUNIX時間を日時に変換する
+/// 現地時刻への変換は、タスク情報に変換する際に集計に用いるタイムゾーンで行う
+fn parse_timestamp(field: &str, value: &str) -> Result<DateTime<FixedOffset>, AppError> {
+    let millis = parse_millis(field, value)?;
+    Utc.timestamp_millis_opt(millis)
+        .single()
+        .map(|datetime| datetime.fixed_offset())
+        .ok_or_else(|| AppError::Api(format!("Out of range {} '{}' in response", field, value)))
+}
+
+impl ClickupResponse {
+    /// 成功した場合はレスポンスのDTOに、失敗した場合はエラー応答に変換する
+    /// エラー応答がJSONでない場合は、レスポンスのテキストをそのままエラーの内容とする
+    fn parse<T: DeserializeOwned>(&self) -> Result<T, ClickupError> {
         if self.0.is_success() {
-            Ok(try_into_dto::<Value>(self.0.text()).unwrap())
+            try_into_dto::<T>(self.0.text()).map_err(ClickupError::unexpected)
         } else {
-            Err(try_into_dto::<ClickupError>(self.0.text()).unwrap())
+            Err(try_into_dto::<ClickupError>(self.0.text())
+                .unwrap_or_else(|_| ClickupError::unexpected(self.0.text())))
         }
     }
-    fn try_to_clickup_teams(&self) -> Result<ClickupTeamsResponseDto, ClickupError> {
-        if self.0.is_success() {
-            Ok(try_into_dto::<ClickupTeamsResponseDto>(self.0.text()).unwrap())
-        } else {
-            Err(try_into_dto::<ClickupError>(self.0.text()).unwrap())
+
+    /// エラー応答を、ステータスコードに応じたアプリケーションのエラーに変換する
+    pub(crate) fn app_error(&self, error: ClickupError) -> AppError {
+        let detail = format!("{} (ECODE: {})", error.err, error.ECODE);
+        match self.0.status() {
+            401 | 403 => AppError::Auth(format!(
+                "{}. Check APP_CLICKUP_ACCESS_TOKEN and APP_CLICKUP_TEAM_IDENT",
+                detail
+            )),
+            429 => AppError::RateLimit(detail),
+            status => AppError::Api(format!("{} [status: {}]", detail, status)),
         }
     }
+}
+
+impl ParseClickupResponse for ClickupResponse {
+    #[cfg(test)]
+    fn try_to_serde_json_value(&self) -> Result<Value, ClickupError> {
+        self.parse::<Value>()
+    }
+    #[cfg(test)]
+    fn try_to_clickup_teams(&self) -> Result<ClickupTeamsResponseDto, ClickupError> {
+        self.parse::<ClickupTeamsResponseDto>()
+    }
     fn try_to_clickup_spaces(&self) -> Result<ClickupSpacesResponseDto, ClickupError> {
-        if self.0.is_success() {
-            Ok(try_into_dto::<ClickupSpacesResponseDto>(self.0.text()).unwrap())
-        } else {
-            Err(try_into_dto::<ClickupError>(self.0.text()).unwrap())
-        }
+        self.parse::<ClickupSpacesResponseDto>()
     }
+    #[cfg(test)]
     fn try_to_clickup_folders(&self) -> Result<ClickupFoldersResponseDto, ClickupError> {
-        if self.0.is_success() {
-            Ok(try_into_dto::<ClickupFoldersResponseDto>(self.0.text()).unwrap())
-        } else {
-            Err(try_into_dto::<ClickupError>(self.0.text()).unwrap())
-        }
+        self.parse::<ClickupFoldersResponseDto>()
     }
     fn try_to_clickup_lists(&self) -> Result<ClickupListsResponseDto, ClickupError> {
-        if self.0.is_success() {
-            Ok(try_into_dto::<ClickupListsResponseDto>(self.0.text()).unwrap())
-        } else {
-            Err(try_into_dto::<ClickupError>(self.0.text()).unwrap())
-        }
+        self.parse::<ClickupListsResponseDto>()
     }
+    #[cfg(test)]
     fn try_to_clickup_list(&self) -> Result<ClickupListResponseDto, ClickupError> {
-        if self.0.is_success() {
-            Ok(try_into_dto::<ClickupListResponseDto>(self.0.text()).unwrap())
-        } else {
-            Err(try_into_dto::<ClickupError>(self.0.text()).unwrap())
-        }
+        self.parse::<ClickupListResponseDto>()
     }
     fn try_to_clickup_tasks(&self) -> Result<ClickupTasksResponseDto, ClickupError> {
-        if self.0.is_success() {
-            Ok(try_into_dto::<ClickupTasksResponseDto>(self.0.text()).unwrap())
-        } else {
-            Err(try_into_dto::<ClickupError>(self.0.text()).unwrap())
-        }
+        self.parse::<ClickupTasksResponseDto>()
     }
+    #[cfg(test)]
     fn try_to_clickup_task(&self) -> Result<ClickupTaskResponseDto, ClickupError> {
-        if self.0.is_success() {
-            Ok(try_into_dto::<ClickupTaskResponseDto>(self.0.text()).unwrap())
-        } else {
-            Err(try_into_dto::<ClickupError>(self.0.text()).unwrap())
-        }
+        self.parse::<ClickupTaskResponseDto>()
     }
     fn try_to_clickup_time_entries(&self) -> Result<ClickupTimeEntriesResponseDto, ClickupError> {
-        if self.0.is_success() {
-            Ok(try_into_dto::<ClickupTimeEntriesResponseDto>(self.0.text()).unwrap())
-        } else {
-            Err(try_into_dto::<ClickupError>(self.0.text()).unwrap())
-        }
+        self.parse::<ClickupTimeEntriesResponseDto>()
     }
 }
 
@@ -118,22 +133,36 @@ pub(crate) struct ClickupError {
     pub err: String,
 }
 
+impl ClickupError {
+    /// 想定外のレスポンス
+    fn unexpected(err: impl ToString) -> Self {
+        Self {
+            ECODE: "UNEXPECTED_RESPONSE".to_string(),
+            err: err.to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
 #[derive(Debug, Deserialize, Serialize)]
 pub(crate) struct ClickupTeamsResponseDto {
     pub teams: Vec<ClickupTeamResponseDto>,
 }
 
+#[cfg(test)]
 #[derive(Debug, Deserialize, Serialize)]
 pub(crate) struct ClickupTeamResponseDto {
     pub id: String,
     pub members: Vec<ClickupUserResponseDto>,
 }
 
+#[cfg(test)]
 #[derive(Debug, Deserialize, Serialize)]
 pub(crate) struct ClickupUserResponseDto {
     pub user: ClickupUserPropertyResponseDto,
 }
 
+#[cfg(test)]
 #[derive(Debug, Deserialize, Serialize)]
 pub(crate) struct ClickupUserPropertyResponseDto {
     pub id: i64,
@@ -146,17 +175,29 @@ pub(crate) struct ClickupSpacesResponseDto {
     pub spaces: Vec<ClickupSpaceResponseDto>,
 }
 
+impl ClickupSpacesResponseDto {
+    /// スペースIDからスペース名を引く表
+    pub(crate) fn names(self) -> HashMap<String, String> {
+        self.spaces
+            .into_iter()
+            .map(|space| (space.id, space.name))
+            .collect()
+    }
+}
+
 #[derive(Debug, Deserialize, Serialize)]
 pub(crate) struct ClickupSpaceResponseDto {
     pub id: String,
     pub name: String,
 }
 
+#[cfg(test)]
 #[derive(Debug, Deserialize, Serialize)]
 pub(crate) struct ClickupFoldersResponseDto {
     pub folders: Vec<ClickupFolderResponseDto>,
 }
 
+#[cfg(test)]
 #[derive(Debug, Deserialize, Serialize)]
 pub(crate) struct ClickupFolderResponseDto {
     pub id: String,
@@ -170,10 +211,25 @@ pub(crate) struct ClickupListsResponseDto {
     pub lists: Vec<ClickupListResponseDto>,
 }
 
+impl ClickupListsResponseDto {
+    /// フォルダに属さないリストが属する、非表示のフォルダのID
+    pub(crate) fn hidden_folder_ids(self) -> HashSet<String> {
+        self.lists
+            .into_iter()
+            .filter_map(|list| list.folder)
+            .filter(|folder| folder.hidden)
+            .map(|folder| folder.id)
+            .collect()
+    }
+}
+
 #[derive(Debug, Deserialize, Serialize)]
 pub(crate) struct ClickupListResponseDto {
     pub id: String,
     pub name: String,
+    /// リスト一覧の取得時のみ返される
+    #[serde(default)]
+    pub folder: Option<ClickupTaskFolderResponseDto>,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -181,9 +237,15 @@ pub(crate) struct ClickupTasksResponseDto {
     pub tasks: Vec<ClickupTaskResponseDto>,
 }
 
-impl From<ClickupTasksResponseDto> for ClickupTasks {
-    fn from(dto: ClickupTasksResponseDto) -> Self {
-        Self::new(dto.tasks.into_iter().map(|t| t.into()).collect())
+impl TryFrom<ClickupTasksResponseDto> for ClickupTasks {
+    type Error = AppError;
+
+    fn try_from(dto: ClickupTasksResponseDto) -> Result<Self, Self::Error> {
+        dto.tasks
+            .into_iter()
+            .map(ClickupTask::try_from)
+            .collect::<Result<_, _>>()
+            .map(Self::new)
     }
 }
 
@@ -195,23 +257,152 @@ pub(crate) struct ClickupTaskResponseDto {
     pub date_updated: String,
     pub time_spent: Option<i64>,
     pub list: ClickupListResponseDto,
+    pub folder: ClickupTaskFolderResponseDto,
+    pub space: ClickupTaskSpaceResponseDto,
+    #[serde(default)]
+    pub tags: Vec<ClickupTagResponseDto>,
+    #[serde(default)]
+    pub custom_fields: Vec<ClickupCustomFieldResponseDto>,
     pub url: String,
 }
 
-impl From<ClickupTaskResponseDto> for ClickupTask {
-    fn from(dto: ClickupTaskResponseDto) -> Self {
-        Self::new(
-            dto.id,
-            dto.name,
-            dto.url,
-            dto.status.status,
-            dto.list.name,
-            None,
-            dto.date_updated.as_str(),
-        )
+impl TryFrom<ClickupTaskResponseDto> for ClickupTask {
+    type Error = AppError;
+
+    fn try_from(dto: ClickupTaskResponseDto) -> Result<Self, Self::Error> {
+        dto.into_clickup_task(&HashMap::new())
+    }
+}
+
+impl ClickupTaskResponseDto {
+    /// タスクのレスポンスにはスペース名が含まれないので、スペースIDから引いた名前を補う
+    pub(crate) fn into_clickup_task(
+        self,
+        space_names: &HashMap<String, String>,
+    ) -> Result<ClickupTask, AppError> {
+        let space_name = space_names.get(&self.space.id).cloned().unwrap_or_default();
+        // フォルダに属さないリストは、非表示のフォルダに属するものとして返される
+        let (folder_id, folder_name) = match self.folder.hidden {
+            true => (String::new(), String::new()),
+            false => (self.folder.id, self.folder.name),
+        };
+        let updated_at = parse_timestamp("date_updated", &self.date_updated)?;
+        Ok(ClickupTask::new(
+            self.id,
+            self.name,
+            self.url,
+            self.status.status,
+            TaskLocation::new(
+                self.space.id,
+                space_name,
+                folder_id,
+                folder_name,
+                self.list.id,
+                self.list.name,
+            ),
+            self.tags.into_iter().map(|tag| tag.name).collect(),
+            self.custom_fields.into_iter().map(Into::into).collect(),
+            TaskDuration::new(),
+            updated_at,
+        ))
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub(crate) struct ClickupTaskFolderResponseDto {
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub hidden: bool,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub(crate) struct ClickupTaskSpaceResponseDto {
+    pub id: String,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub(crate) struct ClickupCustomFieldResponseDto {
+    pub id: String,
+    pub name: String,
+    pub r#type: String,
+    #[serde(default)]
+    pub type_config: ClickupCustomFieldTypeConfigResponseDto,
+    #[serde(default)]
+    pub value: Value,
+}
+
+impl From<ClickupCustomFieldResponseDto> for ClickupCustomField {
+    fn from(dto: ClickupCustomFieldResponseDto) -> Self {
+        let value = dto.resolved_value();
+        ClickupCustomField::new(dto.id, dto.name, value)
+    }
+}
+
+impl ClickupCustomFieldResponseDto {
+    /// ドロップダウンの値は選択肢の並び順かIDで、ラベルの値は選択肢のIDの配列で返されるので、
+    /// 選択肢の表示名に解決する。複数の値はカンマ区切りで連結する
+    fn resolved_value(&self) -> Option<String> {
+        let resolve = |value: &Value| -> Option<String> {
+            let text = match value {
+                Value::String(s) => s.clone(),
+                Value::Number(n) => n.to_string(),
+                Value::Bool(b) => b.to_string(),
+                _ => return None,
+            };
+            match self.type_config.options.iter().find(|o| o.matches(&text)) {
+                Some(option) => option.display_name(),
+                None => Some(text),
+            }
+        };
+        let value = match &self.value {
+            Value::Array(values) => values
+                .iter()
+                .filter_map(resolve)
+                .collect::<Vec<_>>()
+                .join(", "),
+            value => resolve(value)?,
+        };
+        Some(value).filter(|value| !value.is_empty())
+    }
+}
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub(crate) struct ClickupCustomFieldTypeConfigResponseDto {
+    #[serde(default)]
+    pub options: Vec<ClickupCustomFieldOptionResponseDto>,
+}
+
+/// ドロップダウンの選択肢は name を、ラベルの選択肢は label を持つ
+#[derive(Debug, Deserialize, Serialize)]
+pub(crate) struct ClickupCustomFieldOptionResponseDto {
+    pub id: String,
+    pub name: Option<String>,
+    pub label: Option<String>,
+    #[serde(default)]
+    pub orderindex: Value,
+}
+
+impl ClickupCustomFieldOptionResponseDto {
+    fn matches(&self, value: &str) -> bool {
+        let orderindex = match &self.orderindex {
+            Value::String(s) => Some(s.clone()),
+            Value::Number(n) => Some(n.to_string()),
+            _ => None,
+        };
+        self.id == value || orderindex.as_deref() == Some(value)
+    }
+
+    fn display_name(&self) -> Option<String> {
+        self.name.clone().or_else(|| self.label.clone())
     }
 }
 
+#[derive(Debug, Deserialize, Serialize)]
+pub(crate) struct ClickupTagResponseDto {
+    pub name: String,
+}
+
 #[derive(Debug, Deserialize, Serialize)]
 pub(crate) struct ClickupTaskStatusResponseDto {
     pub status: String,
@@ -224,9 +415,24 @@ pub(crate) struct ClickupTimeEntriesResponseDto {
     pub data: Vec<ClickupTimeEntryResponseDto>,
 }
 
-impl From<ClickupTimeEntriesResponseDto> for ClickupTimeEntries {
-    fn from(dto: ClickupTimeEntriesResponseDto) -> Self {
-        Self::new(dto.data.into_iter().map(|te| te.into()).collect())
+impl ClickupTimeEntriesResponseDto {
+    /// タイムエントリーのタスクが属するスペースのID
+    pub(crate) fn space_ids(&self) -> HashSet<String> {
+        self.data
+            .iter()
+            .map(|time_entry| time_entry.task_location.space_id.clone())
+            .collect()
+    }
+
+    pub(crate) fn into_clickup_time_entries(
+        self,
+        hidden_folder_ids: &HashSet<String>,
+    ) -> Result<ClickupTimeEntries, AppError> {
+        self.data
+            .into_iter()
+            .map(|time_entry| time_entry.into_clickup_time_entry(hidden_folder_ids))
+            .collect::<Result<_, _>>()
+            .map(ClickupTimeEntries::new)
     }
 }
 
@@ -239,21 +445,41 @@ pub(crate) struct ClickupTimeEntryResponseDto {
     pub task: ClickupTaskMiniResponseDto,
     pub task_url: String,
     pub task_location: ClickupTaskLocationResponseDto,
+    /// include_task_tags を指定した場合のみ返される
+    #[serde(default)]
+    pub task_tags: Vec<ClickupTagResponseDto>,
 }
 
-impl From<ClickupTimeEntryResponseDto> for ClickupTimeEntry {
-    fn from(dto: ClickupTimeEntryResponseDto) -> ClickupTimeEntry {
-        ClickupTimeEntry::new(
-            dto.id,
-            dto.duration.as_str(),
-            dto.start.as_str(),
-            dto.end.as_str(),
-            dto.task.id,
-            dto.task.name,
-            dto.task_url,
-            dto.task.status.status,
-            dto.task_location.list_name,
-        )
+impl TryFrom<ClickupTimeEntryResponseDto> for ClickupTimeEntry {
+    type Error = AppError;
+
+    fn try_from(dto: ClickupTimeEntryResponseDto) -> Result<Self, Self::Error> {
+        dto.into_clickup_time_entry(&HashSet::new())
+    }
+}
+
+impl ClickupTimeEntryResponseDto {
+    /// タイムエントリーのレスポンスにはフォルダが非表示か否かが含まれないので、
+    /// 非表示のフォルダのIDから判定する
+    pub(crate) fn into_clickup_time_entry(
+        self,
+        hidden_folder_ids: &HashSet<String>,
+    ) -> Result<ClickupTimeEntry, AppError> {
+        let duration = Duration::milliseconds(parse_millis("duration", &self.duration)?);
+        let start = parse_timestamp("start", &self.start)?;
+        let end = parse_timestamp("end", &self.end)?;
+        Ok(ClickupTimeEntry::new(
+            self.id,
+            duration,
+            start,
+            end,
+            self.task.id,
+            self.task.name,
+            self.task_url,
+            self.task.status.status,
+            self.task_location.into_task_location(hidden_folder_ids),
+            self.task_tags.into_iter().map(|tag| tag.name).collect(),
+        ))
     }
 }
 
@@ -273,3 +499,116 @@ pub(crate) struct ClickupTaskLocationResponseDto {
     pub space_id: String,
     pub space_name: String,
 }
+
+impl ClickupTaskLocationResponseDto {
+    fn into_task_location(self, hidden_folder_ids: &HashSet<String>) -> TaskLocation {
+        // フォルダに属さないリストは、非表示のフォルダに属するものとして返される
+        let (folder_id, folder_name) = match hidden_folder_ids.contains(&self.folder_id) {
+            true => (String::new(), String::new()),
+            false => (self.folder_id, self.folder_name),
+        };
+        TaskLocation::new(
+            self.space_id,
+            self.space_name,
+            folder_id,
+            folder_name,
+            self.list_id,
+            self.list_name,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn resolve(json: &str) -> Option<String> {
+        serde_json::from_str::<ClickupCustomFieldResponseDto>(json)
+            .unwrap()
+            .resolved_value()
+    }
+
+    #[test]
+    fn ドロップダウンとラベルの値を選択肢の表示名に解決する() {
+        let options = r#""type_config": {"options": [
+            {"id": "opt-a", "name": "A-001", "orderindex": 0},
+            {"id": "opt-b", "name": "B-001", "orderindex": 1}
+        ]}"#;
+        let dropdown = |value: &str| {
+            resolve(&format!(
+                r#"{{"id": "f1", "name": "charge", "type": "drop_down", {}, "value": {}}}"#,
+                options, value
+            ))
+        };
+
+        // 並び順でもIDでも解決できる
+        assert_eq!(dropdown("1"), Some("B-001".to_string()));
+        assert_eq!(dropdown(r#""opt-a""#), Some("A-001".to_string()));
+        assert_eq!(dropdown("null"), None);
+        assert_eq!(
+            resolve(
+                r#"{"id": "f2", "name": "labels", "type": "labels",
+                    "type_config": {"options": [{"id": "l1", "label": "X"}, {"id": "l2", "label": "Y"}]},
+                    "value": ["l2", "l1"]}"#
+            ),
+            Some("Y, X".to_string())
+        );
+        // 値を持たないフィールドは value を返さない
+        assert_eq!(
+            resolve(r#"{"id": "f3", "name": "code", "type": "short_text"}"#),
+            None
+        );
+    }
+
+    #[test]
+    fn 不正な日時や所要時間は応答の誤りとする() {
+        let time_entry = |duration: &str, start: &str| {
+            serde_json::from_str::<ClickupTimeEntryResponseDto>(&format!(
+                r#"{{"id": "1", "duration": "{}", "start": "{}", "end": "1680487200000",
+                    "task": {{"id": "a", "name": "設計", "status": {{"status": "open", "color": "", "type": "open"}}}},
+                    "task_url": "https://app.clickup.com/t/a",
+                    "task_location": {{"list_id": "l", "list_name": "dev", "folder_id": "f", "folder_name": "client",
+                        "space_id": "s", "space_name": "space"}}}}"#,
+                duration, start
+            ))
+            .unwrap()
+        };
+
+        let entry = ClickupTimeEntry::try_from(time_entry("5400000", "1680483600000")).unwrap();
+        assert_eq!(entry.duration, Duration::minutes(90));
+        assert!(matches!(
+            ClickupTimeEntry::try_from(time_entry("", "1680483600000")),
+            Err(AppError::Api(_))
+        ));
+        assert!(matches!(
+            ClickupTimeEntry::try_from(time_entry("5400000", "9223372036854775807")),
+            Err(AppError::Api(_))
+        ));
+    }
+
+    #[test]
+    fn 非表示のフォルダはフォルダ名に依らずフォルダ無しと判定する() {
+        let lists = serde_json::from_str::<ClickupListsResponseDto>(
+            r#"{"lists": [{"id": "l1", "name": "雑務", "folder": {"id": "h1", "name": "hidden", "hidden": true}}]}"#,
+        )
+        .unwrap();
+        let hidden_folder_ids = lists.hidden_folder_ids();
+        let location = |folder_id: &str, folder_name: &str| {
+            serde_json::from_str::<ClickupTaskLocationResponseDto>(&format!(
+                r#"{{"list_id": "l", "list_name": "dev", "folder_id": "{}", "folder_name": "{}",
+                    "space_id": "s", "space_name": "space"}}"#,
+                folder_id, folder_name
+            ))
+            .unwrap()
+            .into_task_location(&hidden_folder_ids)
+        };
+
+        let folderless = location("h1", "hidden");
+        assert_eq!(folderless.folder_id, "");
+        assert_eq!(folderless.folder_name, "");
+        // 利用者が "hidden" と名付けたフォルダはフォルダとして扱う
+        let named_hidden = location("f1", "hidden");
+        assert_eq!(named_hidden.folder_id, "f1");
+        assert_eq!(named_hidden.folder_name, "hidden");
+    }
+}
@@ -9,6 +9,7 @@ pub(crate) enum ApiParams {
 }
 
 pub(crate) trait CanConvertToQueryString {}
+#[cfg(test)]
 pub(crate) trait CanConvertToRequestBody {}
 
 pub(crate) trait ToQueryString {
@@ -19,11 +20,12 @@ pub(crate) trait ToQueryString {
         let s = serde_json::to_string(self).unwrap();
         let mut m: HashMap<String, Option<String>> = serde_json::from_str(&s).unwrap();
         // query string用パラメータなので、階層構造は存在しないものとする
-        let v: Vec<(String, Option<String>)> = m.drain().map(|(k, v)| (k, v)).collect();
+        let v: Vec<(String, Option<String>)> = m.drain().collect();
         ApiParams::QueryString(v)
     }
 }
 
+#[cfg(test)]
 pub(crate) trait ToRequestBody {
     fn to_params_of_request_body(&self) -> ApiParams
     where
@@ -36,8 +38,10 @@ pub(crate) trait ToRequestBody {
 }
 
 impl<T: CanConvertToQueryString> ToQueryString for T {}
+#[cfg(test)]
 impl<T: CanConvertToRequestBody> ToRequestBody for T {}
 
+#[cfg(test)]
 #[derive(Debug, serde_derive::Serialize)]
 pub(crate) struct PostEnvelope<T: serde::Serialize> {
     post: T,
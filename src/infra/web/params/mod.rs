@@ -3,7 +3,8 @@ mod auth_type;
 mod content_type;
 mod http_methods;
 
-pub(super) use api_params::{ApiParams, CanConvertToQueryString, ToQueryString};
+pub(crate) use api_params::ApiParams;
+pub(super) use api_params::{CanConvertToQueryString, ToQueryString};
 pub(super) use auth_type::AuthType;
 pub(super) use content_type::ContentType;
 pub(super) use http_methods::HttpMethods;
@@ -2,14 +2,33 @@ mod adapter;
 mod cui;
 mod domain;
 mod env;
+mod error;
 mod infra;
 mod logging;
 mod usecase;
 
-pub async fn init() {
-    env::set_dotenv("task-reporter");
-    logging::init_logging_with_bunyan();
+use std::process::ExitCode;
+
+/// 処理を実行し、終了コードを返す
+/// エラーは原因を1行にまとめて標準エラー出力に書き込み、分類毎の終了コードで終了する
+pub async fn init() -> ExitCode {
+    match run().await {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            tracing::debug!("{:?}", e);
+            eprintln!("Error: {:#}", e);
+            error::exit_code(&e)
+        }
+    }
+}
+
+async fn run() -> anyhow::Result<()> {
+    env::set_dotenv("task-reporter")?;
+    // ヘルプ・バージョンの表示や引数の誤りの報告はログの設定が無くても行えるよう、
+    // ログの初期化より先に引数を解析する
+    let opts = cui::parse_opts()?;
+    logging::init_logging()?;
     tracing::debug!("Task Reporter Process Start");
-    let app = cui::Cui::new().await;
-    app.process().await;
+    let app = cui::Cui::new(opts).await?;
+    app.process().await
 }
@@ -10,7 +10,8 @@ fn level(env_level_filter: bool) -> Level {
     };
 
     // 環境変数を使う場合
-    match get_env_var("LOG_LEVEL").unwrap() {
+    // 未設定の場合はTRACEとする
+    match get_env_var("LOG_LEVEL").unwrap_or_default() {
         s if s.to_uppercase() == "ERROR" => Level::ERROR,
         s if s.to_uppercase() == "WARN" => Level::WARN,
         s if s.to_uppercase() == "INFO" => Level::INFO,
@@ -1,38 +1,45 @@
+use crate::error::AppError;
 use tracing::Subscriber;
 use tracing_appender::rolling::RollingFileAppender;
 use tracing_bunyan_formatter::BunyanFormattingLayer;
 use tracing_subscriber::{
     filter::{Filtered, Targets},
+    fmt,
     registry::LookupSpan,
     Layer,
 };
 
 // type: filtered by targets
 
-type BunyanStdioLayerFilterdByTargets<S> =
-    Filtered<BunyanFormattingLayer<fn() -> std::io::Stdout>, Targets, S>;
+type TextStderrLayerFilterdByTargets<S> = Filtered<
+    fmt::Layer<S, fmt::format::DefaultFields, fmt::format::Format, fn() -> std::io::Stderr>,
+    Targets,
+    S,
+>;
 type BunyanRollingFileLayerFilterdByTargets<S> =
     Filtered<BunyanFormattingLayer<RollingFileAppender>, Targets, S>;
 
 // app log
 
-/// (app log) bunyan形式で標準出力に書き込むフォーマッタ
-pub(crate) fn bunyan_stdio_of_app<S>(name: &str) -> BunyanStdioLayerFilterdByTargets<S>
+/// (app log) 人が読める形式で標準エラー出力に書き込むフォーマッタ
+pub(crate) fn text_stderr_of_app<S>() -> TextStderrLayerFilterdByTargets<S>
 where
     S: Subscriber + for<'a> LookupSpan<'a>,
 {
-    let stdio_filter = super::filter::app_only(true);
+    let stderr_filter = super::filter::app_only(true);
 
-    super::layer::bunyan_stdio_format(name).with_filter(stdio_filter)
+    super::layer::text_stderr_format().with_filter(stderr_filter)
 }
 
 /// (app log) bunyan形式でファイルに書き込むフォーマッタ
-pub(crate) fn bunyan_file_of_app<S>(name: &str) -> BunyanRollingFileLayerFilterdByTargets<S>
+pub(crate) fn bunyan_file_of_app<S>(
+    name: &str,
+) -> Result<BunyanRollingFileLayerFilterdByTargets<S>, AppError>
 where
     S: Subscriber + for<'a> LookupSpan<'a>,
 {
     let file_filter = super::filter::app_only(false);
-    let make_writer = super::writer::rolling_file();
+    let make_writer = super::writer::rolling_file()?;
 
-    super::layer::bunyan_file_format(name, make_writer).with_filter(file_filter)
+    Ok(super::layer::bunyan_file_format(name, make_writer).with_filter(file_filter))
 }
@@ -1,11 +1,15 @@
+use std::io::IsTerminal;
 use tracing_bunyan_formatter::BunyanFormattingLayer;
-use tracing_subscriber::fmt::MakeWriter;
+use tracing_subscriber::fmt::{self, MakeWriter};
 
-/// bunyan形式で標準出力に書き込むフォーマッタ
-pub(crate) fn bunyan_stdio_format(
-    name: impl Into<String>,
-) -> BunyanFormattingLayer<fn() -> std::io::Stdout> {
-    BunyanFormattingLayer::new(name.into(), std::io::stdout)
+/// 人が読める形式で標準エラー出力に書き込むフォーマッタ
+/// 標準出力は集計結果の出力に用いるため、ログは書き込まない
+pub(crate) fn text_stderr_format<S>(
+) -> fmt::Layer<S, fmt::format::DefaultFields, fmt::format::Format, fn() -> std::io::Stderr> {
+    // リダイレクトされた場合はエスケープシーケンスによる色付けをしない
+    fmt::layer()
+        .with_ansi(std::io::stderr().is_terminal())
+        .with_writer(std::io::stderr)
 }
 
 /// bunyan形式でファイルに書き込むフォーマッタ
@@ -3,17 +3,20 @@ mod filtered_layer;
 mod layer;
 mod writer;
 
-use crate::env::get_env_var;
+use crate::env::require_env_var;
+use crate::error::AppError;
 use tracing_bunyan_formatter::JsonStorageLayer;
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
-pub(super) fn init_logging_with_bunyan() {
-    let service_name = get_env_var("SERVICE_NAME").unwrap();
+/// 標準エラー出力には人が読める形式で、ファイルにはbunyan形式でログを書き込む
+pub(super) fn init_logging() -> Result<(), AppError> {
+    let service_name = require_env_var("SERVICE_NAME")?;
     // ログ設定にフィルタ・フォーマットを登録し適用する
     tracing_subscriber::registry()
+        .with(filtered_layer::text_stderr_of_app())
         // --- bunyan formatting layer ---
         .with(JsonStorageLayer)
-        .with(filtered_layer::bunyan_stdio_of_app(&service_name))
-        .with(filtered_layer::bunyan_file_of_app(&service_name))
+        .with(filtered_layer::bunyan_file_of_app(&service_name)?)
         .init();
+    Ok(())
 }
@@ -1,33 +1,32 @@
-use crate::env::get_env_var;
+use crate::env::require_env_var;
+use crate::error::AppError;
 
 use chrono::Utc;
 use std::path::PathBuf;
 use tracing_appender::rolling::RollingFileAppender;
 
-// 標準出力用のWriter
-// std::io::stdout を使えば良い
+// 標準エラー出力用のWriter
+// std::io::stderr を使えば良い
 
 /// ファイル出力用のWriter
-pub(super) fn rolling_file() -> RollingFileAppender {
+pub(super) fn rolling_file() -> Result<RollingFileAppender, AppError> {
     // ログディレクトリパスとファイル名を生成する
-    let dirpath = log_dirpath();
-    let filename = get_env_var("LOG_FILENAME").unwrap();
+    let dirpath = log_dirpath()?;
+    let filename = require_env_var("LOG_FILENAME")?;
 
     // ログファイルのWriterを生成する
-    tracing_appender::rolling::hourly(dirpath, filename)
+    Ok(tracing_appender::rolling::hourly(dirpath, filename))
 }
 
-fn log_dirpath() -> PathBuf {
+fn log_dirpath() -> Result<PathBuf, AppError> {
     // 実行時のディレクトリを取得する
-    let current_dir = match std::env::current_dir() {
-        Ok(current_dir) => current_dir,
-        Err(e) => panic!("Fail to get current directory\n{:#?}", e),
-    };
+    let current_dir = std::env::current_dir()
+        .map_err(|e| AppError::Config(format!("Fail to get current directory: {}", e)))?;
 
     // 環境変数からログディレクトリへのPATH要素を取得する
-    let log_dir = get_env_var("LOG_DIR").unwrap();
+    let log_dir = require_env_var("LOG_DIR")?;
     let today = Utc::now().format("%Y-%m-%d").to_string();
 
     // ディレクトリパスを作成する
-    current_dir.join(log_dir).join(today)
+    Ok(current_dir.join(log_dir).join(today))
 }
@@ -1,4 +1,6 @@
+use std::process::ExitCode;
+
 #[tokio::main]
-async fn main() {
-    task_reporter::init().await;
+async fn main() -> ExitCode {
+    task_reporter::init().await
 }
@@ -1,15 +1,19 @@
+use super::AggregateQuery;
 use crate::domain::{
     model::{
         aggregated_by::{
-            ChargeAndDailyRecords, ChargeAndTotalPeriodRecords, TaskAndDailyRecords,
-            TaskAndTotalPeriodRecords,
+            AllAggregatedRecords, ChargeAndDailyRecords, ChargeAndTotalPeriodRecords,
+            LocationLevel, LocationRecords, PivotTable, TaskAndDailyRecords,
+            TaskAndTotalPeriodRecords, WorkingDayCheckRecords,
         },
-        DateRange, TaskRecords,
+        clickup::{ClickupTasks, ClickupTimeEntries},
+        AsVec, ChargeRules, DateRange, RoundingPolicy, RoundingScope, TaskRecords,
     },
     repository::{ClickupTaskRepository, ClickupTimeEntryRepository, Repositories},
 };
 use anyhow::Result;
 use derive_new::new;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 
 #[derive(new, Debug, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
 pub(crate) struct AggregateDurationUseCase<R: Repositories> {
@@ -19,68 +23,173 @@ pub(crate) struct AggregateDurationUseCase<R: Repositories> {
 
 impl<R: Repositories> AggregateDurationUseCase<R> {
     #[tracing::instrument(level = "debug", skip_all)]
-    pub(crate) async fn by_task_and_total_period<T: Into<String>>(
+    pub(crate) async fn by_task_and_total_period(
         &self,
-        start_date: Option<T>,
-        end_date: Option<T>,
-        all: bool,
+        query: &AggregateQuery,
     ) -> Result<TaskAndTotalPeriodRecords> {
-        let date_range = DateRange::new(start_date, end_date);
-        let records = self.fetch_task_records(date_range.clone(), all).await?;
+        let date_range = query.date_range()?;
+        let records = self.fetch_task_records(date_range.clone(), query).await?;
+        let records = round_each_entry(records, query.rounding.as_ref());
 
-        Ok(TaskAndTotalPeriodRecords::new(date_range, records))
+        let aggregated = TaskAndTotalPeriodRecords::new(date_range, records);
+        Ok(match &query.rounding {
+            Some(policy) => aggregated.round(policy),
+            None => aggregated,
+        })
     }
 
     #[tracing::instrument(level = "debug", skip_all)]
-    pub(crate) async fn by_task_and_daily<T: Into<String>>(
+    pub(crate) async fn by_task_and_daily(
         &self,
-        start_date: Option<T>,
-        end_date: Option<T>,
-        all: bool,
+        query: &AggregateQuery,
     ) -> Result<TaskAndDailyRecords> {
-        let date_range = DateRange::new(start_date, end_date);
-        let records = self.fetch_task_records(date_range.clone(), all).await?;
+        let date_range = query.date_range()?;
+        let records = self.fetch_task_records(date_range.clone(), query).await?;
+        let records = round_each_entry(records, query.rounding.as_ref());
 
-        Ok(TaskAndDailyRecords::new(date_range, records))
+        let aggregated = TaskAndDailyRecords::by_bucket(date_range, records, query.bucketing)
+            .fill_working_days(&query.calendar);
+        Ok(match &query.rounding {
+            Some(policy) => aggregated.round(policy),
+            None => aggregated,
+        })
     }
 
     #[tracing::instrument(level = "debug", skip_all)]
-    pub(crate) async fn by_charge_and_total_period<T: Into<String>>(
+    pub(crate) async fn by_charge_and_total_period(
         &self,
-        start_date: Option<T>,
-        end_date: Option<T>,
-        all: bool,
+        query: &AggregateQuery,
     ) -> Result<ChargeAndTotalPeriodRecords> {
-        let date_range = DateRange::new(start_date, end_date);
-        let records = self.fetch_task_records(date_range.clone(), all).await?;
+        let date_range = query.date_range()?;
+        let records = self.fetch_task_records(date_range.clone(), query).await?;
+        let records = round_each_entry(records, query.rounding.as_ref());
 
-        Ok(ChargeAndTotalPeriodRecords::new(date_range, records))
+        let aggregated = ChargeAndTotalPeriodRecords::new(date_range, records);
+        Ok(match &query.rounding {
+            Some(policy) => aggregated.round(policy),
+            None => aggregated,
+        })
     }
 
     #[tracing::instrument(level = "debug", skip_all)]
-    pub(crate) async fn by_charge_and_daily<T: Into<String>>(
+    pub(crate) async fn by_charge_and_daily(
         &self,
-        start_date: Option<T>,
-        end_date: Option<T>,
-        all: bool,
+        query: &AggregateQuery,
     ) -> Result<ChargeAndDailyRecords> {
-        let date_range = DateRange::new(start_date, end_date);
-        let records = self.fetch_task_records(date_range.clone(), all).await?;
+        let date_range = query.date_range()?;
+        let records = self.fetch_task_records(date_range.clone(), query).await?;
+        let records = round_each_entry(records, query.rounding.as_ref());
 
-        Ok(ChargeAndDailyRecords::new(date_range, records))
+        let aggregated = ChargeAndDailyRecords::by_bucket(date_range, records, query.bucketing)
+            .fill_working_days(&query.calendar);
+        Ok(match &query.rounding {
+            Some(policy) => aggregated.round(policy),
+            None => aggregated,
+        })
     }
 
     #[tracing::instrument(level = "debug", skip_all)]
-    async fn fetch_task_records(&self, date_range: DateRange, all: bool) -> Result<TaskRecords> {
+    pub(crate) async fn by_location(
+        &self,
+        query: &AggregateQuery,
+        level: LocationLevel,
+    ) -> Result<LocationRecords> {
+        let date_range = query.date_range()?;
+        let records = self.fetch_task_records(date_range.clone(), query).await?;
+        let records = round_each_entry(records, query.rounding.as_ref());
+
+        let aggregated = LocationRecords::new(date_range, records, level);
+        Ok(match &query.rounding {
+            Some(policy) => aggregated.round(policy),
+            None => aggregated,
+        })
+    }
+
+    #[tracing::instrument(level = "debug", skip_all)]
+    pub(crate) async fn by_all(&self, query: &AggregateQuery) -> Result<AllAggregatedRecords> {
+        let date_range = query.date_range()?;
+        let records = self.fetch_task_records(date_range.clone(), query).await?;
+        let records = round_each_entry(records, query.rounding.as_ref());
+
+        let aggregated =
+            AllAggregatedRecords::new(date_range, records).fill_working_days(&query.calendar);
+        Ok(match &query.rounding {
+            Some(policy) => aggregated.round(policy),
+            None => aggregated,
+        })
+    }
+
+    #[tracing::instrument(level = "debug", skip_all)]
+    pub(crate) async fn by_charge_and_date_pivot(
+        &self,
+        query: &AggregateQuery,
+    ) -> Result<PivotTable> {
+        let date_range = query.date_range()?;
+        let records = self.fetch_task_records(date_range.clone(), query).await?;
+
+        Ok(PivotTable::by_charge(date_range, records))
+    }
+
+    #[tracing::instrument(level = "debug", skip_all)]
+    pub(crate) async fn by_task_and_date_pivot(
+        &self,
+        query: &AggregateQuery,
+    ) -> Result<PivotTable> {
+        let date_range = query.date_range()?;
+        let records = self.fetch_task_records(date_range.clone(), query).await?;
+
+        Ok(PivotTable::by_task(date_range, records))
+    }
+
+    /// 記録の無い稼働日と、所要時間の合計が最低時間に満たない稼働日を列挙する
+    #[tracing::instrument(level = "debug", skip_all)]
+    pub(crate) async fn check_working_days(
+        &self,
+        query: &AggregateQuery,
+    ) -> Result<WorkingDayCheckRecords> {
+        let date_range = query.date_range()?;
+        // 所要時間を持たないタスク情報は判定に影響しないので、タイムエントリーのみを用いる
+        let query = AggregateQuery {
+            all: false,
+            charge_rules: ChargeRules::default(),
+            ..query.clone()
+        };
+        let records = self.fetch_task_records(date_range.clone(), &query).await?;
+
+        Ok(WorkingDayCheckRecords::new(
+            date_range,
+            records,
+            &query.calendar,
+            &query.min_duration,
+        ))
+    }
+
+    #[tracing::instrument(level = "debug", skip_all)]
+    async fn fetch_task_records(
+        &self,
+        date_range: DateRange,
+        query: &AggregateQuery,
+    ) -> Result<TaskRecords> {
+        let charge_rules = &query.charge_rules;
         let tasks = self.task_repo.find_tasks_by_date_range(&date_range).await?;
         let time_entries = self
             .time_entry_repo
             .find_time_entries_by_date_range(&date_range)
             .await?;
 
-        let records_from_tasks: TaskRecords = tasks.into();
-        let records_from_time_entries: TaskRecords = time_entries.into();
-        let records = if all {
+        let custom_charges = match charge_rules.custom_field() {
+            Some(field) => find_custom_charges(&self.task_repo, field, &tasks, &time_entries).await,
+            None => HashMap::new(),
+        };
+
+        let records_from_tasks = tasks.into_task_records(&date_range);
+        let records_from_time_entries = if query.split_entries {
+            // 1日の区切りを跨ぐタイムエントリーを分割し、対象期間外の部分を切り落とす
+            time_entries.into_split_task_records(&date_range)
+        } else {
+            time_entries.into_task_records(&date_range)
+        };
+        let records = if query.all {
             // タスクとタイムエントリーを結合して返す
             records_from_tasks.concat(&records_from_time_entries)
         } else {
@@ -88,8 +197,97 @@ impl<R: Repositories> AggregateDurationUseCase<R> {
             records_from_time_entries
         };
 
+        let records = map_charges(records, charge_rules, &custom_charges);
+
         tracing::debug!("{:#?}", records);
 
         Ok(records)
     }
 }
+
+/// リスト名の代わりに、規則に従ってチャージコードを振り分ける
+/// どの規則にも合致しなかったタスクは警告する
+pub(super) fn map_charges(
+    records: TaskRecords,
+    charge_rules: &ChargeRules,
+    custom_charges: &HashMap<String, String>,
+) -> TaskRecords {
+    let (records, unmapped) = records.map_charges(charge_rules, custom_charges);
+    if !unmapped.is_empty() {
+        tracing::warn!(
+            "{} task(s) matched no charge rule and are aggregated as '{}':\n{}",
+            unmapped.len(),
+            charge_rules.fallback(),
+            unmapped.join("\n")
+        );
+    }
+    records
+}
+
+/// カスタムフィールドにチャージコードを持つタスクの、タスクIDとチャージコードの対応
+/// タイムエントリーにはカスタムフィールドが含まれないので、取得済みでないタスクは
+/// それらが属するリストのタスクとしてまとめて取得する
+/// 取得できなかったタスクは、規則あるいはリスト名でチャージコードを決める
+#[tracing::instrument(level = "debug", skip(task_repo, tasks, time_entries))]
+pub(super) async fn find_custom_charges<T: ClickupTaskRepository>(
+    task_repo: &T,
+    field: &str,
+    tasks: &ClickupTasks,
+    time_entries: &ClickupTimeEntries,
+) -> HashMap<String, String> {
+    let mut found = tasks
+        .as_vec()
+        .iter()
+        .map(|task| (task.task_id.clone(), task.clone()))
+        .collect::<HashMap<_, _>>();
+    let missing = time_entries
+        .as_vec()
+        .iter()
+        .filter(|entry| !found.contains_key(&entry.task_id))
+        .map(|entry| (entry.task_id.clone(), entry.location.list_id.clone()))
+        .collect::<BTreeMap<_, _>>();
+    if !missing.is_empty() {
+        let list_ids = missing.values().cloned().collect::<BTreeSet<_>>();
+        match task_repo.find_tasks_by_list_ids(&list_ids).await {
+            Ok(tasks_in_lists) => found.extend(
+                tasks_in_lists
+                    .into_inner()
+                    .into_iter()
+                    .filter(|task| missing.contains_key(&task.task_id))
+                    .map(|task| (task.task_id.clone(), task)),
+            ),
+            Err(e) => {
+                tracing::warn!("Fail to fetch tasks for custom field '{}': {:#}", field, e)
+            }
+        }
+        let not_found = missing
+            .keys()
+            .filter(|task_id| !found.contains_key(*task_id))
+            .cloned()
+            .collect::<Vec<_>>();
+        if !not_found.is_empty() {
+            tracing::warn!(
+                "{} task(s) could not be fetched and are mapped without custom field '{}': {}",
+                not_found.len(),
+                field,
+                not_found.join(", ")
+            );
+        }
+    }
+
+    found
+        .into_iter()
+        .filter_map(|(task_id, task)| {
+            task.custom_field_value(field)
+                .map(|value| (task_id, value.to_string()))
+        })
+        .collect()
+}
+
+/// タイムエントリー毎に丸める方針の場合、集計前に所要時間を丸める
+fn round_each_entry(records: TaskRecords, rounding: Option<&RoundingPolicy>) -> TaskRecords {
+    match rounding {
+        Some(policy) if policy.scope() == RoundingScope::Entry => records.round_each(policy),
+        _ => records,
+    }
+}
@@ -0,0 +1,39 @@
+use crate::domain::model::{
+    BillingPeriod, Bucketing, ChargeRules, DateRange, DayBoundary, RoundingPolicy, TaskDuration,
+    WorkingCalendar,
+};
+use anyhow::Result;
+
+/// 集計の条件
+/// 各ユースケースは、この中から集計方法に必要なものだけを参照する
+#[derive(Debug, Clone)]
+pub(crate) struct AggregateQuery {
+    pub(crate) start_date: Option<String>,
+    pub(crate) end_date: Option<String>,
+    pub(crate) day_boundary: DayBoundary,
+    /// 指定された場合は、開始日・終了日より優先する
+    pub(crate) period: Option<BillingPeriod>,
+    /// タイムエントリーだけでなく、タスク情報も集計対象とするか否か
+    pub(crate) all: bool,
+    /// 1日の区切りを跨ぐタイムエントリーを分割するか否か
+    pub(crate) split_entries: bool,
+    pub(crate) charge_rules: ChargeRules,
+    pub(crate) rounding: Option<RoundingPolicy>,
+    pub(crate) bucketing: Bucketing,
+    pub(crate) calendar: WorkingCalendar,
+    /// 稼働日の確認で、1日の所要時間の合計が満たすべき最低時間
+    pub(crate) min_duration: TaskDuration,
+}
+
+impl AggregateQuery {
+    /// 対象期間を解決する
+    pub(crate) fn date_range(&self) -> Result<DateRange> {
+        DateRange::resolve(
+            self.start_date.clone(),
+            self.end_date.clone(),
+            self.period.as_ref(),
+            self.day_boundary,
+            self.bucketing.week_start(),
+        )
+    }
+}
@@ -0,0 +1,67 @@
+use super::{
+    aggregate_duration::{find_custom_charges, map_charges},
+    AggregateQuery,
+};
+use crate::domain::{
+    model::{
+        clickup::{ChargedTimeEntry, ClickupTasks},
+        AsVec,
+    },
+    repository::{ClickupTimeEntryRepository, Repositories},
+};
+use anyhow::Result;
+use derive_new::new;
+use std::collections::HashMap;
+
+#[derive(new, Debug, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub(crate) struct ListTimeEntriesUseCase<R: Repositories> {
+    task_repo: R::ClickupTaskRepo,
+    time_entry_repo: R::ClickupTimeEntryRepo,
+}
+
+impl<R: Repositories> ListTimeEntriesUseCase<R> {
+    /// 対象期間内のタイムエントリーを集計せずに、開始日時順で返す
+    /// チャージコードは集計する場合と同じく、カスタムフィールドあるいは規則に従って振り分ける
+    #[tracing::instrument(level = "debug", skip_all)]
+    pub(crate) async fn by_date_range(
+        &self,
+        query: &AggregateQuery,
+    ) -> Result<Vec<ChargedTimeEntry>> {
+        let date_range = query.date_range()?;
+        let mut time_entries = self
+            .time_entry_repo
+            .find_time_entries_by_date_range(&date_range)
+            .await?;
+        time_entries
+            .as_mut_vec()
+            .sort_by_key(|entry| (entry.start, entry.end));
+
+        let charge_rules = &query.charge_rules;
+        let custom_charges = match charge_rules.custom_field() {
+            Some(field) => {
+                let tasks = ClickupTasks::new(Vec::new());
+                find_custom_charges(&self.task_repo, field, &tasks, &time_entries).await
+            }
+            None => HashMap::new(),
+        };
+        // タイムエントリー毎に、同じ順序でタスク情報に変換してから振り分ける
+        let records = map_charges(
+            time_entries.clone().into_task_records(&date_range),
+            charge_rules,
+            &custom_charges,
+        );
+        let charged = time_entries
+            .into_inner()
+            .into_iter()
+            .zip(records.into_inner())
+            .map(|(entry, record)| ChargedTimeEntry {
+                entry,
+                charge_name: record.charge_name,
+            })
+            .collect::<Vec<_>>();
+
+        tracing::debug!("{:#?}", charged);
+
+        Ok(charged)
+    }
+}
@@ -1,17 +1,23 @@
 mod aggregate_duration;
+mod aggregate_query;
+mod list_time_entries;
 
 use crate::{domain::repository::Repositories, infra::repository_impl::RepositoryImpls};
 pub(crate) use aggregate_duration::AggregateDurationUseCase;
+pub(crate) use aggregate_query::AggregateQuery;
+pub(crate) use list_time_entries::ListTimeEntriesUseCase;
 
 pub(crate) trait Usecases {
     type Repositories: Repositories;
 
     fn aggregate_duration_use_case(&self) -> &AggregateDurationUseCase<Self::Repositories>;
+    fn list_time_entries_use_case(&self) -> &ListTimeEntriesUseCase<Self::Repositories>;
 }
 
 #[derive(Debug, Clone)]
 pub(crate) struct UsecaseImpls {
     aggregate_duration_use_case: AggregateDurationUseCase<RepositoryImpls>,
+    list_time_entries_use_case: ListTimeEntriesUseCase<RepositoryImpls>,
 }
 
 impl Usecases for UsecaseImpls {
@@ -20,6 +26,10 @@ impl Usecases for UsecaseImpls {
     fn aggregate_duration_use_case(&self) -> &AggregateDurationUseCase<Self::Repositories> {
         &self.aggregate_duration_use_case
     }
+
+    fn list_time_entries_use_case(&self) -> &ListTimeEntriesUseCase<Self::Repositories> {
+        &self.list_time_entries_use_case
+    }
 }
 
 impl UsecaseImpls {
@@ -28,9 +38,14 @@ impl UsecaseImpls {
             repositories.clickup_task_repository().to_owned(),
             repositories.clickup_time_entry_repository().to_owned(),
         );
+        let list_time_entries_use_case = ListTimeEntriesUseCase::new(
+            repositories.clickup_task_repository().to_owned(),
+            repositories.clickup_time_entry_repository().to_owned(),
+        );
 
         Self {
             aggregate_duration_use_case,
+            list_time_entries_use_case,
         }
     }
 }